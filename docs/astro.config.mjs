@@ -20,7 +20,15 @@ export default defineConfig({
 						{ label: 'axon parse', slug: 'commands/parse' },
 						{ label: 'axon refactor', slug: 'commands/refactor' },
 						{ label: 'axon stats', slug: 'commands/stats' },
-						{ label: 'axon d', slug: 'commands/daily' },
+						{ label: 'axon size', slug: 'commands/size' },
+						{ label: 'axon history', slug: 'commands/history' },
+						{ label: 'axon links', slug: 'commands/links' },
+						{ label: 'axon run', slug: 'commands/run' },
+						{ label: 'axon runs', slug: 'commands/runs' },
+						{ label: 'axon render', slug: 'commands/render' },
+						{ label: 'axon search', slug: 'commands/search' },
+						{ label: 'axon config', slug: 'commands/config' },
+						{ label: 'axon d / w / m', slug: 'commands/daily' },
 						{ label: 'axon (TUI)', slug: 'commands/tui' },
 					],
 				},
@@ -0,0 +1,532 @@
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Key, Table};
+
+use crate::config_check::{self, ConfigFile, Issue, Severity};
+use crate::error::CliError;
+use crate::global_config::{GlobalConfig, Origin};
+use crate::notes::{self, PeriodicKind};
+use crate::runner;
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Report errors and likely mistakes in the config files
+    Check(CheckArgs),
+    /// Print every effective setting and where it comes from
+    Show(ShowArgs),
+    /// Print the effective value of one setting
+    Get(GetArgs),
+    /// Change a setting in its config file, keeping comments and layout
+    Set(SetArgs),
+    /// Write a commented starter config file
+    Init(InitArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct GetArgs {
+    /// Dotted key, e.g. notes_dir or 'schemas."daily.*.md"'
+    pub key: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SetArgs {
+    /// Dotted key, e.g. prompts_dir or periodic.yearly.format
+    pub key: String,
+    pub value: String,
+    /// More settings to change at the same time, as key value pairs
+    #[arg(value_name = "KEY VALUE")]
+    pub more: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// Write axon.toml in the notes directory instead of the global config
+    #[arg(long)]
+    pub notes: bool,
+    /// Overwrite an existing file
+    #[arg(long)]
+    pub force: bool,
+}
+
+pub fn run(args: ConfigArgs) -> Result<(), CliError> {
+    match args.command {
+        ConfigCommand::Check(args) => check(args),
+        ConfigCommand::Show(args) => show(args),
+        ConfigCommand::Get(args) => get(args),
+        ConfigCommand::Set(args) => set(args),
+        ConfigCommand::Init(args) => init(args),
+    }
+}
+
+fn check(args: CheckArgs) -> Result<(), CliError> {
+    let global = ConfigFile::Global.path();
+    let notes = ConfigFile::Notes.path();
+    let issues = config_check::check_all();
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&issues)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+    } else {
+        for path in [&global, &notes] {
+            let status = if !path.exists() {
+                "not found"
+            } else if issues.iter().any(|issue| &issue.file == path) {
+                "problems found"
+            } else {
+                "OK"
+            };
+            println!("{}: {status}", path.display());
+        }
+        print_issues(&issues);
+    }
+
+    if issues.iter().any(|issue| issue.severity == Severity::Error) {
+        Err(CliError::new(1, String::new()))
+    } else {
+        Ok(())
+    }
+}
+
+fn print_issues(issues: &[Issue]) {
+    if issues.is_empty() {
+        println!("\nConfig: OK");
+        return;
+    }
+    println!();
+    for issue in issues {
+        println!("  {issue}");
+    }
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    println!(
+        "\nConfig: {errors} errors, {} warnings",
+        issues.len() - errors
+    );
+}
+
+/// One effective setting.
+#[derive(Debug, Serialize)]
+struct Setting {
+    key: String,
+    value: String,
+    origin: String,
+}
+
+impl Setting {
+    fn new(path: &[&str], value: impl Into<String>, origin: &Origin) -> Self {
+        Self {
+            key: dotted(path),
+            value: value.into(),
+            origin: origin.to_string(),
+        }
+    }
+}
+
+/// `path` as a TOML dotted key, quoting parts where needed.
+fn dotted(path: &[&str]) -> String {
+    path.iter()
+        .map(|part| Key::new(*part).display_repr().to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Every effective setting, resolved the way the other commands resolve them.
+fn settings() -> Vec<Setting> {
+    let global = crate::global_config::load();
+    let (notes_dir, notes_origin) = global.notes_dir_with_origin();
+    let (prompts_dir, prompts_origin) = global.prompts_dir_with_origin();
+    let mut settings = vec![
+        Setting::new(&["notes_dir"], notes_dir.display().to_string(), &notes_origin),
+        Setting::new(&["prompts_dir"], prompts_dir.display().to_string(), &prompts_origin),
+    ];
+    if let Ok(opener) = crate::opener::resolve(&global) {
+        settings.push(Setting::new(&["opener"], opener.template(), &opener.origin));
+    }
+    let global_file = Origin::File(crate::global_config::config_path());
+    let picker_origin = if global.picker.is_some() { &global_file } else { &Origin::BuiltIn };
+    let picker = crate::picker::Backend::from_config(&global);
+    settings.push(Setting::new(&["picker"], picker.name(), picker_origin));
+    settings.extend(runner_settings(&global));
+    for (repo, dir) in &global.repos {
+        settings.push(Setting::new(&["repos", repo], dir.clone(), &global_file));
+    }
+    let tokenizer_origin = if global.size.tokenizer.is_some() { &global_file } else { &Origin::BuiltIn };
+    let tokenizer = crate::size::Tokenizer::from_config(&global);
+    settings.push(Setting::new(&["size", "tokenizer"], tokenizer.name(), tokenizer_origin));
+    for (category, max) in &global.size.max_tokens {
+        settings.push(Setting::new(&["size", "max_tokens", category], max.to_string(), &global_file));
+    }
+
+    let file = Origin::File(notes_dir.join("axon.toml"));
+    let cfg = crate::config::load_config(&notes_dir);
+    for (pattern, schema) in &cfg.schemas {
+        settings.push(Setting::new(&["schemas", pattern], schema.clone(), &file));
+    }
+
+    for kind in notes::periodic_kinds(&notes_dir) {
+        let origin = if cfg.periodic.contains_key(&kind.name) {
+            file.clone()
+        } else {
+            Origin::BuiltIn
+        };
+        settings.extend(periodic_settings(&kind, &origin));
+    }
+
+    for (name, rule) in &cfg.carry_over {
+        if let Some(from) = &rule.from {
+            settings.push(Setting::new(&["carry_over", name, "from"], from.clone(), &file));
+        }
+        if let Some(heading) = &rule.heading {
+            settings.push(Setting::new(&["carry_over", name, "heading"], heading.clone(), &file));
+        }
+        settings.push(Setting::new(&["carry_over", name, "section"], rule.section.clone(), &file));
+    }
+    settings
+}
+
+fn runner_settings(global: &GlobalConfig) -> Vec<Setting> {
+    let file = Origin::File(crate::global_config::config_path());
+    let default_origin = if global.runner.is_some() { &file } else { &Origin::BuiltIn };
+    let mut settings = vec![Setting::new(&["runner"], runner::default_name(global), default_origin)];
+    if !global.runners.contains_key(runner::DEFAULT_RUNNER) {
+        let name = runner::DEFAULT_RUNNER;
+        settings.push(Setting::new(&["runners", name, "command"], name, &Origin::BuiltIn));
+    }
+    for (name, cfg) in &global.runners {
+        let fields = [("command", &cfg.command), ("args", &cfg.args), ("cwd", &cfg.cwd)];
+        for (field, value) in fields {
+            if let Some(value) = value {
+                settings.push(Setting::new(&["runners", name, field], value.clone(), &file));
+            }
+        }
+        for (var, value) in &cfg.env {
+            settings.push(Setting::new(&["runners", name, "env", var], value.clone(), &file));
+        }
+    }
+    settings
+}
+
+fn periodic_settings(kind: &PeriodicKind, origin: &Origin) -> Vec<Setting> {
+    let name = kind.name.as_str();
+    let mut settings = vec![
+        Setting::new(&["periodic", name, "format"], kind.format.clone(), origin),
+        Setting::new(&["periodic", name, "period"], kind.period.as_str(), origin),
+    ];
+    if kind.period == notes::Period::Week {
+        let week_start = kind.week_start.to_string().to_lowercase();
+        settings.push(Setting::new(&["periodic", name, "week_start"], week_start, origin));
+    }
+    if let Some(key) = kind.key {
+        settings.push(Setting::new(&["periodic", name, "key"], key.to_string(), origin));
+    }
+    settings
+}
+
+fn show(args: ShowArgs) -> Result<(), CliError> {
+    let settings = settings();
+    if args.json {
+        let json = serde_json::to_string_pretty(&settings)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let width = settings
+        .iter()
+        .map(|setting| setting.key.len() + setting.value.len())
+        .max()
+        .unwrap_or(0);
+    for setting in &settings {
+        let value = toml_value(&setting.key, &setting.value).to_string();
+        let line = format!("{} = {value}", setting.key);
+        println!("{line:<width$}  # {}", setting.origin, width = width + 5);
+    }
+    Ok(())
+}
+
+/// `value` as written in the config file: unquoted for integer settings.
+fn toml_value(key: &str, value: &str) -> toml_edit::Value {
+    let is_integer = parse_key(key)
+        .is_ok_and(|path| config_check::is_integer_setting(&path.iter().map(String::as_str).collect::<Vec<_>>()));
+    match value.parse::<i64>() {
+        Ok(number) if is_integer => toml_edit::Value::from(number),
+        _ => toml_edit::Value::from(value),
+    }
+}
+
+/// Splits a dotted key into its parts.
+fn parse_key(key: &str) -> Result<Vec<String>, CliError> {
+    Key::parse(key)
+        .map(|keys| keys.iter().map(|key| key.get().to_string()).collect())
+        .map_err(|_| CliError::new(2, format!("Error: Invalid key '{key}' (quote parts containing dots or '*')")))
+}
+
+fn get(args: GetArgs) -> Result<(), CliError> {
+    let path = parse_key(&args.key)?;
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    config_check::setting_file(&path).map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+
+    let key = dotted(&path);
+    match settings().into_iter().find(|setting| setting.key == key) {
+        Some(setting) => {
+            println!("{}", setting.value);
+            Ok(())
+        }
+        None => Err(CliError::new(1, format!("{key} is not set"))),
+    }
+}
+
+fn set(args: SetArgs) -> Result<(), CliError> {
+    if !args.more.len().is_multiple_of(2) {
+        return Err(CliError::new(2, "Error: Expected a value after every key".to_string()));
+    }
+    let mut pairs = vec![(args.key, args.value)];
+    pairs.extend(args.more.chunks(2).map(|pair| (pair[0].clone(), pair[1].clone())));
+
+    // Each file is edited once, with all of its settings, so that a table
+    // can be added whole.
+    let mut edits: Vec<(ConfigFile, String, DocumentMut)> = Vec::new();
+    let mut written: Vec<(String, toml_edit::Value, ConfigFile)> = Vec::new();
+    for (key, value) in pairs {
+        let path = parse_key(&key)?;
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        let file = config_check::setting_file(&path).map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        let value = if config_check::is_integer_setting(&path) {
+            let number = value.parse::<i64>().map_err(|_| {
+                CliError::new(2, format!("Error: {} must be an integer, not '{value}'", dotted(&path)))
+            })?;
+            toml_edit::Value::from(number)
+        } else {
+            toml_edit::Value::from(value.as_str())
+        };
+        if !edits.iter().any(|(edited, ..)| *edited == file) {
+            let source = read_config(&file.path())?;
+            let doc = source.parse().map_err(|_| {
+                CliError::new(
+                    1,
+                    format!(
+                        "Error: {} does not parse. Run `axon config check` and fix it first.",
+                        file.path().display()
+                    ),
+                )
+            })?;
+            edits.push((file, source, doc));
+        }
+        let (_, _, doc) = edits.iter_mut().find(|(edited, ..)| *edited == file).unwrap();
+        set_value(doc, &path, value.clone()).map_err(|err| CliError::new(3, format!("Error: {err}")))?;
+        written.push((dotted(&path), value, file));
+    }
+
+    let keys: Vec<&str> = written.iter().map(|(key, ..)| key.as_str()).collect();
+    for (file, source, doc) in &edits {
+        refuse_new_errors(*file, source, &doc.to_string(), &keys.join(", "))?;
+    }
+    for (file, _, doc) in &edits {
+        write_config(&file.path(), &doc.to_string())?;
+    }
+
+    let settings = settings();
+    for (key, value, file) in &written {
+        let config_path = file.path();
+        println!("Set {key} = {value} in {}", config_path.display());
+        let origin = Origin::File(config_path).to_string();
+        if let Some(setting) = settings.iter().find(|setting| setting.key == *key)
+            && setting.origin != origin
+        {
+            println!("Note: {key} is overridden by {}", setting.origin);
+        }
+    }
+    Ok(())
+}
+
+/// The contents of the config file at `path`; empty if it doesn't exist.
+fn read_config(path: &Path) -> Result<String, CliError> {
+    match std::fs::read_to_string(path) {
+        Ok(source) => Ok(source),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(CliError::new(5, format!("Error: {}: {err}", path.display()))),
+    }
+}
+
+/// Fails when `edited` has config errors that `source` didn't, so that
+/// `set` never leaves a setting that would be dropped on load.
+fn refuse_new_errors(file: ConfigFile, source: &str, edited: &str, key: &str) -> Result<(), CliError> {
+    let errors = |source: &str| -> Vec<String> {
+        file.check(source)
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.message)
+            .collect()
+    };
+    let before = errors(source);
+    let new: Vec<String> = errors(edited)
+        .into_iter()
+        .filter(|message| !before.contains(message))
+        .collect();
+    if new.is_empty() {
+        return Ok(());
+    }
+    Err(CliError::new(
+        2,
+        format!(
+            "Error: setting {key} would make the config invalid, so nothing was written:\n  {}",
+            new.join("\n  ")
+        ),
+    ))
+}
+
+/// Sets the value at `path`, creating tables as needed. An existing value
+/// keeps its surrounding whitespace and trailing comment.
+fn set_value(doc: &mut DocumentMut, path: &[&str], value: toml_edit::Value) -> Result<(), String> {
+    let Some((last, parents)) = path.split_last() else {
+        return Err("empty key".to_string());
+    };
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for (idx, key) in parents.iter().enumerate() {
+        // Only the table holding the value gets a `[header]` of its own.
+        let holds_value = idx + 1 == parents.len();
+        let item = table.entry(key).or_insert_with(|| {
+            let mut new = Table::new();
+            new.set_implicit(!holds_value);
+            Item::Table(new)
+        });
+        table = item
+            .as_table_like_mut()
+            .ok_or_else(|| format!("{} is not a table", dotted(&path[..=idx])))?;
+    }
+
+    match table.get_mut(last) {
+        Some(Item::Value(existing)) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        Some(Item::None) | None => {
+            table.insert(last, Item::Value(value));
+        }
+        Some(_) => return Err(format!("{} is a table, not a value", dotted(path))),
+    }
+    Ok(())
+}
+
+fn write_config(path: &Path, content: &str) -> Result<(), CliError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| CliError::new(5, format!("Error: {}: {err}", parent.display())))?;
+    }
+    std::fs::write(path, content).map_err(|err| CliError::new(5, format!("Error: {}: {err}", path.display())))
+}
+
+const GLOBAL_STARTER: &str = r#"# axon global configuration
+#
+# Directories may start with ~/. The AXON_NOTES_DIR and AXON_PROMPTS_DIR
+# environment variables take precedence over these settings.
+
+# Notes: daily, weekly, and monthly notes, scratch notes. Default: ~/notes
+# notes_dir = "~/notes"
+
+# Prompt files. Default: ~/prompts
+# prompts_dir = "~/prompts"
+
+# Command that opens notes and prompts. {path} is the file and {line} the
+# line to jump to; without {path}, the file is appended. Default: $VISUAL,
+# then $EDITOR, then yazi.
+# opener = "nvim +{line} {path}"
+
+# Fuzzy finder for the TUI's lists: "builtin" (the default) or "fzf". fzf
+# falls back to the built-in picker when it's not installed.
+# picker = "fzf"
+
+# Agent CLIs that run prompts (`axon run`, `p` in the TUI). args may use
+# {prompt_path}, {prompts_md}, and {instruction}, an instruction to read
+# both; it defaults to "{instruction}". Without any runner, `claude` is used.
+# runner = "claude"
+#
+# [runners.claude]
+# command = "claude"
+# args = "{instruction}"
+#
+# [runners.aider]
+# command = "aider"
+# args = "--read {prompts_md} --read {prompt_path}"
+# cwd = "~/code"
+# env = { AIDER_DARK_MODE = "true" }
+
+# Where prompts run, by the {repo} segment of their filename. A prompt such
+# as myapp.feat.auth.spec.initial.v2.md runs in ~/code/myapp.
+# [repos]
+# myapp = "~/code/myapp"
+
+# Prompt size estimates (`axon size`). tokenizer is "chars" (one token per
+# 4 characters, the default) or "words". `axon health` fails for prompts
+# whose tokens, with includes expanded, exceed their category's maximum.
+# [size]
+# tokenizer = "chars"
+#
+# [size.max_tokens]
+# feat = 8000
+# sop = 4000
+"#;
+
+const NOTES_STARTER: &str = r#"# axon notes configuration, read from the notes directory.
+# Run `axon config check` after editing.
+
+# Templates for new notes by filename pattern, relative to this directory.
+# Patterns are tried in sorted order, so avoid patterns that overlap.
+[schemas]
+# "daily.*.md" = "templates/daily.md"
+# "weekly.*.md" = "templates/weekly.md"
+
+# Periodic note kinds; daily, weekly, and monthly are built in.
+# [periodic.quarterly]
+# format = "q.%Y.Q%q"
+# period = "quarter"
+# key = "Q"
+
+# Unfinished tasks to carry into new notes.
+# [carry_over.daily]
+# heading = "Tasks"
+# section = "Tasks"
+"#;
+
+fn init(args: InitArgs) -> Result<(), CliError> {
+    let (file, content) = if args.notes {
+        (ConfigFile::Notes, NOTES_STARTER)
+    } else {
+        (ConfigFile::Global, GLOBAL_STARTER)
+    };
+    let path = file.path();
+    if path.exists() && !args.force {
+        return Err(CliError::new(
+            3,
+            format!("Error: {} already exists (use --force to overwrite)", path.display()),
+        ));
+    }
+    write_config(&path, content)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
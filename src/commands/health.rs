@@ -1,10 +1,13 @@
 use clap::Args;
 use serde::Serialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::commands::links::{default_dirs, format_broken};
 use crate::error::CliError;
 use crate::fs_utils::{file_name_string, list_markdown_files};
-use crate::pattern::{canonical_pattern, exempt_reason, is_valid_filename};
+use crate::links::{check_links, collect_scope, BrokenLink};
+use crate::pattern::{canonical_pattern, exempt_reason, is_valid_filename, parse_filename};
+use crate::size::{measure_file, Tokenizer};
 
 #[derive(Args, Debug)]
 pub struct HealthArgs {
@@ -17,6 +20,9 @@ pub struct HealthArgs {
     /// Only output errors
     #[arg(long)]
     pub quiet: bool,
+    /// Also check for broken links between files
+    #[arg(long)]
+    pub links: bool,
 }
 
 #[derive(Serialize)]
@@ -28,6 +34,12 @@ struct HealthJson {
     strict: bool,
     invalid_files: Vec<FileEntry>,
     exempt_files: Vec<FileEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    broken_links: Option<Vec<BrokenLink>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    broken_includes: Vec<FileEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    oversized_files: Vec<FileEntry>,
 }
 
 #[derive(Serialize)]
@@ -73,6 +85,22 @@ pub fn run(args: HealthArgs) -> Result<(), CliError> {
         invalid_count
     };
 
+    let broken_links = if args.links {
+        let (_, mut known) = collect_scope(&default_dirs())
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        known.extend(files.iter().filter_map(|path| file_name_string(path)));
+        Some(check_links(&files, &known).broken)
+    } else {
+        None
+    };
+    let broken_count = broken_links.as_ref().map_or(0, Vec::len);
+    let broken_includes = broken_includes(&files);
+    let oversized_files = oversized_files(&files);
+    let failed = invalid_total > 0
+        || broken_count > 0
+        || !broken_includes.is_empty()
+        || !oversized_files.is_empty();
+
     if args.json {
         let payload = HealthJson {
             checked: valid + invalid_count + exempt_count,
@@ -82,11 +110,14 @@ pub fn run(args: HealthArgs) -> Result<(), CliError> {
             strict: args.strict,
             invalid_files,
             exempt_files,
+            broken_links,
+            broken_includes,
+            oversized_files,
         };
         let json = serde_json::to_string_pretty(&payload)
             .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
         println!("{json}");
-        if invalid_total > 0 {
+        if failed {
             return Err(CliError::new(1, String::new()));
         }
         return Ok(());
@@ -103,7 +134,13 @@ pub fn run(args: HealthArgs) -> Result<(), CliError> {
                 }
             }
         }
-        if invalid_total > 0 {
+        for broken in broken_links.iter().flatten() {
+            println!("{} (broken link)", format_broken(broken));
+        }
+        for entry in broken_includes.iter().chain(&oversized_files) {
+            println!("{} ({})", entry.file, entry.detail);
+        }
+        if failed {
             return Err(CliError::new(1, String::new()));
         }
         return Ok(());
@@ -115,6 +152,15 @@ pub fn run(args: HealthArgs) -> Result<(), CliError> {
     if !args.strict {
         println!("Exempt: {} files", exempt_count);
     }
+    if args.links {
+        println!("Broken links: {broken_count}");
+    }
+    if !broken_includes.is_empty() {
+        println!("Broken includes: {}", broken_includes.len());
+    }
+    if !oversized_files.is_empty() {
+        println!("Oversized: {} files", oversized_files.len());
+    }
 
     if invalid_total > 0 {
         println!("\nInvalid files:");
@@ -135,7 +181,30 @@ pub fn run(args: HealthArgs) -> Result<(), CliError> {
         }
     }
 
-    if invalid_total == 0 {
+    if let Some(broken_links) = &broken_links
+        && !broken_links.is_empty()
+    {
+        println!("\nBroken links:");
+        for broken in broken_links {
+            println!("  - {}", format_broken(broken));
+        }
+    }
+
+    if !broken_includes.is_empty() {
+        println!("\nBroken includes:");
+        for entry in &broken_includes {
+            println!("  - {} ({})", entry.file, entry.detail);
+        }
+    }
+
+    if !oversized_files.is_empty() {
+        println!("\nOversized files:");
+        for entry in &oversized_files {
+            println!("  - {} ({})", entry.file, entry.detail);
+        }
+    }
+
+    if !failed {
         println!("\nHealth: OK");
         Ok(())
     } else {
@@ -143,3 +212,45 @@ pub fn run(args: HealthArgs) -> Result<(), CliError> {
         Err(CliError::new(1, String::new()))
     }
 }
+
+/// Files whose includes can't be resolved or form a cycle.
+fn broken_includes(files: &[PathBuf]) -> Vec<FileEntry> {
+    files
+        .iter()
+        .filter_map(|path| {
+            let source = std::fs::read_to_string(path).ok()?;
+            if !crate::prompts::has_includes(&source) {
+                return None;
+            }
+            let err = crate::prompts::compose(path).err()?;
+            Some(FileEntry {
+                file: file_name_string(path)?,
+                detail: format!("error: {err}"),
+            })
+        })
+        .collect()
+}
+
+/// Prompts with more tokens, once their includes are expanded, than
+/// `size.max_tokens` allows for their category.
+fn oversized_files(files: &[PathBuf]) -> Vec<FileEntry> {
+    let cfg = crate::global_config::load();
+    if cfg.size.max_tokens.is_empty() {
+        return Vec::new();
+    }
+    let tokenizer = Tokenizer::from_config(&cfg);
+    files
+        .iter()
+        .filter_map(|path| {
+            let name = file_name_string(path)?;
+            let category = parse_filename(&name).ok()?.category().to_string();
+            let max = *cfg.size.max_tokens.get(&category).filter(|max| **max > 0)?;
+            let size = measure_file(path, tokenizer).ok()?;
+            let tokens = size.expanded.unwrap_or(size.size).tokens;
+            (tokens as i64 > max).then(|| FileEntry {
+                file: name,
+                detail: format!("error: ~{tokens} tokens, more than the {category} maximum of {max}"),
+            })
+        })
+        .collect()
+}
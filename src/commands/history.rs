@@ -0,0 +1,78 @@
+use clap::Args;
+use std::path::PathBuf;
+
+use crate::error::CliError;
+use crate::history::{self, EntryStatus, HistoryEntry};
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Show the full rename list of one operation
+    pub id: Option<u64>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// Operation root holding the history (defaults to the current directory)
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub root: PathBuf,
+}
+
+pub fn run(args: HistoryArgs) -> Result<(), CliError> {
+    let log = history::load(&args.root).map_err(|err| CliError::new(5, err))?;
+
+    if let Some(id) = args.id {
+        let entry = log
+            .get(id)
+            .ok_or_else(|| CliError::new(2, format!("Error: No operation with id {id} in history")))?;
+        if args.json {
+            let json = serde_json::to_string_pretty(entry)
+                .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+            println!("{json}");
+            return Ok(());
+        }
+        print_entry(entry);
+        println!();
+        for rename in &entry.renames {
+            println!("  {}", rename.from);
+            println!("    -> {}", rename.to);
+        }
+        return Ok(());
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&log.entries)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    if log.entries.is_empty() {
+        println!("No refactor history.");
+        return Ok(());
+    }
+
+    for entry in log.entries.iter().rev() {
+        print_entry(entry);
+    }
+    Ok(())
+}
+
+fn print_entry(entry: &HistoryEntry) {
+    let status = match entry.status {
+        EntryStatus::Applied => "",
+        EntryStatus::Undone => "  (undone)",
+    };
+    let patterns = match (&entry.from_pattern, &entry.to_pattern) {
+        (Some(from), Some(to)) => format!("  {from} -> {to}"),
+        _ => String::new(),
+    };
+    println!(
+        "#{:<4} {}  {} [{}]  {} files{}{}",
+        entry.id,
+        entry.timestamp,
+        entry.command,
+        entry.method,
+        entry.renames.len(),
+        patterns,
+        status
+    );
+}
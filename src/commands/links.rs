@@ -0,0 +1,76 @@
+use clap::Args;
+use std::path::PathBuf;
+
+use crate::error::CliError;
+use crate::links::{check_links, collect_scope, BrokenLink, LinkReport};
+
+#[derive(Args, Debug)]
+pub struct LinksArgs {
+    /// Directories to check (defaults to the configured prompts and notes directories)
+    pub dirs: Vec<PathBuf>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: LinksArgs) -> Result<(), CliError> {
+    let dirs = if args.dirs.is_empty() {
+        default_dirs()
+    } else {
+        args.dirs
+    };
+
+    let (files, known) =
+        collect_scope(&dirs).map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+    let report = check_links(&files, &known);
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+    } else {
+        print_report(&report);
+    }
+
+    if report.broken.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::new(1, String::new()))
+    }
+}
+
+/// The configured prompts and notes directories that exist.
+pub(crate) fn default_dirs() -> Vec<PathBuf> {
+    let cfg = crate::global_config::load();
+    let mut dirs = Vec::new();
+    for dir in [cfg.prompts_dir(), cfg.notes_dir()] {
+        if dir.is_dir() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+fn print_report(report: &LinkReport) {
+    println!(
+        "Checked {} links in {} files\n",
+        report.links, report.files
+    );
+    if report.broken.is_empty() {
+        println!("Links: OK");
+        return;
+    }
+    println!("Broken links:");
+    for broken in &report.broken {
+        println!("  {}", format_broken(broken));
+    }
+    println!("\nLinks: {} broken", report.broken.len());
+}
+
+pub(crate) fn format_broken(broken: &BrokenLink) -> String {
+    let mut line = format!("{}:{}: {}", broken.file, broken.line, broken.target);
+    if let Some(suggestion) = &broken.suggestion {
+        line.push_str(&format!(" (did you mean {suggestion}?)"));
+    }
+    line
+}
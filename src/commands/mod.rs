@@ -1,5 +1,16 @@
+pub mod config;
 pub mod health;
+pub mod history;
+pub mod links;
 pub mod parse;
+pub mod periodic;
+pub mod redo;
 pub mod refactor;
+pub mod render;
+pub mod run;
+pub mod runs;
+pub mod search;
+pub mod size;
 pub mod stats;
+pub mod undo;
 pub mod validate;
@@ -0,0 +1,83 @@
+use clap::{Args, Command, FromArgMatches};
+
+use crate::error::CliError;
+use crate::notes::{self, PeriodicKind};
+
+#[derive(Args, Debug)]
+pub struct PeriodicArgs {
+    /// Date within the period to open (YYYY-MM-DD, defaults to today)
+    pub date: Option<String>,
+    /// Number of periods to step from the date (e.g. -1 for the previous one)
+    #[arg(long, allow_hyphen_values = true, conflicts_with_all = ["prev", "next"])]
+    pub offset: Option<i64>,
+    /// Open the previous period's note
+    #[arg(long, conflicts_with = "next")]
+    pub prev: bool,
+    /// Open the next period's note
+    #[arg(long)]
+    pub next: bool,
+}
+
+/// The subcommand opening notes of `kind`, named after it with its key as an
+/// alias (`axon daily` / `axon d`).
+pub fn command(kind: &PeriodicKind) -> Command {
+    let mut cmd = PeriodicArgs::augment_args(
+        Command::new(kind.name.clone())
+            .about(format!("Open a {} note (the current period by default)", kind.name)),
+    );
+    if let Some(key) = kind.key {
+        cmd = cmd.visible_alias(key.to_string());
+    }
+    cmd
+}
+
+/// Adds a subcommand for every periodic note kind whose name and key don't
+/// collide with an existing command.
+pub fn add_commands(mut cli: Command, kinds: &[PeriodicKind]) -> Command {
+    for kind in kinds {
+        let key = kind.key.map(|key| key.to_string());
+        let taken = |name: &str| {
+            cli.get_subcommands()
+                .any(|sub| sub.get_name() == name || sub.get_all_aliases().any(|alias| alias == name))
+        };
+        if taken(&kind.name) || key.as_deref().is_some_and(taken) {
+            continue;
+        }
+        cli = cli.subcommand(command(kind));
+    }
+    cli
+}
+
+/// Runs the periodic note command `name` if it is one.
+pub fn dispatch(
+    kinds: &[PeriodicKind],
+    name: &str,
+    matches: &clap::ArgMatches,
+) -> Option<Result<(), CliError>> {
+    let kind = kinds.iter().find(|kind| kind.name == name)?;
+    let args = match PeriodicArgs::from_arg_matches(matches) {
+        Ok(args) => args,
+        Err(err) => return Some(Err(CliError::new(2, err.to_string()))),
+    };
+    Some(run(kind, args))
+}
+
+pub fn run(kind: &PeriodicKind, args: PeriodicArgs) -> Result<(), CliError> {
+    let date = match &args.date {
+        Some(value) => notes::parse_date(value).map_err(|err| CliError::new(2, format!("Error: {err}")))?,
+        None => notes::today(),
+    };
+    let offset = if args.prev {
+        -1
+    } else if args.next {
+        1
+    } else {
+        args.offset.unwrap_or(0)
+    };
+
+    let date = kind.step(date, offset).ok_or_else(|| {
+        CliError::new(2, format!("Error: offset {offset} is out of range for {} notes", kind.name))
+    })?;
+    notes::open_periodic(kind, date)
+        .map_err(|err| CliError::new(1, format!("{} note error: {err}", kind.name)))
+}
@@ -0,0 +1,56 @@
+use clap::Args;
+use std::path::PathBuf;
+
+use crate::commands::refactor::{execute_and_report, rename_dirs, resolve_method_override};
+use crate::commands::undo::recorded_method;
+use crate::error::CliError;
+use crate::history;
+
+#[derive(Args, Debug)]
+pub struct RedoArgs {
+    /// Use git mv for renames
+    #[arg(long, conflicts_with = "no_git")]
+    pub git: bool,
+    /// Use mv for renames even in git repo
+    #[arg(long)]
+    pub no_git: bool,
+    /// Overwrite existing files
+    #[arg(long)]
+    pub force: bool,
+    /// Operation root holding the history (defaults to the current directory)
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub root: PathBuf,
+}
+
+pub fn run(args: RedoArgs) -> Result<(), CliError> {
+    let root = args.root.as_path();
+    let mut log = history::load(root).map_err(|err| CliError::new(5, err))?;
+
+    let entry = log
+        .next_redo()
+        .cloned()
+        .ok_or_else(|| CliError::new(5, "Error: Nothing to redo".to_string()))?;
+
+    let checks: Vec<(&str, &str)> = entry
+        .edits
+        .iter()
+        .map(|edit| (entry.original_path(&edit.file), edit.before.as_str()))
+        .collect();
+    history::verify_contents(root, &checks).map_err(|err| CliError::new(3, err))?;
+    history::verify_renames(root, &entry.renames, args.force)
+        .map_err(|err| CliError::new(3, err))?;
+
+    println!(
+        "Redoing #{} ({}, {} files)...",
+        entry.id,
+        entry.command,
+        entry.renames.len()
+    );
+    let dirs = rename_dirs(root, &entry.renames);
+    let method = resolve_method_override(args.git, args.no_git, &dirs)?
+        .unwrap_or_else(|| recorded_method(&entry, &dirs));
+    execute_and_report(root, &entry.renames, &entry.edits, method, args.force, None)?;
+
+    log.mark_redone(entry.id);
+    history::save(root, &log).map_err(|err| CliError::new(5, format!("Error: {err}")))
+}
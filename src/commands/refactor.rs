@@ -1,18 +1,21 @@
 use clap::Args;
 use dialoguer::{Confirm, Input};
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 
 use crate::error::CliError;
-use crate::fs_utils::{file_name_string, list_markdown_files};
+use crate::fs_utils::{common_ancestor, file_name_string, list_markdown_files, rename_via_temp};
+use crate::git::{self, find_repo_root, GitIndex};
+use crate::history::{self, Operation};
+use crate::links::{plan_link_rewrites, LinkRewrite};
 use crate::pattern::{canonical_pattern_short, exempt_reason, is_valid_filename};
 use crate::refactor::{
-    build_rename_plans, check_existing_target_paths, check_for_duplicate_targets,
-    placeholder_mismatch_message, read_journal, write_journal, RefactorPattern, RenamePlan,
+    build_rename_plans, check_existing_target_paths_in, check_for_duplicate_targets,
+    check_plan_paths, check_plan_sources, folds_case_at, is_case_only_rename, placeholder_mismatch_message,
+    read_journal, read_plan, write_journal, write_plan, ContentEdit, RefactorPattern, RenamePlan,
 };
+use crate::review::review_renames;
 
 const RETRY_FILE: &str = ".axon-retry.json";
-const ROLLBACK_FILE: &str = ".axon-rollback.json";
 
 #[derive(Args, Debug)]
 pub struct RefactorArgs {
@@ -22,12 +25,24 @@ pub struct RefactorArgs {
     /// Target pattern
     #[arg(long)]
     pub to: Option<String>,
+    /// Directory to refactor (repeatable; defaults to the current directory)
+    #[arg(long = "dir", value_name = "DIR")]
+    pub dirs: Vec<PathBuf>,
+    /// Include the configured prompts directory
+    #[arg(long)]
+    pub prompts: bool,
+    /// Include the configured notes directory
+    #[arg(long)]
+    pub notes: bool,
     /// Show what would be renamed and exit
     #[arg(long)]
     pub dry_run: bool,
     /// Skip confirmation prompt
     #[arg(long)]
     pub yes: bool,
+    /// Review every rename before applying: deselect files or edit targets
+    #[arg(long, short = 'i', conflicts_with = "yes")]
+    pub interactive: bool,
     /// Use git mv for renames
     #[arg(long, conflicts_with = "no_git")]
     pub git: bool,
@@ -37,26 +52,132 @@ pub struct RefactorArgs {
     /// Overwrite existing files
     #[arg(long)]
     pub force: bool,
+    /// Rewrite references to renamed files inside markdown files
+    #[arg(long)]
+    pub rewrite_links: bool,
+    /// Commit the renames and link rewrites as a single git commit
+    #[arg(long, conflicts_with_all = ["no_git", "dry_run", "plan_out", "retry", "rollback"])]
+    pub commit: bool,
+    /// Commit message to use instead of the generated one
+    #[arg(long, short = 'm', value_name = "MESSAGE", requires = "commit")]
+    pub message: Option<String>,
+    /// Write the computed plan to a file (.json, .csv or .tsv) instead of renaming
+    #[arg(long, value_name = "FILE")]
+    pub plan_out: Option<PathBuf>,
+    /// Execute a plan file (.json, .csv or .tsv) instead of matching patterns
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["from", "to", "plan_out"])]
+    pub apply: Option<PathBuf>,
     /// Retry previously failed renames
-    #[arg(long, conflicts_with_all = ["rollback", "from", "to"])]
+    #[arg(long, conflicts_with_all = ["rollback", "from", "to", "apply", "plan_out"])]
     pub retry: bool,
-    /// Rollback the last refactor
-    #[arg(long, conflicts_with_all = ["retry", "from", "to"])]
+    /// Rollback the last refactor (same as `axon undo`)
+    #[arg(long, conflicts_with_all = ["retry", "from", "to", "apply", "plan_out"])]
     pub rollback: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum RenameMethod {
+pub(crate) enum RenameMethod {
     Git,
     Fs,
 }
 
+impl RenameMethod {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            RenameMethod::Git => "git",
+            RenameMethod::Fs => "fs",
+        }
+    }
+}
+
+/// The directories a refactor runs over. Rename plans, the history, and the
+/// retry journal all use paths relative to `root`, the deepest directory
+/// containing every directory in scope.
+#[derive(Debug)]
+struct Scope {
+    root: PathBuf,
+    dirs: Vec<PathBuf>,
+}
+
+impl Scope {
+    fn is_cwd(&self) -> bool {
+        self.root == Path::new(".")
+    }
+
+    fn absolute_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.iter().map(|dir| self.root.join(dir)).collect()
+    }
+
+    /// Markdown files in scope, relative to the root.
+    fn markdown_files(&self) -> Result<Vec<String>, CliError> {
+        let mut files = Vec::new();
+        for dir in &self.dirs {
+            let paths = list_markdown_files(&self.root.join(dir))
+                .map_err(|err| CliError::new(5, format!("Error: {}: {err}", dir.display())))?;
+            for path in paths {
+                if let Some(name) = file_name_string(&path) {
+                    files.push(dir.join(name).to_string_lossy().to_string());
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+fn resolve_scope(args: &RefactorArgs) -> Result<Scope, CliError> {
+    let mut dirs = args.dirs.clone();
+    if args.prompts || args.notes {
+        let cfg = crate::global_config::load();
+        if args.prompts {
+            dirs.push(cfg.prompts_dir());
+        }
+        if args.notes {
+            dirs.push(cfg.notes_dir());
+        }
+    }
+
+    if dirs.is_empty() {
+        return Ok(Scope {
+            root: PathBuf::from("."),
+            dirs: vec![PathBuf::new()],
+        });
+    }
+
+    let mut absolute = Vec::new();
+    for dir in dirs {
+        let canonical = dir.canonicalize().map_err(|err| {
+            CliError::new(5, format!("Error: Cannot read directory {}: {err}", dir.display()))
+        })?;
+        if !absolute.contains(&canonical) {
+            absolute.push(canonical);
+        }
+    }
+
+    let root = common_ancestor(&absolute)
+        .ok_or_else(|| CliError::new(2, "Error: Directories share no common root".to_string()))?;
+    let dirs = absolute
+        .iter()
+        .map(|dir| dir.strip_prefix(&root).unwrap_or(dir).to_path_buf())
+        .collect();
+    Ok(Scope { root, dirs })
+}
+
 pub fn run(args: RefactorArgs) -> Result<(), CliError> {
+    let scope = resolve_scope(&args)?;
     if args.retry {
-        return run_retry(&args);
+        return run_retry(&args, &scope);
     }
     if args.rollback {
-        return run_rollback(&args);
+        return crate::commands::undo::undo(
+            &scope.root,
+            None,
+            args.git,
+            args.no_git,
+            args.force,
+        );
+    }
+    if let Some(plan) = args.apply.clone() {
+        return run_apply(&args, &scope, &plan);
     }
 
     let default_pattern = canonical_pattern_short();
@@ -97,33 +218,53 @@ pub fn run(args: RefactorArgs) -> Result<(), CliError> {
         ));
     }
 
-    let files = list_markdown_files(Path::new("."))
-        .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    let files = scope.markdown_files()?;
 
     if files.is_empty() {
-        return Err(CliError::new(
-            1,
+        let message = if scope.is_cwd() {
             "Error: No markdown files found in current directory\n\nAre you in the prompts directory?"
-                .to_string(),
-        ));
+                .to_string()
+        } else {
+            format!(
+                "Error: No markdown files found in {}",
+                scope
+                    .absolute_dirs()
+                    .iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        return Err(CliError::new(1, message));
     }
 
     let mut markdown = Vec::new();
     let mut exempt = Vec::new();
-    for path in files {
-        let Some(name) = file_name_string(&path) else {
-            continue;
-        };
-        if exempt_reason(&name).is_some() {
-            exempt.push(name);
-        } else {
-            markdown.push(name);
+    let mut renames = Vec::new();
+    for dir in &scope.dirs {
+        let mut names = Vec::new();
+        for file in &files {
+            let path = Path::new(file);
+            if path.parent().unwrap_or(Path::new("")) != dir.as_path() {
+                continue;
+            }
+            let Some(name) = file_name_string(path) else {
+                continue;
+            };
+            if exempt_reason(&name).is_some() {
+                exempt.push(name);
+            } else {
+                names.push(name);
+            }
         }
+        let plans = build_rename_plans(&names, &source_pattern, &target_pattern)
+            .map_err(|err| CliError::new(2, err))?;
+        renames.extend(plans.into_iter().map(|plan| {
+            RenamePlan::new(dir.join(plan.from).to_string_lossy(), dir.join(plan.to).to_string_lossy())
+        }));
+        markdown.extend(names);
     }
 
-    let renames = build_rename_plans(&markdown, &source_pattern, &target_pattern)
-        .map_err(|err| CliError::new(2, err))?;
-
     if renames.is_empty() {
         let valid_count = markdown.iter().filter(|name| is_valid_filename(name)).count();
         let mut message = format!("Error: No files match the pattern \"{from}\"\n\n");
@@ -146,7 +287,39 @@ pub fn run(args: RefactorArgs) -> Result<(), CliError> {
         println!("Skipped: {} files (non-matching)", non_matching);
     }
 
-    let renames: Vec<_> = renames
+    apply_renames(&args, &scope, renames, &files, "refactor", Some(from), Some(to))
+}
+
+fn run_apply(args: &RefactorArgs, scope: &Scope, plan: &Path) -> Result<(), CliError> {
+    let renames = read_plan(plan).map_err(|err| {
+        CliError::new(2, format!("Error: Invalid plan file {}\n  - {err}", plan.display()))
+    })?;
+    if renames.is_empty() {
+        println!("No changes to apply.");
+        return Ok(());
+    }
+
+    println!("Plan: {} renames from {}", renames.len(), plan.display());
+    check_plan_paths(&renames).map_err(|err| CliError::new(2, err))?;
+    check_plan_sources(&scope.root, &renames).map_err(|err| CliError::new(1, err))?;
+
+    let files = scope.markdown_files()?;
+    let command = format!("refactor --apply {}", plan.display());
+    apply_renames(args, scope, renames, &files, &command, None, None)
+}
+
+/// Previews, checks, and executes renames. Shared by pattern refactors and
+/// `--apply`; `files` are searched for links to rewrite.
+fn apply_renames(
+    args: &RefactorArgs,
+    scope: &Scope,
+    renames: Vec<RenamePlan>,
+    files: &[String],
+    command: &str,
+    from_pattern: Option<String>,
+    to_pattern: Option<String>,
+) -> Result<(), CliError> {
+    let mut renames: Vec<_> = renames
         .into_iter()
         .filter(|entry| entry.from != entry.to)
         .collect();
@@ -156,29 +329,79 @@ pub fn run(args: RefactorArgs) -> Result<(), CliError> {
         return Ok(());
     }
 
-    println!("\nPreview:\n");
-    print_preview(&renames, Some(3));
+    if args.interactive {
+        let total = renames.len();
+        renames = match review_renames(renames, &scope.root, args.force)
+            .map_err(|err| CliError::new(5, format!("Error: {err}")))?
+        {
+            Some(selected) => selected,
+            None => {
+                println!("\nCancelled. No files were renamed.");
+                return Ok(());
+            }
+        };
+        println!("\nReviewed: {} of {total} renames selected", renames.len());
+        if renames.is_empty() {
+            return Ok(());
+        }
+    } else {
+        println!("\nPreview:\n");
+        print_preview(&renames, Some(3));
+    }
 
     if let Err(err) = check_for_duplicate_targets(&renames) {
         return Err(CliError::new(3, err));
     }
 
-    if !args.force {
-        if let Err(err) = check_existing_target_paths(&renames) {
-            return Err(CliError::new(3, err));
-        }
+    if !args.force
+        && let Err(err) = check_existing_target_paths_in(&scope.root, &renames)
+    {
+        return Err(CliError::new(3, err));
+    }
+
+    if let Some(path) = &args.plan_out {
+        write_plan(path, &renames)
+            .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+        println!("\nWrote plan for {} renames to {}", renames.len(), path.display());
+        println!("Apply it with: axon refactor --apply {}", path.display());
+        return Ok(());
     }
 
-    let method = resolve_method(&args)?;
+    let method = resolve_method(args, &scope.absolute_dirs())?;
+    let commit_repo = if args.commit {
+        Some(prepare_commit(method, &scope.root, &renames)?)
+    } else {
+        None
+    };
+
+    let rewrites = if args.rewrite_links {
+        plan_link_rewrites(&scope.root, files, &renames).map_err(|err| CliError::new(5, err))?
+    } else {
+        Vec::new()
+    };
+    if let Some(repo) = &commit_repo {
+        check_unstaged_edits(repo, &scope.root, &renames, &rewrites)?;
+    }
+    if args.rewrite_links {
+        let count: usize = rewrites.iter().map(|rewrite| rewrite.changes.len()).sum();
+        println!(
+            "Links: {count} references in {} files will be rewritten",
+            rewrites.len()
+        );
+    }
 
     if args.dry_run {
         println!("\nDry run (no changes made):\n");
         print_preview(&renames, None);
+        if !rewrites.is_empty() {
+            println!("Link rewrites:\n");
+            print_link_preview(&rewrites);
+        }
         return Ok(());
     }
 
     let chosen_method = method;
-    if !args.yes {
+    if !args.yes && !args.interactive {
         let proceed = Confirm::new()
             .with_prompt(format!(
                 "Proceed with {}?",
@@ -195,72 +418,238 @@ pub fn run(args: RefactorArgs) -> Result<(), CliError> {
         }
     }
 
-    execute_and_report(&renames, chosen_method, args.force, true)
+    let operation = Operation {
+        command: command.to_string(),
+        from_pattern,
+        to_pattern,
+        method: chosen_method.name().to_string(),
+    };
+    let edits: Vec<ContentEdit> = rewrites.into_iter().map(|rewrite| rewrite.edit).collect();
+    execute_and_report(
+        &scope.root,
+        &renames,
+        &edits,
+        chosen_method,
+        args.force,
+        Some(&operation),
+    )?;
+    if let Some(repo) = commit_repo {
+        let message = match &args.message {
+            Some(message) => message.clone(),
+            None => commit_message(&operation, &renames, &edits),
+        };
+        commit_refactor(&repo, &scope.root, &renames, &edits, &message)?;
+    }
+    if !scope.is_cwd() {
+        println!(
+            "History recorded in {}. Undo with: axon undo --root {}",
+            scope.root.display(),
+            scope.root.display()
+        );
+    }
+    Ok(())
 }
 
-fn run_retry(args: &RefactorArgs) -> Result<(), CliError> {
-    let journal_path = Path::new(RETRY_FILE);
-    if !journal_path.exists() {
+/// Checks that `--commit` can produce a commit holding only this refactor:
+/// renames must use git, stay in one repository, and nothing may be staged
+/// yet. Returns the repository root.
+fn prepare_commit(
+    method: RenameMethod,
+    root: &Path,
+    renames: &[RenamePlan],
+) -> Result<PathBuf, CliError> {
+    if method != RenameMethod::Git {
         return Err(CliError::new(
-            5,
-            format!("Error: {RETRY_FILE} not found"),
+            2,
+            "Error: --commit requires renaming with git".to_string(),
         ));
     }
 
-    let renames = read_journal(journal_path).map_err(|err| CliError::new(5, err))?;
-    if renames.is_empty() {
+    let mut repos: Vec<PathBuf> = Vec::new();
+    for dir in rename_dirs(root, renames) {
+        if let Some(repo) = find_repo_root(&dir)
+            && !repos.contains(&repo)
+        {
+            repos.push(repo);
+        }
+    }
+    if repos.len() > 1 {
+        return Err(CliError::new(
+            2,
+            "Error: --commit cannot span more than one git repository".to_string(),
+        ));
+    }
+    let Some(repo) = repos.pop() else {
+        return Err(CliError::new(
+            2,
+            "Error: --commit requires renaming with git".to_string(),
+        ));
+    };
+
+    let staged = git::staged_files(&repo).map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    if !staged.is_empty() {
+        let mut message = String::from("Error: Unrelated changes are already staged\n\n");
+        for file in staged {
+            message.push_str(&format!("  {file}\n"));
+        }
+        message.push_str("\nCommit or unstage them first. Aborting. No files were renamed.");
+        return Err(CliError::new(3, message));
+    }
+    Ok(repo)
+}
+
+/// Refuses `--commit` when a file whose links would be rewritten has
+/// unstaged changes, which would otherwise be committed along with the
+/// rewritten links.
+fn check_unstaged_edits(
+    repo: &Path,
+    root: &Path,
+    renames: &[RenamePlan],
+    rewrites: &[LinkRewrite],
+) -> Result<(), CliError> {
+    let files = rewrites
+        .iter()
+        .map(|rewrite| {
+            let file = &rewrite.edit.file;
+            let current = renames
+                .iter()
+                .find(|rename| rename.to == *file)
+                .map_or(file, |rename| &rename.from);
+            std::path::absolute(root.join(current))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    let unstaged =
+        git::unstaged_files(repo, &files).map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    if unstaged.is_empty() {
         return Ok(());
     }
+    let mut message = String::from("Error: Files whose links would be rewritten have unstaged changes\n\n");
+    for file in unstaged {
+        message.push_str(&format!("  {file}\n"));
+    }
+    message.push_str("\nCommit or stash them first. Aborting. No files were renamed.");
+    Err(CliError::new(3, message))
+}
 
-    let method = resolve_method(args)?;
-    execute_and_report(&renames, method, args.force, true)?;
-    let _ = std::fs::remove_file(RETRY_FILE);
-    Ok(())
+fn commit_message(operation: &Operation, renames: &[RenamePlan], edits: &[ContentEdit]) -> String {
+    let mut message = match (&operation.from_pattern, &operation.to_pattern) {
+        (Some(from), Some(to)) => format!("Rename {} files: {from} -> {to}\n\n", renames.len()),
+        _ => format!("Rename {} files ({})\n\n", renames.len(), operation.command),
+    };
+    for rename in renames {
+        message.push_str(&format!("- {} -> {}\n", rename.from, rename.to));
+    }
+    if !edits.is_empty() {
+        message.push_str(&format!("\nRewrote links in {} files.\n", edits.len()));
+    }
+    message
 }
 
-fn run_rollback(args: &RefactorArgs) -> Result<(), CliError> {
-    let journal_path = Path::new(ROLLBACK_FILE);
+fn commit_refactor(
+    repo: &Path,
+    root: &Path,
+    renames: &[RenamePlan],
+    edits: &[ContentEdit],
+    message: &str,
+) -> Result<(), CliError> {
+    let files = edits
+        .iter()
+        .map(|edit| std::path::absolute(root.join(&edit.file)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    match git::commit(repo, &files, message) {
+        Ok(Some(hash)) => {
+            println!("Committed {} renames as {hash}.", renames.len());
+            Ok(())
+        }
+        Ok(None) => {
+            println!("Nothing to commit: no tracked files were renamed.");
+            Ok(())
+        }
+        Err(err) => Err(CliError::new(
+            5,
+            format!("Error: Files were renamed but the commit failed: {err}"),
+        )),
+    }
+}
+
+fn run_retry(args: &RefactorArgs, scope: &Scope) -> Result<(), CliError> {
+    let journal_path = scope.root.join(RETRY_FILE);
     if !journal_path.exists() {
         return Err(CliError::new(
             5,
-            format!("Error: {ROLLBACK_FILE} not found"),
+            format!("Error: {} not found", journal_path.display()),
         ));
     }
 
-    let renames = read_journal(journal_path).map_err(|err| CliError::new(5, err))?;
+    let (renames, edits) = read_journal(&journal_path).map_err(|err| CliError::new(5, err))?;
     if renames.is_empty() {
         return Ok(());
     }
-
-    let mut reversed = Vec::new();
-    for rename in renames.iter().rev() {
-        reversed.push(RenamePlan {
-            from: rename.to.clone(),
-            to: rename.from.clone(),
-        });
-    }
-
-    let method = resolve_method(args)?;
-    execute_and_report(&reversed, method, args.force, false)?;
-    let _ = std::fs::remove_file(ROLLBACK_FILE);
+    // Files to edit may still sit under the name they had before the
+    // remaining renames.
+    let checks: Vec<(&str, &str)> = edits
+        .iter()
+        .map(|edit| {
+            let path = renames
+                .iter()
+                .find(|rename| rename.to == edit.file)
+                .map_or(edit.file.as_str(), |rename| rename.from.as_str());
+            (path, edit.before.as_str())
+        })
+        .collect();
+    history::verify_contents(&scope.root, &checks).map_err(|err| CliError::new(3, err))?;
+
+    let method = resolve_method(args, &rename_dirs(&scope.root, &renames))?;
+    let operation = Operation {
+        command: "refactor --retry".to_string(),
+        from_pattern: None,
+        to_pattern: None,
+        method: method.name().to_string(),
+    };
+    execute_and_report(&scope.root, &renames, &edits, method, args.force, Some(&operation))?;
+    let _ = std::fs::remove_file(journal_path);
     Ok(())
 }
 
-fn resolve_method(args: &RefactorArgs) -> Result<RenameMethod, CliError> {
-    let in_git = is_git_repo();
-    if args.git {
-        if !in_git {
+fn resolve_method(args: &RefactorArgs, dirs: &[PathBuf]) -> Result<RenameMethod, CliError> {
+    Ok(resolve_method_override(args.git, args.no_git, dirs)?.unwrap_or_else(|| {
+        if in_git_repo(dirs) {
+            RenameMethod::Git
+        } else {
+            RenameMethod::Fs
+        }
+    }))
+}
+
+/// Resolves an explicit `--git` / `--no-git` choice for renames in `dirs`.
+/// Returns `None` when neither flag was given so callers can pick their own
+/// default.
+pub(crate) fn resolve_method_override(
+    git: bool,
+    no_git: bool,
+    dirs: &[PathBuf],
+) -> Result<Option<RenameMethod>, CliError> {
+    if git {
+        if !git::is_installed() {
+            return Err(CliError::new(
+                5,
+                "Error: git is not installed\n\nUse --no-git to rename with regular mv.".to_string(),
+            ));
+        }
+        if !in_git_repo(dirs) {
             return Err(CliError::new(
                 5,
                 "Error: Not in a git repository\n\nUse --no-git to rename with regular mv, or initialize a git repo first.".to_string(),
             ));
         }
-        return Ok(RenameMethod::Git);
+        return Ok(Some(RenameMethod::Git));
     }
-    if args.no_git {
-        return Ok(RenameMethod::Fs);
+    if no_git {
+        return Ok(Some(RenameMethod::Fs));
     }
-    Ok(if in_git { RenameMethod::Git } else { RenameMethod::Fs })
+    Ok(None)
 }
 
 fn prompt_pattern(prompt: &str, default: Option<&str>) -> Result<String, CliError> {
@@ -273,10 +662,10 @@ fn prompt_pattern(prompt: &str, default: Option<&str>) -> Result<String, CliErro
     let value = input
         .interact_text()
         .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
-    if value.trim().is_empty() {
-        if let Some(default) = default {
-            return Ok(default.to_string());
-        }
+    if value.trim().is_empty()
+        && let Some(default) = default
+    {
+        return Ok(default.to_string());
     }
     Ok(value)
 }
@@ -288,30 +677,67 @@ fn print_preview(renames: &[RenamePlan], limit: Option<usize>) {
         println!("    -> {}", entry.to);
         println!();
     }
-    if let Some(limit) = limit {
-        if renames.len() > limit {
-            println!("  ... and {} more", renames.len() - limit);
+    if let Some(limit) = limit
+        && renames.len() > limit
+    {
+        println!("  ... and {} more", renames.len() - limit);
+    }
+}
+
+fn print_link_preview(rewrites: &[LinkRewrite]) {
+    for rewrite in rewrites {
+        for change in &rewrite.changes {
+            println!("  {}:{}", rewrite.edit.file, change.line);
+            println!("    {} -> {}", change.from, change.to);
+            println!();
         }
     }
 }
 
-fn execute_and_report(
+/// Renames every entry in order, stopping at the first failure, then
+/// applies `edits` once every rename succeeded. When `record` is given the
+/// completed changes are appended to the history, and any unprocessed
+/// renames are written to the retry journal together with `edits`. Without
+/// it (undo and redo) a failure rolls back the renames already made, so the
+/// files stay as the history describes them.
+pub(crate) fn execute_and_report(
+    root: &Path,
     renames: &[RenamePlan],
+    edits: &[ContentEdit],
     method: RenameMethod,
     force: bool,
-    write_journals: bool,
+    record: Option<&Operation>,
 ) -> Result<(), CliError> {
     println!("Renaming {} files...", renames.len());
 
+    let paths = renames
+        .iter()
+        .map(|entry| absolute_pair(root, entry))
+        .collect::<Result<Vec<_>, _>>()?;
+    let index = match method {
+        RenameMethod::Git => {
+            let sources: Vec<PathBuf> = paths.iter().map(|(from, _)| from.clone()).collect();
+            Some(GitIndex::load(&sources).map_err(|err| CliError::new(5, format!("Error: {err}")))?)
+        }
+        RenameMethod::Fs => None,
+    };
+
     let mut successes = Vec::new();
     let mut failures: Option<(RenamePlan, String)> = None;
 
     for (idx, entry) in renames.iter().enumerate() {
         let step = idx + 1;
         let total = renames.len();
-        match perform_rename(entry, method, force) {
+        match perform_rename(root, entry, force) {
             Ok(()) => {
-                println!("  [{step}/{total}] OK: {}", entry.from);
+                match &index {
+                    Some(index) => println!(
+                        "  [{step}/{total}] OK: {} ({})",
+                        entry.from,
+                        index.status(&paths[idx].0).label()
+                    ),
+                    None => println!("  [{step}/{total}] OK: {}", entry.from),
+                }
                 successes.push(entry.clone());
             }
             Err(err) => {
@@ -323,13 +749,26 @@ fn execute_and_report(
         }
     }
 
+    if let Some((failed_entry, error)) = &failures
+        && record.is_none()
+    {
+        return Err(rolled_back(root, &successes, failed_entry, error));
+    }
+
+    let staged = match &index {
+        Some(index) => index.stage_renames(&paths[..successes.len()]),
+        None => Ok(()),
+    };
+
     if let Some((failed_entry, error)) = failures {
         let remaining_index = successes.len();
         let remaining = renames[remaining_index..].to_vec();
 
-        if write_journals {
-            let _ = write_journal(Path::new(ROLLBACK_FILE), &successes);
-            let _ = write_journal(Path::new(RETRY_FILE), &remaining);
+        if let Some(operation) = record {
+            if !successes.is_empty() {
+                let _ = record_history(root, operation, &successes, &[]);
+            }
+            let _ = write_journal(&root.join(RETRY_FILE), &remaining, edits);
         }
 
         let mut message = format!(
@@ -352,61 +791,120 @@ fn execute_and_report(
                 "Remaining {remaining_count} files were not processed.\n\n"
             ));
         }
-        message.push_str("To retry failed files: axon refactor --retry\n");
-        message.push_str("To rollback successful renames: axon refactor --rollback");
-        return Err(CliError::new(4, message));
+        if !edits.is_empty() {
+            message.push_str("Links were not rewritten; --retry rewrites them once every file is renamed.\n\n");
+        }
+        if let Err(err) = staged {
+            message.push_str(&format!("Failed to stage renames in git: {err}\n\n"));
+        }
+        if record.is_some() {
+            message.push_str("To retry failed files: axon refactor --retry\n");
+            message.push_str("To rollback successful renames: axon undo");
+        }
+        return Err(CliError::new(4, message.trim_end().to_string()));
     }
 
-    if write_journals {
-        let _ = write_journal(Path::new(ROLLBACK_FILE), renames);
-        let _ = std::fs::remove_file(RETRY_FILE);
+    let contents: Vec<(&str, &str)> = edits
+        .iter()
+        .map(|edit| (edit.file.as_str(), edit.after.as_str()))
+        .collect();
+    history::write_contents(root, &contents)
+        .map_err(|err| CliError::new(5, format!("Error: Failed to rewrite links: {err}")))?;
+
+    if let Some(operation) = record {
+        record_history(root, operation, renames, edits)?;
+        let _ = std::fs::remove_file(root.join(RETRY_FILE));
     }
+    staged.map_err(|err| {
+        CliError::new(
+            5,
+            format!("Error: Files were renamed but staging them in git failed: {err}"),
+        )
+    })?;
 
     println!("Done. {} files renamed.", renames.len());
+    if !edits.is_empty() {
+        println!("Rewrote links in {} files.", edits.len());
+    }
     Ok(())
 }
 
-fn perform_rename(entry: &RenamePlan, method: RenameMethod, force: bool) -> Result<(), String> {
-    match method {
-        RenameMethod::Git => {
-            let mut cmd = Command::new("git");
-            cmd.arg("mv");
-            if force {
-                cmd.arg("-f");
-            }
-            cmd.arg("--").arg(&entry.from).arg(&entry.to);
-            let output = cmd.output().map_err(|err| err.to_string())?;
-            if output.status.success() {
-                Ok(())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let message = stderr.trim();
-                if message.is_empty() {
-                    Err("git mv failed".to_string())
-                } else {
-                    Err(message.to_string())
-                }
-            }
+/// Reverses `done` after `failed` could not be renamed, and describes
+/// what's left for the user to fix.
+fn rolled_back(root: &Path, done: &[RenamePlan], failed: &RenamePlan, error: &str) -> CliError {
+    let mut stuck = Vec::new();
+    for entry in done.iter().rev() {
+        let back = RenamePlan::new(entry.to.as_str(), entry.from.as_str());
+        if let Err(err) = perform_rename(root, &back, false) {
+            stuck.push(format!("  - {} -> {}: {err}\n", back.from, back.to));
         }
-        RenameMethod::Fs => {
-            if force && Path::new(&entry.to).exists() {
-                std::fs::remove_file(&entry.to).map_err(|err| err.to_string())?;
-            }
-            std::fs::rename(&entry.from, &entry.to).map_err(|err| err.to_string())
+    }
+
+    let mut message = format!("Error: Rename failed: {}: {error}\n\n", failed.from);
+    if stuck.is_empty() {
+        message.push_str(&format!(
+            "Rolled back {} completed renames. No files were changed.",
+            done.len()
+        ));
+    } else {
+        message.push_str("These renames could not be rolled back:\n");
+        for line in &stuck {
+            message.push_str(line);
         }
+        message.push_str("\nRename them back by hand, then run the command again.");
     }
+    CliError::new(4, message)
 }
 
-fn is_git_repo() -> bool {
-    let output = Command::new("git")
-        .args(["rev-parse", "--is-inside-work-tree"])
-        .output();
-    let Ok(output) = output else {
-        return false;
+fn record_history(
+    root: &Path,
+    operation: &Operation,
+    renames: &[RenamePlan],
+    edits: &[ContentEdit],
+) -> Result<(), CliError> {
+    let mut log = history::load(root).map_err(|err| CliError::new(5, err))?;
+    log.record(operation, renames, edits);
+    history::save(root, &log).map_err(|err| CliError::new(5, format!("Error: {err}")))
+}
+
+fn perform_rename(root: &Path, entry: &RenamePlan, force: bool) -> Result<(), String> {
+    let from = root.join(&entry.from);
+    let to = root.join(&entry.to);
+    if is_case_only_rename(&entry.from, &entry.to) && folds_case_at(&to) {
+        return rename_via_temp(&from, &to).map_err(|err| err.to_string());
+    }
+    if force && to.exists() {
+        std::fs::remove_file(&to).map_err(|err| err.to_string())?;
+    }
+    std::fs::rename(&from, &to).map_err(|err| err.to_string())
+}
+
+fn absolute_pair(root: &Path, entry: &RenamePlan) -> Result<(PathBuf, PathBuf), CliError> {
+    let absolute = |path: &str| {
+        std::path::absolute(root.join(path))
+            .map_err(|err| CliError::new(5, format!("Error: {path}: {err}")))
     };
-    if !output.status.success() {
-        return false;
+    Ok((absolute(&entry.from)?, absolute(&entry.to)?))
+}
+
+/// The directories containing the source of each rename.
+pub(crate) fn rename_dirs(root: &Path, renames: &[RenamePlan]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for rename in renames {
+        let dir = root
+            .join(&rename.from)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| root.to_path_buf());
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    stdout.trim() == "true"
+    dirs
+}
+
+/// Whether every directory is inside a git work tree and git is installed
+/// to rename with.
+pub(crate) fn in_git_repo(dirs: &[PathBuf]) -> bool {
+    !dirs.is_empty() && git::is_installed() && dirs.iter().all(|dir| find_repo_root(dir).is_some())
 }
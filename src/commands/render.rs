@@ -0,0 +1,104 @@
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::error::CliError;
+use crate::template;
+
+#[derive(Args, Debug)]
+pub struct RenderArgs {
+    /// Prompt file, prompt name without its version, or a fuzzy query (as for `axon run`)
+    pub prompt: String,
+    /// Set a variable, e.g. --var ticket=ABC-1 (repeatable; overrides --vars-file)
+    #[arg(long = "var", value_name = "NAME=VALUE")]
+    pub vars: Vec<String>,
+    /// TOML file of variables, e.g. ticket = "ABC-1"
+    #[arg(long, value_name = "FILE")]
+    pub vars_file: Option<PathBuf>,
+    /// List the variables the prompt uses instead of rendering it
+    #[arg(long)]
+    pub list_vars: bool,
+    /// Write the rendered prompt to this file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub out: Option<PathBuf>,
+}
+
+pub fn run(args: RenderArgs) -> Result<(), CliError> {
+    let dir = std::path::absolute(crate::global_config::load().prompts_dir())
+        .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    let path = super::run::find_prompt(&args.prompt, &dir)?;
+    let source = std::fs::read_to_string(&path)
+        .map_err(|err| CliError::new(5, format!("Error: {}: {err}", path.display())))?;
+    let source = crate::prompts::expand_include_comments(&source);
+
+    let mut ctx = crate::prompts::include_context(path.parent().unwrap_or(&dir));
+    if args.list_vars {
+        let variables = template::variables(&source, &ctx)
+            .map_err(|err| CliError::new(1, format!("Error: {}: {err}", path.display())))?;
+        for variable in variables {
+            if variable.required {
+                println!("{}", variable.name);
+            } else {
+                println!("{} (optional)", variable.name);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(file) = &args.vars_file {
+        for (name, value) in read_vars_file(file)? {
+            ctx.set(&name, value);
+        }
+    }
+    for var in &args.vars {
+        let Some((name, value)) = var.split_once('=') else {
+            return Err(CliError::new(
+                2,
+                format!("Error: invalid --var '{var}', expected NAME=VALUE"),
+            ));
+        };
+        ctx.set(name.trim(), value);
+    }
+
+    let rendered = template::render(&source, &ctx)
+        .map_err(|err| CliError::new(1, format!("Error: {}: {err}", path.display())))?;
+    if !rendered.unknown.is_empty() {
+        return Err(CliError::new(
+            1,
+            format!(
+                "Error: missing variables: {} (set them with --var NAME=VALUE or --vars-file)",
+                rendered.unknown.join(", ")
+            ),
+        ));
+    }
+
+    match &args.out {
+        Some(out) => {
+            std::fs::write(out, &rendered.text)
+                .map_err(|err| CliError::new(5, format!("Error: {}: {err}", out.display())))?;
+            println!("Wrote {}", out.display());
+        }
+        None => print!("{}", rendered.text),
+    }
+    Ok(())
+}
+
+/// Reads a TOML file of variables. Numbers and booleans become their text.
+fn read_vars_file(path: &Path) -> Result<Vec<(String, String)>, CliError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| CliError::new(5, format!("Error: {}: {err}", path.display())))?;
+    let table: toml::Table = toml::from_str(&content)
+        .map_err(|err| CliError::new(2, format!("Error: {}: {}", path.display(), err.message())))?;
+    table
+        .into_iter()
+        .map(|(name, value)| match value {
+            toml::Value::String(text) => Ok((name, text)),
+            toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                Ok((name, value.to_string()))
+            }
+            _ => Err(CliError::new(
+                2,
+                format!("Error: {}: `{name}` must be a string", path.display()),
+            )),
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::error::CliError;
+use crate::runner;
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Prompt file, prompt name without its version for the latest one, or a
+    /// fuzzy query; omit to start a session with prompts.md
+    pub prompt: Option<String>,
+    /// Runner to use instead of the default (see [runners.<name>] in the config)
+    #[arg(long)]
+    pub runner: Option<String>,
+    /// Print the instruction for the agent instead of running it
+    #[arg(long)]
+    pub print: bool,
+}
+
+pub fn run(args: RunArgs) -> Result<(), CliError> {
+    let cfg = crate::global_config::load();
+    let runner = runner::resolve(&cfg, args.runner.as_deref())
+        .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+
+    let dir = std::path::absolute(cfg.prompts_dir())
+        .map_err(|err| CliError::new(5, format!("Error: {err}")))?;
+    let prompt_path = match &args.prompt {
+        Some(prompt) => Some(find_prompt(prompt, &dir)?),
+        None => None,
+    };
+    let invocation = crate::prompts::invocation(&cfg, prompt_path)
+        .map_err(|err| CliError::new(1, format!("Error: {err}")))?;
+
+    if args.print {
+        println!("{}", invocation.instruction());
+        return Ok(());
+    }
+    runner
+        .run(&invocation)
+        .map_err(|err| CliError::new(1, format!("run error: {err}")))
+}
+
+/// A path to an existing file, else the prompt in `dir` the query resolves to.
+pub(crate) fn find_prompt(prompt: &str, dir: &Path) -> Result<PathBuf, CliError> {
+    let path = PathBuf::from(prompt);
+    if path.is_file() {
+        return std::path::absolute(&path).map_err(|err| CliError::new(5, format!("Error: {err}")));
+    }
+    let files = crate::prompts::list_prompts_in(dir);
+    crate::prompts::resolve_prompt(prompt, &files)
+        .map(|file| dir.join(file))
+        .map_err(|err| CliError::new(1, format!("Error: {err} in {}", dir.display())))
+}
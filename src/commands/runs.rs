@@ -0,0 +1,125 @@
+use clap::Args;
+
+use crate::error::CliError;
+use crate::run_log::{self, RunRecord};
+
+#[derive(Args, Debug)]
+pub struct RunsArgs {
+    /// Only runs of prompts whose filename contains this text
+    #[arg(long)]
+    pub prompt: Option<String>,
+    /// Only runs of prompts for this repo
+    #[arg(long)]
+    pub repo: Option<String>,
+    /// Only runs with this runner
+    #[arg(long)]
+    pub runner: Option<String>,
+    /// Only runs on or after this date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+    /// Only runs that failed
+    #[arg(long)]
+    pub failed: bool,
+    /// Show at most this many runs, most recent first (0 for all)
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: RunsArgs) -> Result<(), CliError> {
+    let since = match &args.since {
+        Some(value) => Some(
+            crate::notes::parse_date(value)
+                .map_err(|err| CliError::new(2, format!("Error: {err}")))?
+                .format("%Y-%m-%d")
+                .to_string(),
+        ),
+        None => None,
+    };
+    let records = run_log::load().map_err(|err| {
+        CliError::new(5, format!("Error: {}: {err}", run_log::log_path().display()))
+    })?;
+
+    let mut runs: Vec<&RunRecord> = records
+        .iter()
+        .rev()
+        .filter(|record| matches(record, &args, since.as_deref()))
+        .collect();
+    if args.limit > 0 {
+        runs.truncate(args.limit);
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&runs)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    if runs.is_empty() {
+        println!("No runs recorded.");
+        return Ok(());
+    }
+    let prompt_width = runs.iter().map(|record| prompt_label(record).len()).max().unwrap_or(0);
+    let runner_width = runs.iter().map(|record| record.runner.len()).max().unwrap_or(0);
+    for record in runs {
+        println!(
+            "{}  {:prompt_width$}  {:runner_width$}  {:7}  {}",
+            record.timestamp.get(..16).unwrap_or(&record.timestamp).replace('T', " "),
+            prompt_label(record),
+            record.runner,
+            status_label(record),
+            format_duration(record.duration_ms),
+        );
+    }
+    Ok(())
+}
+
+fn matches(record: &RunRecord, args: &RunsArgs, since: Option<&str>) -> bool {
+    if let Some(text) = &args.prompt
+        && !record.prompt.as_ref().is_some_and(|prompt| prompt.contains(text.as_str()))
+    {
+        return false;
+    }
+    if let Some(repo) = &args.repo
+        && record.repo.as_ref() != Some(repo)
+    {
+        return false;
+    }
+    if let Some(runner) = &args.runner
+        && &record.runner != runner
+    {
+        return false;
+    }
+    // Timestamps start with the local date, so they compare as text.
+    if let Some(since) = since
+        && record.timestamp.get(..10).is_none_or(|date| date < since)
+    {
+        return false;
+    }
+    !(args.failed && record.success)
+}
+
+fn prompt_label(record: &RunRecord) -> &str {
+    record.prompt.as_deref().unwrap_or("[session]")
+}
+
+fn status_label(record: &RunRecord) -> String {
+    match record.exit_code {
+        Some(0) => "ok".to_string(),
+        Some(code) => format!("exit {code}"),
+        None => "killed".to_string(),
+    }
+}
+
+/// `42s`, `3m12s`, or `1h05m`.
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
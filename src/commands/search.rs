@@ -0,0 +1,130 @@
+use clap::Args;
+use crossterm::style::Stylize;
+use std::io::IsTerminal;
+
+use crate::error::CliError;
+use crate::fs_utils::file_name_string;
+use crate::search::{self, FileResult, Filter, Hit, Query, Source};
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Text to look for in notes and prompts
+    pub query: String,
+    /// Treat the query as a regular expression
+    #[arg(short = 'e', long)]
+    pub regex: bool,
+    /// Match case even when the query is all lowercase
+    #[arg(short = 's', long)]
+    pub case_sensitive: bool,
+    /// Only search notes
+    #[arg(long, conflicts_with = "prompts")]
+    pub notes: bool,
+    /// Only search prompts
+    #[arg(long)]
+    pub prompts: bool,
+    /// Only notes of this kind: a periodic kind such as daily, scratch, or other
+    #[arg(long)]
+    pub kind: Option<String>,
+    /// Only prompts for this repo
+    #[arg(long)]
+    pub repo: Option<String>,
+    /// Only prompts in this category (feat or sop)
+    #[arg(long)]
+    pub category: Option<String>,
+    /// Only prompts for this feature (or SOP name)
+    #[arg(long)]
+    pub feature: Option<String>,
+    /// Only prompts of this type
+    #[arg(long = "type", value_name = "TYPE")]
+    pub doc_type: Option<String>,
+    /// Only prompts of this variant
+    #[arg(long)]
+    pub variant: Option<String>,
+    /// Show at most this many files, best match first (0 for all)
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: SearchArgs) -> Result<(), CliError> {
+    let query = Query::new(&args.query, args.regex, args.case_sensitive)
+        .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+    let source = if args.notes {
+        Some(Source::Note)
+    } else if args.prompts {
+        Some(Source::Prompt)
+    } else {
+        None
+    };
+    let filter = Filter {
+        source,
+        kind: args.kind,
+        repo: args.repo,
+        category: args.category,
+        feature: args.feature,
+        doc_type: args.doc_type,
+        variant: args.variant,
+    };
+
+    let kinds = crate::notes::periodic_kinds(&crate::notes::notes_dir());
+    let mut files = search::default_files();
+    files.retain(|(source, path)| {
+        file_name_string(path).is_some_and(|name| filter.matches(*source, &name, &kinds))
+    });
+    let mut results = search::search(&query, &files);
+    if args.limit > 0 {
+        results.truncate(args.limit);
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&results)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+    } else {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        for result in &results {
+            print_result(result, color);
+        }
+    }
+
+    if results.is_empty() {
+        return Err(CliError::new(1, format!("No matches for '{}'", args.query)));
+    }
+    Ok(())
+}
+
+/// `file:line: text` for each matching line, or just `file` when only the
+/// name matches.
+fn print_result(result: &FileResult, color: bool) {
+    let file = if color {
+        result.file.as_str().magenta().to_string()
+    } else {
+        result.file.clone()
+    };
+    if result.hits.is_empty() {
+        println!("{file}");
+    }
+    for hit in &result.hits {
+        let line = if color {
+            hit.line.to_string().green().to_string()
+        } else {
+            hit.line.to_string()
+        };
+        let text = if color { highlight(hit) } else { hit.text.clone() };
+        println!("{file}:{line}: {text}");
+    }
+}
+
+fn highlight(hit: &Hit) -> String {
+    let mut out = String::new();
+    let mut pos = 0;
+    for range in &hit.matches {
+        out.push_str(&hit.text[pos..range.start]);
+        out.push_str(&hit.text[range.clone()].red().bold().to_string());
+        pos = range.end;
+    }
+    out.push_str(&hit.text[pos..]);
+    out
+}
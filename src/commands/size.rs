@@ -0,0 +1,113 @@
+use clap::Args;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::error::CliError;
+use crate::fs_utils::{file_name_string, list_markdown_files};
+use crate::pattern::exempt_reason;
+use crate::size::{measure_file, FileSize, Size, Tokenizer};
+
+#[derive(Args, Debug)]
+pub struct SizeArgs {
+    /// Files to measure (defaults to the prompt files in the current directory)
+    pub files: Vec<PathBuf>,
+    /// Token estimate heuristic: chars or words (defaults to size.tokenizer in the config)
+    #[arg(long)]
+    pub tokenizer: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct SizeJson {
+    tokenizer: &'static str,
+    files: Vec<FileSize>,
+    total: Size,
+}
+
+pub fn run(args: SizeArgs) -> Result<(), CliError> {
+    let tokenizer = match &args.tokenizer {
+        Some(name) => Tokenizer::parse(name).map_err(|err| CliError::new(2, format!("Error: {err}")))?,
+        None => Tokenizer::from_config(&crate::global_config::load()),
+    };
+    let sizes = if args.files.is_empty() {
+        measure_dir(Path::new("."), tokenizer)?
+    } else {
+        args.files
+            .iter()
+            .map(|path| {
+                measure_file(path, tokenizer)
+                    .map_err(|err| CliError::new(5, format!("Error: {}: {err}", path.display())))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut total = Size::default();
+    let mut expanded_tokens = 0;
+    for entry in &sizes {
+        total.add(&entry.size);
+        expanded_tokens += entry.expanded.map_or(entry.size.tokens, |expanded| expanded.tokens);
+    }
+
+    if args.json {
+        let payload = SizeJson {
+            tokenizer: tokenizer.name(),
+            files: sizes,
+            total,
+        };
+        let json = serde_json::to_string_pretty(&payload)
+            .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let width = sizes
+        .iter()
+        .map(|entry| entry.file.len())
+        .chain(["File".len(), "Total".len()])
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:width$}  {:>8}  {:>7}  {:>6}  {:>7}  {:>8}",
+        "File", "Bytes", "Words", "Lines", "Tokens", "Expanded"
+    );
+    for entry in &sizes {
+        let expanded = match &entry.expanded {
+            Some(expanded) => expanded.tokens.to_string(),
+            None => "error".to_string(),
+        };
+        print_row(&entry.file, &entry.size, &expanded, width);
+    }
+    if sizes.len() > 1 {
+        print_row("Total", &total, &expanded_tokens.to_string(), width);
+    }
+    println!("\nTokens are estimated with the {} heuristic.", tokenizer.name());
+    Ok(())
+}
+
+/// Sizes of the non-exempt markdown files in `dir`.
+pub(crate) fn measure_dir(dir: &Path, tokenizer: Tokenizer) -> Result<Vec<FileSize>, CliError> {
+    let files = list_markdown_files(dir).map_err(|err| CliError::new(2, format!("Error: {err}")))?;
+    let mut sizes = Vec::new();
+    for path in files {
+        let Some(name) = file_name_string(&path) else {
+            continue;
+        };
+        if exempt_reason(&name).is_some() {
+            continue;
+        }
+        let mut entry = measure_file(&path, tokenizer)
+            .map_err(|err| CliError::new(5, format!("Error: {}: {err}", path.display())))?;
+        entry.file = name;
+        sizes.push(entry);
+    }
+    Ok(sizes)
+}
+
+fn print_row(file: &str, size: &Size, expanded: &str, width: usize) {
+    println!(
+        "{file:width$}  {:>8}  {:>7}  {:>6}  {:>7}  {expanded:>8}",
+        size.bytes, size.words, size.lines, size.tokens
+    );
+}
@@ -3,9 +3,11 @@ use serde::Serialize;
 use std::collections::BTreeMap;
 use std::path::Path;
 
+use crate::commands::size::measure_dir;
 use crate::error::CliError;
 use crate::fs_utils::{file_name_string, list_markdown_files};
 use crate::pattern::{exempt_reason, parse_filename, ParsedFilename};
+use crate::size::{FileSize, Tokenizer};
 
 #[derive(Args, Debug)]
 pub struct StatsArgs {
@@ -24,8 +26,20 @@ struct StatsJson {
     by_category: BTreeMap<String, usize>,
     by_type: BTreeMap<String, usize>,
     by_variant: BTreeMap<String, usize>,
+    most_run: Vec<PromptRuns>,
+    never_run: Vec<String>,
+    sizes: Vec<FileSize>,
 }
 
+#[derive(Serialize)]
+struct PromptRuns {
+    prompt: String,
+    runs: usize,
+}
+
+/// How many prompts "Most run" lists.
+const MOST_RUN_LIMIT: usize = 5;
+
 pub fn run(args: StatsArgs) -> Result<(), CliError> {
     let files = list_markdown_files(Path::new("."))
         .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
@@ -37,6 +51,7 @@ pub fn run(args: StatsArgs) -> Result<(), CliError> {
     let mut by_category = BTreeMap::new();
     let mut by_type = BTreeMap::new();
     let mut by_variant = BTreeMap::new();
+    let mut prompts = Vec::new();
 
     for path in files {
         let Some(name) = file_name_string(&path) else {
@@ -54,6 +69,7 @@ pub fn run(args: StatsArgs) -> Result<(), CliError> {
             }
         };
         valid += 1;
+        prompts.push(name);
         *by_repo.entry(parsed.repo().to_string()).or_insert(0) += 1;
         *by_category.entry(parsed.category().to_string()).or_insert(0) += 1;
 
@@ -65,6 +81,27 @@ pub fn run(args: StatsArgs) -> Result<(), CliError> {
 
     let total = valid + invalid + exempt;
 
+    // A missing or unreadable run log just means nothing has run yet.
+    let records = crate::run_log::load().unwrap_or_default();
+    let mut run_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for prompt in records.iter().filter_map(|record| record.prompt.clone()) {
+        *run_counts.entry(prompt).or_insert(0) += 1;
+    }
+    let mut most_run: Vec<PromptRuns> = run_counts
+        .iter()
+        .map(|(prompt, runs)| PromptRuns {
+            prompt: prompt.clone(),
+            runs: *runs,
+        })
+        .collect();
+    most_run.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.prompt.cmp(&b.prompt)));
+    most_run.truncate(MOST_RUN_LIMIT);
+    prompts.sort();
+    let never_run: Vec<String> = prompts
+        .into_iter()
+        .filter(|prompt| !run_counts.contains_key(prompt))
+        .collect();
+
     if args.json {
         let payload = StatsJson {
             total,
@@ -75,6 +112,9 @@ pub fn run(args: StatsArgs) -> Result<(), CliError> {
             by_category,
             by_type,
             by_variant,
+            most_run,
+            never_run,
+            sizes: measure_dir(Path::new("."), Tokenizer::from_config(&crate::global_config::load()))?,
         };
         let json = serde_json::to_string_pretty(&payload)
             .map_err(|err| CliError::new(2, format!("Error: {err}")))?;
@@ -94,6 +134,11 @@ pub fn run(args: StatsArgs) -> Result<(), CliError> {
     print_map("By type", &by_type);
     print_map("By variant", &by_variant);
 
+    // Without any runs, every prompt would be listed as never run.
+    if !records.is_empty() {
+        print_runs(&most_run, &never_run);
+    }
+
     Ok(())
 }
 
@@ -118,3 +163,21 @@ fn print_map(title: &str, map: &BTreeMap<String, usize>) {
     }
     println!();
 }
+
+fn print_runs(most_run: &[PromptRuns], never_run: &[String]) {
+    if !most_run.is_empty() {
+        println!("Most run:");
+        let width = most_run.iter().map(|entry| entry.prompt.len()).max().unwrap_or(0);
+        for entry in most_run {
+            println!("  {:width$}: {} runs", entry.prompt, entry.runs);
+        }
+        println!();
+    }
+    if !never_run.is_empty() {
+        println!("Never run:");
+        for prompt in never_run {
+            println!("  {prompt}");
+        }
+        println!();
+    }
+}
@@ -0,0 +1,102 @@
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::commands::refactor::{
+    execute_and_report, in_git_repo, rename_dirs, resolve_method_override, RenameMethod,
+};
+use crate::error::CliError;
+use crate::history::{self, EntryStatus, HistoryEntry};
+use crate::refactor::ContentEdit;
+
+#[derive(Args, Debug)]
+pub struct UndoArgs {
+    /// History id to undo (defaults to the most recent operation)
+    pub id: Option<u64>,
+    /// Use git mv for renames
+    #[arg(long, conflicts_with = "no_git")]
+    pub git: bool,
+    /// Use mv for renames even in git repo
+    #[arg(long)]
+    pub no_git: bool,
+    /// Overwrite existing files
+    #[arg(long)]
+    pub force: bool,
+    /// Operation root holding the history (defaults to the current directory)
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub root: PathBuf,
+}
+
+pub fn run(args: UndoArgs) -> Result<(), CliError> {
+    undo(&args.root, args.id, args.git, args.no_git, args.force)
+}
+
+pub(crate) fn undo(
+    root: &Path,
+    id: Option<u64>,
+    git: bool,
+    no_git: bool,
+    force: bool,
+) -> Result<(), CliError> {
+    let mut log = history::load(root).map_err(|err| CliError::new(5, err))?;
+
+    let entry = match id {
+        Some(id) => {
+            let entry = log
+                .get(id)
+                .ok_or_else(|| CliError::new(2, format!("Error: No operation with id {id} in history")))?;
+            if entry.status == EntryStatus::Undone {
+                return Err(CliError::new(
+                    2,
+                    format!("Error: Operation {id} has already been undone"),
+                ));
+            }
+            entry.clone()
+        }
+        None => log
+            .last_applied()
+            .cloned()
+            .ok_or_else(|| CliError::new(5, "Error: Nothing to undo".to_string()))?,
+    };
+
+    let reversed = entry.reversed();
+    let checks: Vec<(&str, &str)> = entry
+        .edits
+        .iter()
+        .map(|edit| (edit.file.as_str(), edit.after.as_str()))
+        .collect();
+    history::verify_contents(root, &checks).map_err(|err| CliError::new(3, err))?;
+    history::verify_renames(root, &reversed, force).map_err(|err| CliError::new(3, err))?;
+    let restores: Vec<ContentEdit> = entry
+        .edits
+        .iter()
+        .map(|edit| ContentEdit {
+            file: entry.original_path(&edit.file).to_string(),
+            before: edit.after.clone(),
+            after: edit.before.clone(),
+        })
+        .collect();
+
+    println!(
+        "Undoing #{} ({}, {} files)...",
+        entry.id,
+        entry.command,
+        entry.renames.len()
+    );
+    let dirs = rename_dirs(root, &reversed);
+    let method = resolve_method_override(git, no_git, &dirs)?
+        .unwrap_or_else(|| recorded_method(&entry, &dirs));
+    execute_and_report(root, &reversed, &restores, method, force, None)?;
+
+    log.mark_undone(entry.id);
+    history::save(root, &log).map_err(|err| CliError::new(5, format!("Error: {err}")))
+}
+
+/// The method an entry was recorded with, falling back to mv when the
+/// directories are no longer in a git repository.
+pub(crate) fn recorded_method(entry: &HistoryEntry, dirs: &[PathBuf]) -> RenameMethod {
+    if entry.method == RenameMethod::Git.name() && in_git_repo(dirs) {
+        RenameMethod::Git
+    } else {
+        RenameMethod::Fs
+    }
+}
@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
 use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
@@ -7,6 +8,74 @@ use std::path::Path;
 pub struct Config {
     #[serde(default)]
     pub schemas: BTreeMap<String, String>,
+    /// Periodic note kinds by name, merged over the built-in daily, weekly,
+    /// and monthly notes.
+    #[serde(default, deserialize_with = "skip_invalid")]
+    pub periodic: BTreeMap<String, PeriodicConfig>,
+    /// Carry-over rules by the periodic note kind they fill.
+    #[serde(default, deserialize_with = "skip_invalid")]
+    pub carry_over: BTreeMap<String, CarryOverConfig>,
+}
+
+/// Deserializes a map, leaving out the entries that don't fit `T` so that
+/// one bad table doesn't take the rest of the file with it. `config check`
+/// reports the entries left out.
+pub(crate) fn skip_invalid<'de, D, T>(deserializer: D) -> Result<BTreeMap<String, T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let tables = BTreeMap::<String, toml::Value>::deserialize(deserializer)?;
+    Ok(tables
+        .into_iter()
+        .filter_map(|(name, table)| Some((name, table.try_into().ok()?)))
+        .collect())
+}
+
+/// Deserializes a value, falling back to the default when it has the wrong
+/// type, so that one bad setting doesn't take the rest of the file with it.
+pub(crate) fn or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = toml::Value::deserialize(deserializer)?;
+    Ok(value.try_into().unwrap_or_default())
+}
+
+/// A `[carry_over.<kind>]` table: where a new periodic note collects
+/// unfinished tasks from.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CarryOverConfig {
+    /// Kind to collect from. The same kind carries from the most recent
+    /// earlier note; another kind rolls up every note of the previous period.
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Only collect tasks under this heading.
+    #[serde(default)]
+    pub heading: Option<String>,
+    /// Heading of the section the tasks are added to.
+    #[serde(default = "default_carry_section")]
+    pub section: String,
+}
+
+fn default_carry_section() -> String {
+    "Carried over".to_string()
+}
+
+/// A `[periodic.<name>]` table in `axon.toml`.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct PeriodicConfig {
+    /// chrono format of the filename without `.md`, e.g. `weekly.%G-W%V`.
+    pub format: String,
+    /// One of `day`, `week`, `month`, `quarter`, `year`.
+    pub period: String,
+    /// First day of the week for `week` periods (defaults to monday).
+    #[serde(default)]
+    pub week_start: Option<String>,
+    /// Key opening this kind in the TUI and alias of its CLI command.
+    #[serde(default)]
+    pub key: Option<String>,
 }
 
 pub fn load_config(notes_dir: &Path) -> Config {
@@ -18,23 +87,19 @@ pub fn load_config(notes_dir: &Path) -> Config {
 }
 
 impl Config {
-    pub fn resolve_schema(&self, notes_dir: &Path, filename: &str) -> Option<String> {
-        let schema_file = self
-            .schemas
+    /// The schema file of the first pattern matching `filename`.
+    pub fn schema_file(&self, filename: &str) -> Option<&str> {
+        self.schemas
             .iter()
             .find(|(pattern, _)| glob_match(pattern, filename))
-            .map(|(_, schema)| schema)?;
-
-        let content = fs::read_to_string(notes_dir.join(schema_file)).ok()?;
-
-        // Extract date from filename: "weekly.2026.02.23.md" -> "2026.02.23"
-        let date = filename
-            .strip_suffix(".md")
-            .and_then(|s| s.split_once('.'))
-            .map(|(_, d)| d)
-            .unwrap_or("");
+            .map(|(_, schema)| schema.as_str())
+    }
 
-        Some(content.replace("{{date}}", date))
+    /// The unrendered schema for `filename`; see `notes::schema_context` for
+    /// the variables it is rendered with.
+    pub fn resolve_schema(&self, notes_dir: &Path, filename: &str) -> Option<String> {
+        let schema_file = self.schema_file(filename)?;
+        fs::read_to_string(notes_dir.join(schema_file)).ok()
     }
 }
 
@@ -65,10 +130,11 @@ pub fn glob_match(pattern: &str, value: &str) -> bool {
     }
 
     // Last segment must match at end
-    if let Some(last) = parts.last() {
-        if !last.is_empty() && !value.ends_with(last) {
-            return false;
-        }
+    if let Some(last) = parts.last()
+        && !last.is_empty()
+        && !value.ends_with(last)
+    {
+        return false;
     }
 
     true
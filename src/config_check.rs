@@ -0,0 +1,644 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use toml_edit::{ImDocument, Item, TableLike};
+
+use crate::config::{CarryOverConfig, Config, PeriodicConfig};
+use crate::links::edit_distance;
+use crate::global_config::{GlobalConfig, Origin, RunnerConfig};
+use crate::notes::PeriodicKind;
+use crate::opener::Opener;
+use crate::runner::{Runner, DEFAULT_RUNNER};
+use crate::pattern::ParsedFilename;
+use crate::picker::Backend;
+use crate::size::Tokenizer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The setting is dropped when the config is loaded.
+    Error,
+    /// The setting loads but probably doesn't do what was meant.
+    Warning,
+}
+
+/// A problem found in a config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Issue {
+    pub file: PathBuf,
+    /// 1-based line and column, when the problem has a location.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, ":{line}:{column}")?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, ": {severity}: {}", self.message)
+    }
+}
+
+/// Expected shape of a config value.
+enum Shape {
+    Str,
+    Int,
+    /// A table with these keys.
+    Table(&'static [(&'static str, Shape)]),
+    /// A table of arbitrary keys whose values share one shape.
+    Map(&'static Shape),
+}
+
+const PERIODIC: Shape = Shape::Table(&[
+    ("format", Shape::Str),
+    ("period", Shape::Str),
+    ("week_start", Shape::Str),
+    ("key", Shape::Str),
+]);
+
+const CARRY_OVER: Shape = Shape::Table(&[
+    ("from", Shape::Str),
+    ("heading", Shape::Str),
+    ("section", Shape::Str),
+]);
+
+const RUNNER: Shape = Shape::Table(&[
+    ("command", Shape::Str),
+    ("args", Shape::Str),
+    ("cwd", Shape::Str),
+    ("env", Shape::Map(&Shape::Str)),
+]);
+
+const SIZE: Shape = Shape::Table(&[
+    ("tokenizer", Shape::Str),
+    ("max_tokens", Shape::Map(&Shape::Int)),
+]);
+
+/// `<notes_dir>/axon.toml`.
+const NOTES_CONFIG: Shape = Shape::Table(&[
+    ("schemas", Shape::Map(&Shape::Str)),
+    ("periodic", Shape::Map(&PERIODIC)),
+    ("carry_over", Shape::Map(&CARRY_OVER)),
+]);
+
+/// `~/.config/axon/config.toml`.
+const GLOBAL_CONFIG: Shape = Shape::Table(&[
+    ("notes_dir", Shape::Str),
+    ("prompts_dir", Shape::Str),
+    ("opener", Shape::Str),
+    ("picker", Shape::Str),
+    ("runner", Shape::Str),
+    ("runners", Shape::Map(&RUNNER)),
+    ("repos", Shape::Map(&Shape::Str)),
+    ("size", SIZE),
+]);
+
+/// Which config file a setting lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFile {
+    /// `~/.config/axon/config.toml`
+    Global,
+    /// `<notes_dir>/axon.toml`
+    Notes,
+}
+
+impl ConfigFile {
+    pub fn path(self) -> PathBuf {
+        match self {
+            ConfigFile::Global => crate::global_config::config_path(),
+            ConfigFile::Notes => crate::notes::notes_dir().join("axon.toml"),
+        }
+    }
+
+    /// Checks `source` as the contents of this file.
+    pub fn check(self, source: &str) -> Vec<Issue> {
+        match self {
+            ConfigFile::Global => check_global_source(&self.path(), source),
+            ConfigFile::Notes => check_notes_source(&crate::notes::notes_dir(), source),
+        }
+    }
+}
+
+/// The file holding the string or integer setting at `path`, e.g.
+/// `["periodic", "yearly", "format"]`.
+pub fn setting_file(path: &[&str]) -> Result<ConfigFile, String> {
+    [
+        (ConfigFile::Global, &GLOBAL_CONFIG),
+        (ConfigFile::Notes, &NOTES_CONFIG),
+    ]
+    .into_iter()
+    .find(|(_, shape)| matches!(setting_shape(shape, path), Some(Shape::Str | Shape::Int)))
+    .map(|(file, _)| file)
+    .ok_or_else(|| format!("unknown setting `{}`", path.join(".")))
+}
+
+/// Whether the setting at `path` holds an integer rather than a string.
+pub fn is_integer_setting(path: &[&str]) -> bool {
+    [&GLOBAL_CONFIG, &NOTES_CONFIG]
+        .into_iter()
+        .any(|shape| matches!(setting_shape(shape, path), Some(Shape::Int)))
+}
+
+fn setting_shape<'a>(shape: &'a Shape, path: &[&str]) -> Option<&'a Shape> {
+    match (shape, path.split_first()) {
+        (Shape::Str | Shape::Int, None) => Some(shape),
+        (Shape::Table(fields), Some((key, rest))) => fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .and_then(|(_, field)| setting_shape(field, rest)),
+        (Shape::Map(values), Some((_, rest))) => setting_shape(values, rest),
+        _ => None,
+    }
+}
+
+/// A config file being checked.
+struct Checked<'a> {
+    path: &'a Path,
+    source: &'a str,
+    issues: Vec<Issue>,
+}
+
+impl Checked<'_> {
+    fn push(&mut self, offset: Option<usize>, severity: Severity, message: String) {
+        let (line, column) = match offset {
+            Some(offset) => {
+                let (line, column) = line_column(self.source, offset);
+                (Some(line), Some(column))
+            }
+            None => (None, None),
+        };
+        self.issues.push(Issue {
+            file: self.path.to_path_buf(),
+            line,
+            column,
+            severity,
+            message,
+        });
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// Checks the global config file, if it exists.
+pub fn check_global() -> Vec<Issue> {
+    let path = crate::global_config::config_path();
+    let Ok(source) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    check_global_source(&path, &source)
+}
+
+/// Checks `source` as the global config file at `path`.
+pub fn check_global_source(path: &Path, source: &str) -> Vec<Issue> {
+    let path = path.to_path_buf();
+    let mut checked = Checked {
+        path: &path,
+        source,
+        issues: Vec::new(),
+    };
+    let Some(doc) = parse(&mut checked, &GLOBAL_CONFIG) else {
+        return checked.issues;
+    };
+    let cfg = match toml::from_str::<GlobalConfig>(source) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            deserialize_error(&mut checked, &err);
+            return checked.issues;
+        }
+    };
+    if let Ok(tables) = toml::from_str::<toml::Table>(source) {
+        check_skipped::<RunnerConfig>(&mut checked, &doc, &tables, "runners", &cfg.runners);
+    }
+    // Any other value of the wrong type is dropped on its own.
+    for issue in &mut checked.issues {
+        if issue.severity == Severity::Error && !issue.message.ends_with(" ignored)") {
+            issue.message.push_str(" (the setting is ignored)");
+        }
+    }
+    if let Some(opener) = &cfg.opener
+        && let Err(err) = Opener::parse(opener, Origin::File(path.clone()))
+    {
+        checked.push(path_offset(&doc, &["opener"]), Severity::Error, err);
+    }
+    if let Some(picker) = &cfg.picker
+        && let Err(err) = Backend::parse(picker)
+    {
+        checked.push(path_offset(&doc, &["picker"]), Severity::Error, err);
+    }
+    check_runners(&mut checked, &doc, &cfg);
+    check_size(&mut checked, &doc, &cfg);
+    for repo in cfg.repos.keys() {
+        if cfg.repo_dir(repo).is_some_and(|dir| !dir.is_dir()) {
+            checked.push(
+                path_offset(&doc, &["repos", repo]),
+                Severity::Warning,
+                format!("repos.{repo}: directory `{}` does not exist", cfg.repos[repo]),
+            );
+        }
+    }
+    checked.issues.sort_by_key(|issue| (issue.line, issue.column));
+    checked.issues
+}
+
+fn check_runners(checked: &mut Checked, doc: &ImDocument<String>, cfg: &GlobalConfig) {
+    for (name, runner) in &cfg.runners {
+        if let Err(err) = Runner::from_config(name, runner) {
+            checked.push(path_offset(doc, &["runners", name]), Severity::Error, err);
+        }
+        if let Some(cwd) = &runner.cwd
+            && !crate::global_config::expand_tilde(cwd).is_dir()
+        {
+            checked.push(
+                path_offset(doc, &["runners", name, "cwd"]),
+                Severity::Warning,
+                format!("runners.{name}: working directory `{cwd}` does not exist"),
+            );
+        }
+    }
+    if let Some(name) = &cfg.runner
+        && name != DEFAULT_RUNNER
+        && !cfg.runners.contains_key(name)
+    {
+        checked.push(
+            path_offset(doc, &["runner"]),
+            Severity::Error,
+            format!("`runner` is '{name}', but there is no [runners.{name}] table"),
+        );
+    }
+}
+
+fn check_size(checked: &mut Checked, doc: &ImDocument<String>, cfg: &GlobalConfig) {
+    if let Some(name) = &cfg.size.tokenizer
+        && let Err(err) = Tokenizer::parse(name)
+    {
+        checked.push(path_offset(doc, &["size", "tokenizer"]), Severity::Error, err);
+    }
+    for (category, max) in &cfg.size.max_tokens {
+        let offset = path_offset(doc, &["size", "max_tokens", category]);
+        if *max <= 0 {
+            checked.push(
+                offset,
+                Severity::Error,
+                format!("size.max_tokens.{category} must be positive, not {max}"),
+            );
+        }
+        if !ParsedFilename::CATEGORIES.contains(&category.as_str()) {
+            checked.push(
+                offset,
+                Severity::Warning,
+                format!(
+                    "size.max_tokens.{category}: no prompt has category '{category}' (expected {})",
+                    ParsedFilename::CATEGORIES.join(" or ")
+                ),
+            );
+        }
+    }
+}
+
+/// Checks `<notes_dir>/axon.toml`, if it exists: syntax, unknown keys,
+/// schema files, overlapping schema patterns, and periodic note tables.
+pub fn check_notes(notes_dir: &Path) -> Vec<Issue> {
+    let path = notes_dir.join("axon.toml");
+    let Ok(source) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    check_notes_source(notes_dir, &source)
+}
+
+/// Checks `source` as the `axon.toml` of `notes_dir`.
+pub fn check_notes_source(notes_dir: &Path, source: &str) -> Vec<Issue> {
+    let path = notes_dir.join("axon.toml");
+    let mut checked = Checked {
+        path: &path,
+        source,
+        issues: Vec::new(),
+    };
+    let Some(doc) = parse(&mut checked, &NOTES_CONFIG) else {
+        return checked.issues;
+    };
+    let cfg = match toml::from_str::<Config>(source) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            deserialize_error(&mut checked, &err);
+            return checked.issues;
+        }
+    };
+
+    if let Ok(tables) = toml::from_str::<toml::Table>(source) {
+        check_skipped::<PeriodicConfig>(&mut checked, &doc, &tables, "periodic", &cfg.periodic);
+        check_skipped::<CarryOverConfig>(&mut checked, &doc, &tables, "carry_over", &cfg.carry_over);
+    }
+    check_schemas(&mut checked, &doc, &cfg, notes_dir);
+    check_periodic(&mut checked, &doc, &cfg);
+    checked.issues.sort_by_key(|issue| (issue.line, issue.column));
+    checked.issues
+}
+
+/// The issues in every config file axon reads.
+pub fn check_all() -> Vec<Issue> {
+    let mut issues = check_global();
+    issues.extend(check_notes(&crate::notes::notes_dir()));
+    issues
+}
+
+/// Config errors and unknown keys: the issues that leave a setting
+/// silently ignored, and so are worth mentioning outside `axon config check`.
+pub fn ignored_settings() -> Vec<Issue> {
+    check_all()
+        .into_iter()
+        .filter(|issue| issue.severity == Severity::Error || issue.message.starts_with("unknown key"))
+        .collect()
+}
+
+/// Prints a warning for every ignored setting, pointing at `axon config check`.
+pub fn warn_invalid() {
+    let issues = ignored_settings();
+    if issues.is_empty() {
+        return;
+    }
+    for issue in &issues {
+        eprintln!("Warning: {issue}");
+    }
+    eprintln!("Invalid settings and unknown keys are ignored. Run `axon config check` for details.");
+}
+
+fn parse(checked: &mut Checked, shape: &Shape) -> Option<ImDocument<String>> {
+    match ImDocument::parse(checked.source.to_string()) {
+        Ok(doc) => {
+            walk(checked, doc.as_item(), shape, "");
+            Some(doc)
+        }
+        Err(err) => {
+            let offset = err.span().map(|span| span.start);
+            let message = format!("{} (the whole file is ignored)", error_message(err.message()));
+            checked.push(offset, Severity::Error, message);
+            None
+        }
+    }
+}
+
+/// Reports a file that fails to load, which makes axon fall back to the
+/// defaults for every setting in it.
+fn deserialize_error(checked: &mut Checked, err: &toml::de::Error) {
+    // The structural walk usually reported the value at fault already.
+    if !checked.issues.iter().any(|issue| issue.severity == Severity::Error) {
+        let offset = err.span().map(|span| span.start);
+        checked.push(offset, Severity::Error, error_message(err.message()));
+    }
+    if let Some(issue) = checked
+        .issues
+        .iter_mut()
+        .find(|issue| issue.severity == Severity::Error)
+    {
+        issue.message.push_str(" (the whole file is ignored)");
+    }
+}
+
+/// Reports the `[<section>.<name>]` tables that were left out of `loaded`
+/// because they don't deserialize, such as one missing a required field.
+fn check_skipped<T: serde::de::DeserializeOwned>(
+    checked: &mut Checked,
+    doc: &ImDocument<String>,
+    tables: &toml::Table,
+    section: &str,
+    loaded: &BTreeMap<String, T>,
+) {
+    let Some(toml::Value::Table(section_tables)) = tables.get(section) else {
+        return;
+    };
+    for (name, table) in section_tables {
+        if loaded.contains_key(name) {
+            continue;
+        }
+        let path = format!("`{section}.{name}");
+        let in_table = |message: &str| {
+            message
+                .strip_prefix(&path)
+                .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('`'))
+        };
+        // The structural walk usually reported the value at fault already.
+        if let Some(issue) = checked
+            .issues
+            .iter_mut()
+            .find(|issue| issue.severity == Severity::Error && in_table(&issue.message))
+        {
+            issue.message.push_str(" (the table is ignored)");
+            continue;
+        }
+        let reason = match table.clone().try_into::<T>() {
+            Ok(_) => continue,
+            Err(err) => error_message(err.message()),
+        };
+        checked.push(
+            path_offset(doc, &[section, name]),
+            Severity::Error,
+            format!("{section}.{name}: {reason} (the table is ignored)"),
+        );
+    }
+}
+
+/// Joins a multi-line parser message into one line.
+fn error_message(message: &str) -> String {
+    let lines: Vec<&str> = message.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    lines.join(", ")
+}
+
+/// Reports values of the wrong type and keys `shape` doesn't know.
+fn walk(checked: &mut Checked, item: &Item, shape: &Shape, path: &str) {
+    let offset = item.span().map(|span| span.start);
+    match shape {
+        Shape::Str => {
+            if !item.is_str() {
+                checked.push(offset, Severity::Error, format!("`{path}` must be a string"));
+            }
+        }
+        Shape::Int => {
+            if !item.is_integer() {
+                checked.push(offset, Severity::Error, format!("`{path}` must be an integer"));
+            }
+        }
+        Shape::Table(fields) => {
+            let Some(table) = table_like(checked, item, path) else {
+                return;
+            };
+            for (key, value) in table.iter() {
+                let child = join(path, key);
+                match fields.iter().find(|(name, _)| *name == key) {
+                    Some((_, field)) => walk(checked, value, field, &child),
+                    None => {
+                        let mut message = format!("unknown key `{child}`");
+                        let closest = fields
+                            .iter()
+                            .map(|(name, _)| (edit_distance(key, name), *name))
+                            .min();
+                        if let Some((distance, name)) = closest
+                            && distance <= 2
+                        {
+                            message.push_str(&format!(" (did you mean `{name}`?)"));
+                        }
+                        checked.push(key_offset(table, key), Severity::Warning, message);
+                    }
+                }
+            }
+        }
+        Shape::Map(values) => {
+            let Some(table) = table_like(checked, item, path) else {
+                return;
+            };
+            for (key, value) in table.iter() {
+                walk(checked, value, values, &join(path, key));
+            }
+        }
+    }
+}
+
+fn table_like<'a>(checked: &mut Checked, item: &'a Item, path: &str) -> Option<&'a dyn TableLike> {
+    let table = item.as_table_like();
+    if table.is_none() {
+        let offset = item.span().map(|span| span.start);
+        checked.push(offset, Severity::Error, format!("`{path}` must be a table"));
+    }
+    table
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn key_offset(table: &dyn TableLike, key: &str) -> Option<usize> {
+    let (key, _) = table.get_key_value(key)?;
+    key.span().map(|span| span.start)
+}
+
+/// Offset of the key at `path`, e.g. `["schemas", "daily.*"]`.
+fn path_offset(doc: &ImDocument<String>, path: &[&str]) -> Option<usize> {
+    let (last, parents) = path.split_last()?;
+    let mut table: &dyn TableLike = doc.as_table();
+    for key in parents {
+        table = table.get(key)?.as_table_like()?;
+    }
+    key_offset(table, last)
+}
+
+fn check_schemas(checked: &mut Checked, doc: &ImDocument<String>, cfg: &Config, notes_dir: &Path) {
+    for (pattern, schema) in &cfg.schemas {
+        if !notes_dir.join(schema).is_file() {
+            checked.push(
+                path_offset(doc, &["schemas", pattern]),
+                Severity::Warning,
+                format!("schema file `{schema}` for `{pattern}` does not exist"),
+            );
+        }
+    }
+
+    // Patterns are tried in sorted order, not the order they are written in.
+    let patterns: Vec<&String> = cfg.schemas.keys().collect();
+    for (idx, later) in patterns.iter().enumerate() {
+        if let Some(earlier) = patterns[..idx]
+            .iter()
+            .find(|earlier| globs_overlap(earlier, later))
+        {
+            checked.push(
+                path_offset(doc, &["schemas", later]),
+                Severity::Warning,
+                format!(
+                    "`{later}` overlaps `{earlier}`, which is tried first (patterns are tried in sorted order)"
+                ),
+            );
+        }
+    }
+}
+
+/// Whether some filename matches both `*` patterns.
+pub fn globs_overlap(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut memo = BTreeMap::new();
+    overlap_from(&a, &b, 0, 0, &mut memo)
+}
+
+fn overlap_from(
+    a: &[char],
+    b: &[char],
+    i: usize,
+    j: usize,
+    memo: &mut BTreeMap<(usize, usize), bool>,
+) -> bool {
+    if let Some(result) = memo.get(&(i, j)) {
+        return *result;
+    }
+    let result = match (a.get(i), b.get(j)) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            overlap_from(a, b, i + 1, j, memo) || (j < b.len() && overlap_from(a, b, i, j + 1, memo))
+        }
+        (_, Some('*')) => {
+            overlap_from(a, b, i, j + 1, memo) || (i < a.len() && overlap_from(a, b, i + 1, j, memo))
+        }
+        (Some(x), Some(y)) => x == y && overlap_from(a, b, i + 1, j + 1, memo),
+        _ => false,
+    };
+    memo.insert((i, j), result);
+    result
+}
+
+fn check_periodic(checked: &mut Checked, doc: &ImDocument<String>, cfg: &Config) {
+    let mut names: Vec<String> = PeriodicKind::builtin()
+        .into_iter()
+        .map(|kind| kind.name)
+        .collect();
+    for (name, table) in &cfg.periodic {
+        match PeriodicKind::from_config(name, table) {
+            Ok(_) => {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+            Err(err) => checked.push(
+                path_offset(doc, &["periodic", name]),
+                Severity::Error,
+                err,
+            ),
+        }
+    }
+
+    for (name, rule) in &cfg.carry_over {
+        if !names.contains(name) {
+            checked.push(
+                path_offset(doc, &["carry_over", name]),
+                Severity::Warning,
+                format!("carry_over.{name}: unknown periodic note kind `{name}`"),
+            );
+        }
+        if let Some(from) = &rule.from
+            && !names.contains(from)
+        {
+            checked.push(
+                path_offset(doc, &["carry_over", name, "from"]),
+                Severity::Warning,
+                format!("carry_over.{name}: unknown periodic note kind `{from}`"),
+            );
+        }
+    }
+}
+
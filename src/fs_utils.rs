@@ -1,6 +1,8 @@
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 pub fn list_markdown_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
     let mut files = Vec::new();
@@ -24,3 +26,56 @@ pub fn file_name_string(path: &Path) -> Option<String> {
         .and_then(|name| name.to_str())
         .map(|s| s.to_string())
 }
+
+/// The deepest directory containing every path. Paths should be absolute
+/// (or all relative to the same base).
+pub fn common_ancestor(paths: &[PathBuf]) -> Option<PathBuf> {
+    let (first, rest) = paths.split_first()?;
+    let mut ancestor = first.clone();
+    for path in rest {
+        while !path.starts_with(&ancestor) {
+            if !ancestor.pop() {
+                return None;
+            }
+        }
+    }
+    Some(ancestor)
+}
+
+/// Whether `dir` is on a case-insensitive filesystem, probed by creating a
+/// lowercase file and looking it up in uppercase. The answer is cached per
+/// directory; a directory that can't be written to counts as case-sensitive
+/// and is probed again next time.
+pub fn is_case_insensitive(dir: &Path) -> bool {
+    static PROBED: OnceLock<Mutex<HashMap<PathBuf, bool>>> = OnceLock::new();
+    let probed = PROBED.get_or_init(Default::default);
+    if let Some(folds) = probed.lock().unwrap().get(dir) {
+        return *folds;
+    }
+
+    let name = format!(".axon-case-probe-{}", std::process::id());
+    let probe = dir.join(&name);
+    if std::fs::write(&probe, b"").is_err() {
+        return false;
+    }
+    let folds = dir.join(name.to_uppercase()).exists();
+    let _ = std::fs::remove_file(&probe);
+    probed.lock().unwrap().insert(dir.to_path_buf(), folds);
+    folds
+}
+
+/// Renames through a temporary name. A case-only rename on a
+/// case-insensitive filesystem may otherwise be a no-op.
+pub fn rename_via_temp(from: &Path, to: &Path) -> io::Result<()> {
+    let name = to
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = to.with_file_name(format!(".{name}.axon-tmp-{}", std::process::id()));
+    std::fs::rename(from, &temp)?;
+    if let Err(err) = std::fs::rename(&temp, to) {
+        let _ = std::fs::rename(&temp, from);
+        return Err(err);
+    }
+    Ok(())
+}
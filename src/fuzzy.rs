@@ -0,0 +1,91 @@
+/// Scores how well `query` matches `candidate`, fzf style: the characters
+/// of every whitespace-separated term must appear in order (not necessarily
+/// contiguously) in the candidate, ignoring case. Terms match independently,
+/// in any order and possibly overlapping. Consecutive characters and
+/// matches at the start of a segment (after `.`, `-`, `_`, `/`, or a space)
+/// score higher; gaps score lower. Returns `None` when a term doesn't match.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    find(query, candidate).map(|(score, _)| score)
+}
+
+/// Like [`score`], also returning the positions (in chars) of the
+/// candidate's matched characters, sorted, for highlighting.
+pub fn find(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let candidate = lowercase_chars(candidate);
+    let mut total = 0;
+    let mut positions = Vec::new();
+    for term in query.split_whitespace() {
+        let (score, matched) = best_start(&lowercase_chars(term), &candidate)?;
+        total += score;
+        positions.extend(matched);
+    }
+    positions.sort_unstable();
+    positions.dedup();
+    Some((total, positions))
+}
+
+/// A match of one of [`rank`]'s candidates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Index into the candidates.
+    pub index: usize,
+    /// Matched character positions, as from [`find`].
+    pub positions: Vec<usize>,
+}
+
+/// The candidates matching `query`, best first; equally good ones keep
+/// their order. An empty query matches everything.
+pub fn rank(query: &str, candidates: &[String]) -> Vec<Match> {
+    let mut scored: Vec<(i64, Match)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            let (score, positions) = find(query, candidate)?;
+            Some((score, Match { index, positions }))
+        })
+        .collect();
+    scored.sort_by_key(|(score, found)| (std::cmp::Reverse(*score), found.index));
+    scored.into_iter().map(|(_, found)| found).collect()
+}
+
+/// Lowercases one char at a time, so positions line up with the original.
+fn lowercase_chars(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// The best greedy match of `term`, trying every occurrence of its first
+/// character as the starting point.
+fn best_start(term: &[char], candidate: &[char]) -> Option<(i64, Vec<usize>)> {
+    let first = *term.first()?;
+    candidate
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| **c == first)
+        .filter_map(|(start, _)| match_from(term, candidate, start))
+        .max_by_key(|(score, matched)| (*score, std::cmp::Reverse(matched[0])))
+}
+
+fn match_from(term: &[char], candidate: &[char], start: usize) -> Option<(i64, Vec<usize>)> {
+    let mut matched = Vec::with_capacity(term.len());
+    let mut pos = start;
+    for c in term {
+        let offset = candidate[pos..].iter().position(|other| other == c)?;
+        matched.push(pos + offset);
+        pos += offset + 1;
+    }
+
+    let mut score = 0;
+    for (idx, &at) in matched.iter().enumerate() {
+        score += 16;
+        if at == 0 || matches!(candidate[at - 1], '.' | '-' | '_' | '/' | ' ') {
+            score += 8;
+        }
+        if idx > 0 {
+            let gap = (at - matched[idx - 1] - 1) as i64;
+            score += if gap == 0 { 8 } else { -gap.min(8) };
+        }
+    }
+    Some((score, matched))
+}
@@ -0,0 +1,343 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::OnceLock;
+
+/// How git sees a file that is about to be renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Tracked,
+    Untracked,
+    Ignored,
+}
+
+impl FileStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            FileStatus::Tracked => "tracked",
+            FileStatus::Untracked => "untracked",
+            FileStatus::Ignored => "ignored",
+        }
+    }
+}
+
+/// Whether the `git` binary can be run. Checked once per process.
+pub fn is_installed() -> bool {
+    static INSTALLED: OnceLock<bool> = OnceLock::new();
+    *INSTALLED.get_or_init(|| {
+        Command::new("git")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// The nearest directory at or above `dir` containing a `.git` directory or
+/// file (worktrees and submodules use a file). Does not run git.
+pub fn find_repo_root(dir: &Path) -> Option<PathBuf> {
+    let dir = std::path::absolute(dir).ok()?;
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    mode: String,
+    object: String,
+}
+
+#[derive(Debug, Default)]
+struct Repo {
+    /// Staged entries of the files being renamed, keyed by path relative to
+    /// the repository root.
+    entries: BTreeMap<PathBuf, IndexEntry>,
+    ignored: BTreeSet<PathBuf>,
+}
+
+/// Index state for a set of files, loaded with one `git ls-files` and one
+/// `git check-ignore` per repository. Renames happen on the filesystem and
+/// are then staged with a single `git update-index` per repository, which
+/// keeps the staged content of each file exactly as `git mv` would.
+#[derive(Debug, Default)]
+pub struct GitIndex {
+    repos: BTreeMap<PathBuf, Repo>,
+}
+
+impl GitIndex {
+    /// Loads the index entries of `files`, which must be absolute paths.
+    pub fn load(files: &[PathBuf]) -> Result<Self, String> {
+        let mut by_repo: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+        for file in files {
+            let parent = file.parent().unwrap_or(file);
+            let root = find_repo_root(parent)
+                .ok_or_else(|| format!("{} is not in a git repository", file.display()))?;
+            let relative = file.strip_prefix(&root).unwrap_or(file).to_path_buf();
+            by_repo.entry(root).or_default().push(relative);
+        }
+
+        let mut index = GitIndex::default();
+        for (root, paths) in by_repo {
+            let entries = staged_entries(&root, &paths)?;
+            let untracked: Vec<&PathBuf> =
+                paths.iter().filter(|path| !entries.contains_key(*path)).collect();
+            let ignored = ignored_paths(&root, &untracked)?;
+            index.repos.insert(root, Repo { entries, ignored });
+        }
+        Ok(index)
+    }
+
+    pub fn status(&self, file: &Path) -> FileStatus {
+        let Some((root, repo)) = self.repo_for(file) else {
+            return FileStatus::Untracked;
+        };
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        if repo.entries.contains_key(relative) {
+            FileStatus::Tracked
+        } else if repo.ignored.contains(relative) {
+            FileStatus::Ignored
+        } else {
+            FileStatus::Untracked
+        }
+    }
+
+    /// Moves the index entries of tracked files that were renamed on disk.
+    /// Takes absolute `(from, to)` pairs; untracked files are skipped.
+    pub fn stage_renames(&self, renames: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+        let mut input: BTreeMap<&Path, Vec<u8>> = BTreeMap::new();
+        for (from, to) in renames {
+            let Some((root, repo)) = self.repo_for(from) else {
+                continue;
+            };
+            let from_rel = from.strip_prefix(root).unwrap_or(from);
+            let Some(entry) = repo.entries.get(from_rel) else {
+                continue;
+            };
+            let to_rel = to.strip_prefix(root).map_err(|_| {
+                format!(
+                    "{} cannot move outside its repository {}",
+                    from.display(),
+                    root.display()
+                )
+            })?;
+
+            let zero = "0".repeat(entry.object.len());
+            let buffer = input.entry(root.as_path()).or_default();
+            buffer.extend_from_slice(format!("0 {zero}\t").as_bytes());
+            buffer.extend_from_slice(from_rel.to_string_lossy().as_bytes());
+            buffer.push(0);
+            buffer.extend_from_slice(format!("{} {}\t", entry.mode, entry.object).as_bytes());
+            buffer.extend_from_slice(to_rel.to_string_lossy().as_bytes());
+            buffer.push(0);
+        }
+
+        for (root, stdin) in input {
+            let output = git_with_input(root, &["update-index", "-z", "--index-info"], &stdin)?;
+            if !output.status.success() {
+                return Err(git_error("git update-index", &output.stderr));
+            }
+        }
+        Ok(())
+    }
+
+    fn repo_for(&self, file: &Path) -> Option<(&PathBuf, &Repo)> {
+        self.repos
+            .iter()
+            .filter(|(root, _)| file.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+    }
+}
+
+/// Paths with staged changes in the repository at `root`.
+pub fn staged_files(root: &Path) -> Result<Vec<String>, String> {
+    let output = Command::new("git")
+        .current_dir(root)
+        .args(["diff", "--cached", "--name-only", "-z"])
+        .output()
+        .map_err(|err| format!("git diff: {err}"))?;
+    if !output.status.success() {
+        return Err(git_error("git diff", &output.stderr));
+    }
+    Ok(split_paths(&output.stdout))
+}
+
+/// Paths among `files` whose working tree content differs from the index
+/// in the repository at `root`.
+pub fn unstaged_files(root: &Path, files: &[PathBuf]) -> Result<Vec<String>, String> {
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let output = Command::new("git")
+        .current_dir(root)
+        .args(["--literal-pathspecs", "diff", "--name-only", "-z", "--"])
+        .args(files)
+        .output()
+        .map_err(|err| format!("git diff: {err}"))?;
+    if !output.status.success() {
+        return Err(git_error("git diff", &output.stderr));
+    }
+    Ok(split_paths(&output.stdout))
+}
+
+/// Stages the content of the tracked files among `files` and commits
+/// everything staged. The files are written to the object store with one
+/// `git hash-object` and staged with one `git update-index`, so only their
+/// current content is staged. Returns the short hash of the new commit, or
+/// `None` if nothing was staged.
+pub fn commit(root: &Path, files: &[PathBuf], message: &str) -> Result<Option<String>, String> {
+    let relative: Vec<PathBuf> = files
+        .iter()
+        .map(|file| file.strip_prefix(root).unwrap_or(file).to_path_buf())
+        .collect();
+    let tracked = if relative.is_empty() {
+        BTreeMap::new()
+    } else {
+        staged_entries(root, &relative)?
+    };
+    if !tracked.is_empty() {
+        let mut paths = Vec::new();
+        for path in tracked.keys() {
+            paths.extend_from_slice(path.to_string_lossy().as_bytes());
+            paths.push(b'\n');
+        }
+        let output = git_with_input(root, &["hash-object", "-w", "--stdin-paths"], &paths)?;
+        if !output.status.success() {
+            return Err(git_error("git hash-object", &output.stderr));
+        }
+        let objects = String::from_utf8_lossy(&output.stdout);
+
+        let mut input = Vec::new();
+        for ((path, entry), object) in tracked.iter().zip(objects.lines()) {
+            input.extend_from_slice(format!("{} {object}\t", entry.mode).as_bytes());
+            input.extend_from_slice(path.to_string_lossy().as_bytes());
+            input.push(0);
+        }
+        let output = git_with_input(root, &["update-index", "-z", "--index-info"], &input)?;
+        if !output.status.success() {
+            return Err(git_error("git update-index", &output.stderr));
+        }
+    }
+
+    if staged_files(root)?.is_empty() {
+        return Ok(None);
+    }
+
+    let output = git_with_input(root, &["commit", "-q", "-F", "-"], message.as_bytes())?;
+    if !output.status.success() {
+        return Err(git_error("git commit", &output.stderr));
+    }
+    let output = Command::new("git")
+        .current_dir(root)
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .map_err(|err| format!("git rev-parse: {err}"))?;
+    Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+fn staged_entries(
+    root: &Path,
+    paths: &[PathBuf],
+) -> Result<BTreeMap<PathBuf, IndexEntry>, String> {
+    let output = Command::new("git")
+        .current_dir(root)
+        .args(["--literal-pathspecs", "ls-files", "--stage", "-z", "--"])
+        .args(paths)
+        .output()
+        .map_err(|err| format!("git ls-files: {err}"))?;
+    if !output.status.success() {
+        return Err(git_error("git ls-files", &output.stderr));
+    }
+
+    let mut entries = BTreeMap::new();
+    let mut unmerged = Vec::new();
+    for record in output.stdout.split(|byte| *byte == 0) {
+        let record = String::from_utf8_lossy(record);
+        let Some((meta, path)) = record.split_once('\t') else {
+            continue;
+        };
+        let mut fields = meta.split(' ');
+        let (Some(mode), Some(object), Some(stage)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if stage != "0" {
+            unmerged.push(path.to_string());
+            continue;
+        }
+        entries.insert(
+            PathBuf::from(path),
+            IndexEntry {
+                mode: mode.to_string(),
+                object: object.to_string(),
+            },
+        );
+    }
+
+    if !unmerged.is_empty() {
+        unmerged.dedup();
+        return Err(format!(
+            "unresolved merge conflicts in {}",
+            unmerged.join(", ")
+        ));
+    }
+    Ok(entries)
+}
+
+fn ignored_paths(root: &Path, paths: &[&PathBuf]) -> Result<BTreeSet<PathBuf>, String> {
+    if paths.is_empty() {
+        return Ok(BTreeSet::new());
+    }
+    let mut stdin = Vec::new();
+    for path in paths {
+        stdin.extend_from_slice(path.to_string_lossy().as_bytes());
+        stdin.push(0);
+    }
+    let output = git_with_input(root, &["check-ignore", "-z", "--stdin"], &stdin)?;
+    // Exit code 1 means none of the paths are ignored.
+    if !output.status.success() && output.status.code() != Some(1) {
+        return Err(git_error("git check-ignore", &output.stderr));
+    }
+    Ok(split_paths(&output.stdout)
+        .into_iter()
+        .map(PathBuf::from)
+        .collect())
+}
+
+fn git_with_input(root: &Path, args: &[&str], stdin: &[u8]) -> Result<Output, String> {
+    let command = format!("git {}", args[0]);
+    let mut child = Command::new("git")
+        .current_dir(root)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("{command}: {err}"))?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(stdin).map_err(|err| format!("{command}: {err}"))?;
+    }
+    child
+        .wait_with_output()
+        .map_err(|err| format!("{command}: {err}"))
+}
+
+fn split_paths(output: &[u8]) -> Vec<String> {
+    output
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .collect()
+}
+
+fn git_error(command: &str, stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let message = stderr.trim();
+    if message.is_empty() {
+        format!("{command} failed")
+    } else {
+        message.to_string()
+    }
+}
@@ -1,21 +1,74 @@
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::config::{or_default, skip_invalid};
+
+/// Settings of `~/.config/axon/config.toml`. A value of the wrong type is
+/// dropped on its own, as is a runner table that doesn't load, so the other
+/// settings still apply.
 #[derive(Debug, Deserialize, Default)]
 pub struct GlobalConfig {
-    #[serde(default)]
+    #[serde(default, deserialize_with = "or_default")]
     pub notes_dir: Option<String>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "or_default")]
     pub prompts_dir: Option<String>,
+    /// Command template opening files, e.g. `nvim +{line} {path}`.
+    #[serde(default, deserialize_with = "or_default")]
+    pub opener: Option<String>,
+    /// Fuzzy finder for the TUI's lists: `builtin` or `fzf`.
+    #[serde(default, deserialize_with = "or_default")]
+    pub picker: Option<String>,
+    /// Name of the runner prompts run with when none is chosen.
+    #[serde(default, deserialize_with = "or_default")]
+    pub runner: Option<String>,
+    /// Agent CLIs that run prompts, by name.
+    #[serde(default, deserialize_with = "skip_invalid")]
+    pub runners: BTreeMap<String, RunnerConfig>,
+    /// Checkout directories by the `{repo}` segment of prompt filenames.
+    #[serde(default, deserialize_with = "skip_invalid")]
+    pub repos: BTreeMap<String, String>,
+    #[serde(default, deserialize_with = "or_default")]
+    pub size: SizeConfig,
+}
+
+/// The `[size]` table.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct SizeConfig {
+    /// Token estimate heuristic: `chars` or `words`.
+    #[serde(default, deserialize_with = "or_default")]
+    pub tokenizer: Option<String>,
+    /// Most tokens a prompt may have once expanded, by category (`feat`,
+    /// `sop`), enforced by `axon health`.
+    #[serde(default, deserialize_with = "skip_invalid")]
+    pub max_tokens: BTreeMap<String, i64>,
+}
+
+/// A `[runners.<name>]` table.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct RunnerConfig {
+    /// The program, optionally with fixed arguments.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Argument template with `{prompt_path}`, `{prompts_md}`, and
+    /// `{instruction}`. Default: `{instruction}`.
+    #[serde(default)]
+    pub args: Option<String>,
+    /// Working directory; may start with `~/`.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
 }
 
-fn config_path() -> PathBuf {
+pub fn config_path() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
     PathBuf::from(home).join(".config/axon/config.toml")
 }
 
-fn expand_tilde(path: &str) -> PathBuf {
+pub(crate) fn expand_tilde(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/") {
         let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
         PathBuf::from(home).join(rest)
@@ -38,24 +91,54 @@ pub fn load() -> GlobalConfig {
     toml::from_str(&content).unwrap_or_default()
 }
 
+/// Where an effective setting comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Env(&'static str),
+    File(PathBuf),
+    BuiltIn,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Env(var) => write!(f, "env {var}"),
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::BuiltIn => write!(f, "default"),
+        }
+    }
+}
+
 impl GlobalConfig {
     pub fn notes_dir(&self) -> PathBuf {
-        if let Ok(dir) = std::env::var("AXON_NOTES_DIR") {
-            return PathBuf::from(dir);
-        }
-        if let Some(ref dir) = self.notes_dir {
-            return expand_tilde(dir);
-        }
-        home_dir().join("notes")
+        self.notes_dir_with_origin().0
     }
 
     pub fn prompts_dir(&self) -> PathBuf {
-        if let Ok(dir) = std::env::var("AXON_PROMPTS_DIR") {
-            return PathBuf::from(dir);
-        }
-        if let Some(ref dir) = self.prompts_dir {
-            return expand_tilde(dir);
-        }
-        home_dir().join("prompts")
+        self.prompts_dir_with_origin().0
+    }
+
+    /// The directory prompts for `repo` run in, if configured.
+    pub fn repo_dir(&self, repo: &str) -> Option<PathBuf> {
+        self.repos.get(repo).map(|dir| expand_tilde(dir))
+    }
+
+    pub fn notes_dir_with_origin(&self) -> (PathBuf, Origin) {
+        resolve_dir("AXON_NOTES_DIR", self.notes_dir.as_deref(), "notes")
+    }
+
+    pub fn prompts_dir_with_origin(&self) -> (PathBuf, Origin) {
+        resolve_dir("AXON_PROMPTS_DIR", self.prompts_dir.as_deref(), "prompts")
+    }
+}
+
+/// The environment variable, then the config file, then `~/<default>`.
+fn resolve_dir(var: &'static str, configured: Option<&str>, default: &str) -> (PathBuf, Origin) {
+    if let Ok(dir) = std::env::var(var) {
+        return (PathBuf::from(dir), Origin::Env(var));
+    }
+    if let Some(dir) = configured {
+        return (expand_tilde(dir), Origin::File(config_path()));
     }
+    (home_dir().join(default), Origin::BuiltIn)
 }
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::refactor::{folds_case_at, target_conflicts, ContentEdit, RenamePlan};
+
+pub const STATE_DIR: &str = ".axon";
+const HISTORY_FILE: &str = "history.json";
+/// Number of operations kept in the history. Entries store whole file
+/// contents for rewritten links, so the oldest are dropped past this.
+pub const MAX_ENTRIES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryStatus {
+    Applied,
+    Undone,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub timestamp: String,
+    pub command: String,
+    #[serde(default)]
+    pub from_pattern: Option<String>,
+    #[serde(default)]
+    pub to_pattern: Option<String>,
+    pub method: String,
+    pub status: EntryStatus,
+    pub renames: Vec<RenamePlan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<ContentEdit>,
+}
+
+impl HistoryEntry {
+    /// Renames that reverse this entry, in the order they must be applied.
+    pub fn reversed(&self) -> Vec<RenamePlan> {
+        self.renames
+            .iter()
+            .rev()
+            .map(|rename| RenamePlan::new(rename.to.as_str(), rename.from.as_str()))
+            .collect()
+    }
+
+    /// Where an edited file lives before this entry's renames are applied.
+    pub fn original_path<'a>(&'a self, file: &'a str) -> &'a str {
+        self.renames
+            .iter()
+            .find(|rename| rename.to == file)
+            .map(|rename| rename.from.as_str())
+            .unwrap_or(file)
+    }
+}
+
+/// Describes an operation about to be recorded in the history.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub command: String,
+    pub from_pattern: Option<String>,
+    pub to_pattern: Option<String>,
+    pub method: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    pub entries: Vec<HistoryEntry>,
+    /// Ids of undone entries, most recently undone last.
+    #[serde(default)]
+    pub redo: Vec<u64>,
+}
+
+pub fn history_path(root: &Path) -> PathBuf {
+    root.join(STATE_DIR).join(HISTORY_FILE)
+}
+
+pub fn load(root: &Path) -> Result<History, String> {
+    let path = history_path(root);
+    if !path.exists() {
+        return Ok(History::default());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("Error: Invalid history file {}: {err}", path.display()))
+}
+
+pub fn save(root: &Path, history: &History) -> Result<(), String> {
+    let path = history_path(root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(history).map_err(|err| err.to_string())?;
+    std::fs::write(&path, contents).map_err(|err| err.to_string())
+}
+
+impl History {
+    /// Appends a new applied entry, dropping the oldest past `MAX_ENTRIES`.
+    /// Recording a new operation discards the redo stack.
+    pub fn record(
+        &mut self,
+        operation: &Operation,
+        renames: &[RenamePlan],
+        edits: &[ContentEdit],
+    ) -> u64 {
+        let id = self.entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+        self.entries.push(HistoryEntry {
+            id,
+            timestamp: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            command: operation.command.clone(),
+            from_pattern: operation.from_pattern.clone(),
+            to_pattern: operation.to_pattern.clone(),
+            method: operation.method.clone(),
+            status: EntryStatus::Applied,
+            renames: renames.to_vec(),
+            edits: edits.to_vec(),
+        });
+        let excess = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..excess);
+        self.redo.clear();
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// The most recent entry that has not been undone.
+    pub fn last_applied(&self) -> Option<&HistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.status == EntryStatus::Applied)
+    }
+
+    pub fn next_redo(&self) -> Option<&HistoryEntry> {
+        self.redo.last().and_then(|id| self.get(*id))
+    }
+
+    pub fn mark_undone(&mut self, id: u64) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.status = EntryStatus::Undone;
+            self.redo.retain(|redo_id| *redo_id != id);
+            self.redo.push(id);
+        }
+    }
+
+    pub fn mark_redone(&mut self, id: u64) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.status = EntryStatus::Applied;
+            self.redo.retain(|redo_id| *redo_id != id);
+        }
+    }
+}
+
+/// Checks that every rename can still be applied: each source exists and,
+/// unless `force` is set, each target is free. Returns a description of
+/// every mismatch.
+pub fn verify_renames(root: &Path, renames: &[RenamePlan], force: bool) -> Result<(), String> {
+    let mut problems = Vec::new();
+    for rename in renames {
+        if !root.join(&rename.from).exists() {
+            problems.push(format!("  {} is missing", rename.from));
+        }
+        if !force
+            && target_conflicts(
+                rename,
+                |path| root.join(path).exists(),
+                |path| folds_case_at(&root.join(path)),
+            )
+        {
+            problems.push(format!("  {} already exists", rename.to));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = String::from("Error: Files no longer match the recorded operation\n\n");
+    for problem in problems {
+        message.push_str(&problem);
+        message.push('\n');
+    }
+    message.push_str("\nAborting. No files were renamed.");
+    Err(message)
+}
+
+/// Checks that each file still holds the content recorded for it. Each
+/// check is a path and the content expected at that path.
+pub fn verify_contents(root: &Path, checks: &[(&str, &str)]) -> Result<(), String> {
+    let mut changed = Vec::new();
+    for (file, expected) in checks {
+        match std::fs::read_to_string(root.join(file)) {
+            Ok(content) if content == *expected => {}
+            Ok(_) => changed.push(format!("  {file} was modified")),
+            Err(_) => changed.push(format!("  {file} is missing")),
+        }
+    }
+
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = String::from("Error: File contents no longer match the recorded operation\n\n");
+    for problem in changed {
+        message.push_str(&problem);
+        message.push('\n');
+    }
+    message.push_str("\nAborting. No files were changed.");
+    Err(message)
+}
+
+pub fn write_contents(root: &Path, contents: &[(&str, &str)]) -> Result<(), String> {
+    for (file, content) in contents {
+        std::fs::write(root.join(file), content).map_err(|err| format!("{file}: {err}"))?;
+    }
+    Ok(())
+}
@@ -1,10 +1,25 @@
 pub mod commands;
 pub mod config;
+pub mod config_check;
 pub mod error;
 pub mod fs_utils;
+pub mod fuzzy;
+pub mod git;
 pub mod global_config;
+pub mod history;
+pub mod links;
 pub mod notes;
+pub mod opener;
 pub mod pattern;
+pub mod picker;
+pub mod preview;
 pub mod prompts;
 pub mod refactor;
+pub mod review;
+pub mod run_log;
+pub mod runner;
+pub mod search;
+pub mod size;
+pub mod tasks;
+pub mod template;
 pub mod tui;
@@ -0,0 +1,402 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::fs_utils::{file_name_string, list_markdown_files};
+use crate::pattern::{is_valid_filename, parse_filename};
+use crate::refactor::{ContentEdit, RenamePlan};
+
+/// A single reference rewritten inside a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkChange {
+    pub line: usize,
+    pub from: String,
+    pub to: String,
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'
+}
+
+/// Whether the text following a filename stem ends the reference. A stem
+/// is terminated by `.md` or by anything that cannot continue a filename,
+/// so `foo.v1` does not match inside `foo.v12` or `foo.v1.x.md`.
+fn stem_ends_at(rest: &str) -> Option<usize> {
+    if let Some(after) = rest.strip_prefix(".md")
+        && !after.starts_with(is_name_char)
+        && !after.starts_with(|c: char| c == '.' && after[1..].starts_with(is_name_char))
+    {
+        return Some(3);
+    }
+    let mut chars = rest.chars();
+    match chars.next() {
+        None => Some(0),
+        Some(c) if is_name_char(c) => None,
+        Some('.') if chars.next().is_some_and(is_name_char) => None,
+        Some(_) => Some(0),
+    }
+}
+
+fn stem_starts_at(before: &str) -> bool {
+    !before.ends_with(|c: char| is_name_char(c) || c == '.')
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+/// Rewrites every reference to a renamed file in `content`. References are
+/// matched by filename with or without the `.md` extension, which covers
+/// bare mentions, markdown links, and `[[wikilinks]]`. Returns `None` when
+/// nothing changed.
+pub fn rewrite_references(
+    content: &str,
+    renames: &[RenamePlan],
+) -> Option<(String, Vec<LinkChange>)> {
+    let mut matches: Vec<(usize, usize, String, String)> = Vec::new();
+    for rename in renames {
+        // Links name files, not paths, so only the final component matters.
+        let from = file_name(&rename.from);
+        let to = file_name(&rename.to);
+        let from_stem = from.strip_suffix(".md").unwrap_or(from);
+        let to_stem = to.strip_suffix(".md").unwrap_or(to);
+        if from_stem.is_empty() || from_stem == to_stem {
+            continue;
+        }
+        for (start, _) in content.match_indices(from_stem) {
+            let end = start + from_stem.len();
+            if !stem_starts_at(&content[..start]) {
+                continue;
+            }
+            let Some(ext_len) = stem_ends_at(&content[end..]) else {
+                continue;
+            };
+            let ext = &content[end..end + ext_len];
+            matches.push((
+                start,
+                end + ext_len,
+                format!("{from_stem}{ext}"),
+                format!("{to_stem}{ext}"),
+            ));
+        }
+    }
+
+    if matches.is_empty() {
+        return None;
+    }
+
+    // Longest match first at each position, then drop overlaps.
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    let mut output = String::with_capacity(content.len());
+    let mut changes = Vec::new();
+    let mut pos = 0;
+    for (start, end, from, to) in matches {
+        if start < pos {
+            continue;
+        }
+        output.push_str(&content[pos..start]);
+        output.push_str(&to);
+        changes.push(LinkChange {
+            line: content[..start].matches('\n').count() + 1,
+            from,
+            to,
+        });
+        pos = end;
+    }
+    output.push_str(&content[pos..]);
+
+    Some((output, changes))
+}
+
+/// The rewritten contents of one file along with the references changed.
+#[derive(Debug, Clone)]
+pub struct LinkRewrite {
+    pub edit: ContentEdit,
+    pub changes: Vec<LinkChange>,
+}
+
+/// Computes reference rewrites for every file in `files`. Edits are keyed
+/// by each file's name after the renames are applied.
+pub fn plan_link_rewrites(
+    root: &Path,
+    files: &[String],
+    renames: &[RenamePlan],
+) -> Result<Vec<LinkRewrite>, String> {
+    let mut rewrites = Vec::new();
+    for file in files {
+        let content = match std::fs::read_to_string(root.join(file)) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidData => continue,
+            Err(err) => return Err(format!("Error: {file}: {err}")),
+        };
+        let Some((after, changes)) = rewrite_references(&content, renames) else {
+            continue;
+        };
+        let target = renames
+            .iter()
+            .find(|rename| &rename.from == file)
+            .map(|rename| rename.to.clone())
+            .unwrap_or_else(|| file.clone());
+        rewrites.push(LinkRewrite {
+            edit: ContentEdit {
+                file: target,
+                before: content,
+                after,
+            },
+            changes,
+        });
+    }
+    Ok(rewrites)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Markdown,
+    Wiki,
+    Bare,
+}
+
+/// A reference to another file found in markdown content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub line: usize,
+    pub kind: LinkKind,
+    pub target: String,
+}
+
+static MARKDOWN_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+static WIKI_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+static BARE_NAME_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn markdown_link_regex() -> &'static Regex {
+    MARKDOWN_LINK_REGEX.get_or_init(|| {
+        Regex::new(r"!?\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+[^)]*)?\)")
+            .expect("invalid markdown link regex")
+    })
+}
+
+fn wiki_link_regex() -> &'static Regex {
+    WIKI_LINK_REGEX.get_or_init(|| {
+        Regex::new(r"!?\[\[([^\]|#]+)(?:#[^\]|]*)?(?:\|[^\]]*)?\]\]").expect("invalid wikilink regex")
+    })
+}
+
+fn bare_name_regex() -> &'static Regex {
+    BARE_NAME_REGEX.get_or_init(|| {
+        Regex::new(r"[a-z0-9][a-z0-9.-]*\.v[1-9]\d*(?:\.md)?").expect("invalid bare name regex")
+    })
+}
+
+fn is_external(target: &str) -> bool {
+    target.starts_with('#') || target.contains("://") || target.starts_with("mailto:")
+}
+
+/// Extracts markdown links, wikilinks, and bare filenames that follow the
+/// naming convention. Fenced code blocks are skipped.
+pub fn extract_links(content: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut spans = Vec::new();
+        for caps in markdown_link_regex().captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            spans.push(whole.range());
+            let target = caps.get(1).unwrap().as_str();
+            if is_external(target) {
+                continue;
+            }
+            let target = target.split(['#', '?']).next().unwrap_or("");
+            if target.is_empty() {
+                continue;
+            }
+            links.push(Link {
+                line: line_no,
+                kind: LinkKind::Markdown,
+                target: target.replace("%20", " "),
+            });
+        }
+        for caps in wiki_link_regex().captures_iter(line) {
+            spans.push(caps.get(0).unwrap().range());
+            let target = caps.get(1).unwrap().as_str().trim();
+            if target.is_empty() {
+                continue;
+            }
+            links.push(Link {
+                line: line_no,
+                kind: LinkKind::Wiki,
+                target: target.to_string(),
+            });
+        }
+        for found in bare_name_regex().find_iter(line) {
+            if spans.iter().any(|span| span.contains(&found.start())) {
+                continue;
+            }
+            if !stem_starts_at(&line[..found.start()]) || stem_ends_at(&line[found.end()..]).is_none() {
+                continue;
+            }
+            let name = found.as_str();
+            let name = if name.ends_with(".md") {
+                name.to_string()
+            } else {
+                format!("{name}.md")
+            };
+            if !is_valid_filename(&name) {
+                continue;
+            }
+            links.push(Link {
+                line: line_no,
+                kind: LinkKind::Bare,
+                target: name,
+            });
+        }
+    }
+
+    links.sort_by_key(|link| link.line);
+    links
+}
+
+/// A link whose target does not exist.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenLink {
+    pub file: String,
+    pub line: usize,
+    pub kind: LinkKind,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkReport {
+    pub files: usize,
+    pub links: usize,
+    pub broken: Vec<BrokenLink>,
+}
+
+/// Checks every link in `files`. Relative markdown links resolve against
+/// the linking file's directory; wikilinks and bare filenames resolve by
+/// name against `known`, the filenames of every directory in scope.
+pub fn check_links(files: &[PathBuf], known: &BTreeSet<String>) -> LinkReport {
+    let mut report = LinkReport::default();
+    for path in files {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        report.files += 1;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for link in extract_links(&content) {
+            report.links += 1;
+            let exists = match link.kind {
+                LinkKind::Markdown => dir.join(&link.target).exists(),
+                LinkKind::Wiki | LinkKind::Bare => {
+                    let name = markdown_name(&link.target);
+                    [link.target.as_str(), name.as_str()]
+                        .iter()
+                        .any(|name| known.contains(*name) || dir.join(name).exists())
+                }
+            };
+            if exists {
+                continue;
+            }
+            let name = Path::new(&link.target)
+                .file_name()
+                .map(|name| markdown_name(&name.to_string_lossy()))
+                .unwrap_or_else(|| link.target.clone());
+            report.broken.push(BrokenLink {
+                file: path.strip_prefix(".").unwrap_or(path).display().to_string(),
+                line: link.line,
+                kind: link.kind,
+                suggestion: suggest_name(&name, known),
+                target: link.target,
+            });
+        }
+    }
+    report
+}
+
+fn markdown_name(target: &str) -> String {
+    if target.ends_with(".md") {
+        target.to_string()
+    } else {
+        format!("{target}.md")
+    }
+}
+
+/// Suggests the existing filename closest to `name`: the newest version of
+/// the same prompt if there is one, otherwise the nearest name by edit
+/// distance.
+pub fn suggest_name(name: &str, known: &BTreeSet<String>) -> Option<String> {
+    if let Ok(parsed) = parse_filename(name) {
+        let identity = parsed.identity();
+        let newest = known
+            .iter()
+            .filter_map(|candidate| {
+                let other = parse_filename(candidate).ok()?;
+                (other.identity() == identity).then(|| (other.version(), candidate))
+            })
+            .max_by_key(|(version, _)| *version);
+        if let Some((_, candidate)) = newest {
+            return Some(candidate.clone());
+        }
+    }
+
+    let threshold = (name.len() / 3).max(2);
+    known
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b_chars.len()]
+}
+
+/// Lists the markdown files in each directory along with the names of
+/// every file in them, which wikilinks and bare filenames resolve against.
+pub fn collect_scope(dirs: &[PathBuf]) -> std::io::Result<(Vec<PathBuf>, BTreeSet<String>)> {
+    let mut files = Vec::new();
+    let mut known = BTreeSet::new();
+    for dir in dirs {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            if let Some(name) = file_name_string(&entry.path()) {
+                known.insert(name);
+            }
+        }
+        files.extend(list_markdown_files(dir)?);
+    }
+    Ok((files, known))
+}
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 use axon::commands;
 
@@ -15,9 +15,27 @@ enum Commands {
     Validate(commands::validate::ValidateArgs),
     Parse(commands::parse::ParseArgs),
     Refactor(commands::refactor::RefactorArgs),
-Stats(commands::stats::StatsArgs),
-    /// Open today's daily notes directory in yazi
-    D,
+    Stats(commands::stats::StatsArgs),
+    /// Estimate prompt sizes in bytes, words, lines, and tokens
+    Size(commands::size::SizeArgs),
+    /// List past refactor operations
+    History(commands::history::HistoryArgs),
+    /// Reverse a refactor operation
+    Undo(commands::undo::UndoArgs),
+    /// Re-apply the most recently undone operation
+    Redo(commands::redo::RedoArgs),
+    /// Report broken links between prompts and notes
+    Links(commands::links::LinksArgs),
+    /// Run a prompt with an agent CLI
+    Run(commands::run::RunArgs),
+    /// List past prompt runs
+    Runs(commands::runs::RunsArgs),
+    /// Fill in a prompt's {{variables}}
+    Render(commands::render::RenderArgs),
+    /// Search the contents of notes and prompts
+    Search(commands::search::SearchArgs),
+    /// Check, show, and edit the configuration
+    Config(commands::config::ConfigArgs),
     /// Create a new note with schema applied
     N {
         /// Filename for the new note (e.g. weekly.2026.03.02.md)
@@ -26,24 +44,46 @@ Stats(commands::stats::StatsArgs),
 }
 
 fn main() {
-    let cli = Cli::parse();
+    // Periodic note commands (`axon d`, `axon w`, ...) come from config.
+    let kinds = axon::notes::periodic_kinds(&axon::notes::notes_dir());
+    let matches = commands::periodic::add_commands(Cli::command(), &kinds).get_matches();
+    if matches.subcommand_name() != Some("config") {
+        axon::config_check::warn_invalid();
+    }
+    if let Some((name, sub)) = matches.subcommand()
+        && let Some(result) = commands::periodic::dispatch(&kinds, name, sub)
+    {
+        exit_on_error(result);
+        return;
+    }
+
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
     let result = match cli.command {
         Some(Commands::Health(args)) => commands::health::run(args),
         Some(Commands::Validate(args)) => commands::validate::run(args),
         Some(Commands::Parse(args)) => commands::parse::run(args),
         Some(Commands::Refactor(args)) => commands::refactor::run(args),
         Some(Commands::Stats(args)) => commands::stats::run(args),
-        Some(Commands::D) => axon::notes::open_daily().map_err(|e| axon::error::CliError {
-            code: 1,
-            message: format!("daily note error: {e}"),
-        }),
+        Some(Commands::Size(args)) => commands::size::run(args),
+        Some(Commands::History(args)) => commands::history::run(args),
+        Some(Commands::Undo(args)) => commands::undo::run(args),
+        Some(Commands::Redo(args)) => commands::redo::run(args),
+        Some(Commands::Links(args)) => commands::links::run(args),
+        Some(Commands::Run(args)) => commands::run::run(args),
+        Some(Commands::Runs(args)) => commands::runs::run(args),
+        Some(Commands::Render(args)) => commands::render::run(args),
+        Some(Commands::Search(args)) => commands::search::run(args),
+        Some(Commands::Config(args)) => commands::config::run(args),
         Some(Commands::N { filename }) => axon::notes::create_and_open_note(&filename).map_err(|e| axon::error::CliError {
             code: 1,
             message: format!("note error: {e}"),
         }),
         None => axon::tui::run(),
     };
+    exit_on_error(result);
+}
 
+fn exit_on_error(result: Result<(), axon::error::CliError>) {
     if let Err(err) = result {
         err.print();
         std::process::exit(err.code);
@@ -1,88 +1,505 @@
+use std::fmt::Write;
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
 
-use chrono::{Datelike, Local};
+use chrono::format::{Item, Parsed, StrftimeItems};
+use chrono::{Datelike, Local, Months, NaiveDate, Weekday};
 
-use crate::config;
+use crate::config::{self, CarryOverConfig, PeriodicConfig};
+use crate::tasks::{self, Task};
+use crate::template::{self, Context, Value};
 
 pub fn notes_dir() -> PathBuf {
     let cfg = crate::global_config::load();
     cfg.notes_dir()
 }
 
-pub fn open_daily() -> std::io::Result<()> {
-    let dir = notes_dir();
-    let path = create_daily(&dir)?;
-    open_yazi(&path)
+/// The length of a periodic note's period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
 }
 
-pub fn create_daily(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
-    let date = Local::now().format("%Y.%m.%d");
-    let filename = format!("daily.{date}.md");
-    let path = notes_dir.join(&filename);
+impl Period {
+    pub fn parse(value: &str) -> Option<Period> {
+        match value {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            "month" => Some(Period::Month),
+            "quarter" => Some(Period::Quarter),
+            "year" => Some(Period::Year),
+            _ => None,
+        }
+    }
 
-    if !path.exists() {
-        fs::create_dir_all(notes_dir)?;
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::Quarter => "quarter",
+            Period::Year => "year",
+        }
+    }
+}
 
-        let cfg = config::load_config(notes_dir);
-        let content = cfg.resolve_schema(notes_dir, &filename).unwrap_or_default();
-        fs::write(&path, content)?;
+/// A kind of periodic note: how its files are named and how long each
+/// period lasts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodicKind {
+    pub name: String,
+    /// chrono format of the filename without `.md`.
+    pub format: String,
+    pub period: Period,
+    pub week_start: Weekday,
+    pub key: Option<char>,
+}
+
+impl PeriodicKind {
+    pub fn new(name: &str, format: &str, period: Period, key: Option<char>) -> Self {
+        Self {
+            name: name.to_string(),
+            format: format.to_string(),
+            period,
+            week_start: Weekday::Mon,
+            key,
+        }
     }
 
-    Ok(path)
+    /// The daily, weekly, and monthly notes available without configuration.
+    pub fn builtin() -> Vec<PeriodicKind> {
+        vec![
+            PeriodicKind::new("daily", "daily.%Y.%m.%d", Period::Day, Some('d')),
+            PeriodicKind::new("weekly", "weekly.%Y.%m.%d", Period::Week, Some('w')),
+            PeriodicKind::new("monthly", "monthly.%Y.%m", Period::Month, Some('m')),
+        ]
+    }
+
+    /// Builds a kind from its `[periodic.<name>]` table.
+    pub fn from_config(name: &str, cfg: &PeriodicConfig) -> Result<PeriodicKind, String> {
+        let period = Period::parse(&cfg.period).ok_or_else(|| {
+            format!(
+                "periodic.{name}: unknown period '{}' (expected day, week, month, quarter or year)",
+                cfg.period
+            )
+        })?;
+        if StrftimeItems::new(&cfg.format).any(|item| matches!(item, Item::Error)) {
+            return Err(format!("periodic.{name}: invalid format '{}'", cfg.format));
+        }
+        let week_start = match &cfg.week_start {
+            Some(day) => day
+                .parse::<Weekday>()
+                .map_err(|_| format!("periodic.{name}: unknown week_start '{day}'"))?,
+            None => Weekday::Mon,
+        };
+        let key = match cfg.key.as_deref() {
+            None => None,
+            Some(key) => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => return Err(format!("periodic.{name}: key must be a single character")),
+                }
+            }
+        };
+        let kind = PeriodicKind {
+            name: name.to_string(),
+            format: cfg.format.clone(),
+            period,
+            week_start,
+            key,
+        };
+        kind.check_format()
+            .map_err(|reason| format!("periodic.{name}: format '{}' {reason}", cfg.format))?;
+        Ok(kind)
+    }
+
+    /// Checks that the format renders from a date alone and that each
+    /// period's filename parses back to that period.
+    fn check_format(&self) -> Result<(), &'static str> {
+        // A leap day, the ends of a month, quarter and year, and a week
+        // that spans two years.
+        let samples = [(2024, 2, 29), (2026, 3, 4), (2026, 6, 30), (2026, 12, 31), (2027, 1, 1)];
+        for (year, month, day) in samples {
+            let date = NaiveDate::from_ymd_opt(year, month, day).unwrap_or_default();
+            let mut stem = String::new();
+            if write!(stem, "{}", date.format(&self.format)).is_err() {
+                return Err("has fields a date doesn't have, such as a time of day");
+            }
+            let start = self.start_of(date);
+            if self.parse_filename(&self.filename(start)) != Some(start) {
+                return Err("doesn't name each period uniquely");
+            }
+        }
+        Ok(())
+    }
+
+    /// The first day of the period containing `date`.
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self.period {
+            Period::Day => date,
+            Period::Week => {
+                date - chrono::Duration::days(date.weekday().days_since(self.week_start) as i64)
+            }
+            Period::Month => date.with_day(1).unwrap_or(date),
+            Period::Quarter => {
+                let month = (date.month0() / 3) * 3 + 1;
+                NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+            }
+            Period::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+        }
+    }
+
+    /// The start of the period `offset` periods away from the one containing
+    /// `date`, or `None` if that's past year 9999 or before year 0, which
+    /// filenames can't hold.
+    pub fn step(&self, date: NaiveDate, offset: i64) -> Option<NaiveDate> {
+        let start = self.start_of(date);
+        let months = match self.period {
+            Period::Day => {
+                let delta = chrono::Duration::try_days(offset)?;
+                return start.checked_add_signed(delta).filter(in_range);
+            }
+            Period::Week => {
+                let delta = chrono::Duration::try_weeks(offset)?;
+                return start.checked_add_signed(delta).filter(in_range);
+            }
+            Period::Month => offset,
+            Period::Quarter => offset.checked_mul(3)?,
+            Period::Year => offset.checked_mul(12)?,
+        };
+        let delta = Months::new(months.unsigned_abs().try_into().ok()?);
+        let stepped = if months < 0 {
+            start.checked_sub_months(delta)
+        } else {
+            start.checked_add_months(delta)
+        };
+        stepped.filter(in_range)
+    }
+
+    pub fn filename(&self, date: NaiveDate) -> String {
+        format!("{}.md", self.start_of(date).format(&self.format))
+    }
+
+    /// The period start encoded in a filename of this kind, e.g.
+    /// `daily.2026.03.02.md` or `weekly.2026-W42.md`.
+    pub fn parse_filename(&self, filename: &str) -> Option<NaiveDate> {
+        let stem = filename.strip_suffix(".md")?;
+        let mut parsed = Parsed::new();
+        chrono::format::parse(&mut parsed, stem, StrftimeItems::new(&self.format)).ok()?;
+
+        let date = match parsed.to_naive_date() {
+            Ok(date) => date,
+            Err(_) => {
+                if let (Some(year), Some(week)) = (parsed.isoyear(), parsed.isoweek()) {
+                    NaiveDate::from_isoywd_opt(year, week, self.week_start)?
+                } else {
+                    let year = parsed.year()?;
+                    let month = match (parsed.month(), parsed.quarter()) {
+                        (Some(month), _) => month,
+                        (None, Some(quarter)) => (quarter - 1) * 3 + 1,
+                        (None, None) => 1,
+                    };
+                    NaiveDate::from_ymd_opt(year, month, parsed.day().unwrap_or(1))?
+                }
+            }
+        };
+
+        // Reject names that only parse loosely, e.g. a mid-week date for a
+        // weekly note.
+        let start = self.start_of(date);
+        (self.filename(start) == filename).then_some(start)
+    }
 }
 
-pub fn open_weekly() -> std::io::Result<()> {
-    let dir = notes_dir();
-    let path = create_weekly(&dir)?;
-    open_yazi(&path)
+fn in_range(date: &NaiveDate) -> bool {
+    (0..=9999).contains(&date.year())
 }
 
-pub fn create_weekly(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
-    let now = Local::now().date_naive();
-    let monday = now - chrono::Duration::days(now.weekday().num_days_from_monday() as i64);
-    let date = monday.format("%Y.%m.%d");
-    let filename = format!("weekly.{date}.md");
+/// The periodic note kinds for `notes_dir`: the built-in ones, overridden
+/// and extended by `[periodic.<name>]` tables in its `axon.toml`. Invalid
+/// tables are skipped.
+pub fn periodic_kinds(notes_dir: &std::path::Path) -> Vec<PeriodicKind> {
+    let cfg = config::load_config(notes_dir);
+    let mut kinds = PeriodicKind::builtin();
+    for (name, table) in &cfg.periodic {
+        let Ok(kind) = PeriodicKind::from_config(name, table) else {
+            continue;
+        };
+        match kinds.iter_mut().find(|existing| existing.name == kind.name) {
+            Some(existing) => *existing = kind,
+            None => kinds.push(kind),
+        }
+    }
+    kinds
+}
+
+/// The kind and period start of a periodic note filename.
+pub fn detect_periodic<'a>(
+    kinds: &'a [PeriodicKind],
+    filename: &str,
+) -> Option<(&'a PeriodicKind, NaiveDate)> {
+    kinds
+        .iter()
+        .find_map(|kind| kind.parse_filename(filename).map(|date| (kind, date)))
+}
+
+/// Parses a date given on the command line: `2026-03-02` or `2026.03.02`.
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y.%m.%d"))
+        .map_err(|_| format!("Invalid date '{value}' (expected YYYY-MM-DD)"))
+}
+
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Creates the note of `kind` for the period containing `date` from its
+/// schema, unless it already exists.
+pub fn create_periodic(
+    notes_dir: &std::path::Path,
+    kind: &PeriodicKind,
+    date: NaiveDate,
+) -> std::io::Result<PathBuf> {
+    create_periodic_on(notes_dir, kind, date, today())
+}
+
+/// [`create_periodic`] as if `today` were the current date. Only the note
+/// for the current period collects tasks under its `[carry_over]` rule, so
+/// opening a past or future note leaves today's tasks alone.
+pub fn create_periodic_on(
+    notes_dir: &std::path::Path,
+    kind: &PeriodicKind,
+    date: NaiveDate,
+    today: NaiveDate,
+) -> std::io::Result<PathBuf> {
+    let filename = kind.filename(date);
     let path = notes_dir.join(&filename);
 
     if !path.exists() {
         fs::create_dir_all(notes_dir)?;
 
         let cfg = config::load_config(notes_dir);
-        let content = cfg.resolve_schema(notes_dir, &filename).unwrap_or_default();
+        let mut content = schema_content(notes_dir, &cfg, &filename);
+        let current = kind.start_of(date) == kind.start_of(today);
+        let rule = cfg.carry_over.get(&kind.name).filter(|_| current);
+        let carried = match rule {
+            Some(rule) => collect_carry_over(notes_dir, kind, date, rule)?,
+            None => Vec::new(),
+        };
+        if let Some(rule) = rule {
+            let mut texts: Vec<String> = Vec::new();
+            for task in carried.iter().flat_map(|source| &source.tasks) {
+                let text = format!("{}{}", task.indent, task.text);
+                if !texts.contains(&text) {
+                    texts.push(text);
+                }
+            }
+            content = tasks::insert_tasks(&content, &rule.section, &texts);
+        }
         fs::write(&path, content)?;
+
+        // Tasks moved within a kind are marked so they move only once. A
+        // rollup from another kind copies them, leaving that kind's own
+        // carry-over to move them.
+        for source in carried.into_iter().filter(|source| !source.rollup) {
+            let lines: Vec<usize> = source.tasks.iter().map(|task| task.line).collect();
+            fs::write(&source.path, tasks::mark_carried(&source.content, &lines))?;
+        }
     }
 
     Ok(path)
 }
 
-pub fn open_monthly() -> std::io::Result<()> {
-    let dir = notes_dir();
-    let path = create_monthly(&dir)?;
-    open_yazi(&path)
+/// Template variables for a new note named `filename`:
+///
+/// - `date`: the period start of a periodic note, today otherwise; shown as
+///   the part of the filename after the first dot, e.g. `2026.03.02`
+/// - `today`, `yesterday`, `tomorrow` (relative to `date`), `week_number`
+/// - `filename`, and `kind` for periodic notes
+/// - `prev_note` / `next_note`: link names of the neighbouring periodic notes
+pub fn schema_context(notes_dir: &std::path::Path, filename: &str) -> Context<'static> {
+    let kinds = periodic_kinds(notes_dir);
+    let periodic = detect_periodic(&kinds, filename);
+    let date = periodic.map_or_else(today, |(_, date)| date);
+
+    // "weekly.2026.02.23.md" -> "2026.02.23"
+    let display = filename
+        .strip_suffix(".md")
+        .and_then(|s| s.split_once('.'))
+        .map(|(_, d)| d.to_string())
+        .unwrap_or_else(|| date.format("%Y-%m-%d").to_string());
+
+    let mut ctx = Context::new().with_include_dir(notes_dir);
+    ctx.set_value("date", Value::Date { date, display });
+    ctx.set_value("today", Value::date(today()));
+    ctx.set_value("yesterday", Value::date(date - chrono::Duration::days(1)));
+    ctx.set_value("tomorrow", Value::date(date + chrono::Duration::days(1)));
+    ctx.set("week_number", date.format("%V").to_string());
+    ctx.set("filename", filename);
+    if let Some((kind, date)) = periodic {
+        let link = |offset| {
+            let name = kind.filename(kind.step(date, offset)?);
+            Some(name.trim_end_matches(".md").to_string())
+        };
+        ctx.set("kind", kind.name.clone());
+        if let Some(prev) = link(-1) {
+            ctx.set("prev_note", prev);
+        }
+        if let Some(next) = link(1) {
+            ctx.set("next_note", next);
+        }
+    }
+    ctx
 }
 
-pub fn create_monthly(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
-    let now = Local::now();
-    let filename = format!("monthly.{}.{:02}.md", now.year(), now.month());
-    let path = notes_dir.join(&filename);
+/// The rendered schema for a new note, or an empty note if no schema
+/// matches. Unknown variables and template errors are reported on stderr;
+/// a schema that fails to render is used as written.
+fn schema_content(notes_dir: &std::path::Path, cfg: &config::Config, filename: &str) -> String {
+    let (Some(schema), Some(content)) = (
+        cfg.schema_file(filename),
+        cfg.resolve_schema(notes_dir, filename),
+    ) else {
+        return String::new();
+    };
+    match template::render(&content, &schema_context(notes_dir, filename)) {
+        Ok(rendered) => {
+            if !rendered.unknown.is_empty() {
+                eprintln!(
+                    "Warning: {schema}: unknown template variables: {}",
+                    rendered.unknown.join(", ")
+                );
+            }
+            rendered.text
+        }
+        Err(err) => {
+            eprintln!("Warning: {schema}: {err}");
+            content
+        }
+    }
+}
 
-    if !path.exists() {
-        fs::create_dir_all(notes_dir)?;
+/// Unfinished tasks found in one earlier note.
+struct CarrySource {
+    path: PathBuf,
+    content: String,
+    tasks: Vec<Task>,
+    /// Whether the tasks are rolled up from another kind.
+    rollup: bool,
+}
 
-        let cfg = config::load_config(notes_dir);
-        let content = cfg.resolve_schema(notes_dir, &filename).unwrap_or_default();
-        fs::write(&path, content)?;
+/// The notes a new note of `kind` for `date` carries tasks from: the most
+/// recent earlier note of the same kind, or every note of the `from` kind
+/// in the previous period. A rollup collects tasks that were carried on
+/// within the `from` kind too, since they were still open.
+fn collect_carry_over(
+    notes_dir: &std::path::Path,
+    kind: &PeriodicKind,
+    date: NaiveDate,
+    rule: &CarryOverConfig,
+) -> std::io::Result<Vec<CarrySource>> {
+    let kinds = periodic_kinds(notes_dir);
+    let source_name = rule.from.as_deref().unwrap_or(&kind.name);
+    let Some(source_kind) = kinds.iter().find(|other| other.name == source_name) else {
+        return Ok(Vec::new());
+    };
+
+    let start = kind.start_of(date);
+    let mut notes: Vec<(NaiveDate, String)> = list_notes_in(notes_dir)
+        .into_iter()
+        .filter_map(|name| source_kind.parse_filename(&name).map(|date| (date, name)))
+        .filter(|(date, _)| *date < start)
+        .collect();
+    notes.sort();
+
+    let rollup = source_kind.name != kind.name;
+    let selected: Vec<String> = if !rollup {
+        notes.pop().map(|(_, name)| name).into_iter().collect()
+    } else {
+        let previous = kind.step(date, -1).unwrap_or(NaiveDate::MIN);
+        notes
+            .into_iter()
+            .filter(|(date, _)| *date >= previous)
+            .map(|(_, name)| name)
+            .collect()
+    };
+
+    let mut sources = Vec::new();
+    for name in selected {
+        let path = notes_dir.join(&name);
+        let content = fs::read_to_string(&path)?;
+        let heading = rule.heading.as_deref();
+        let tasks = if rollup {
+            tasks::open_tasks(&content, heading)
+        } else {
+            tasks::unchecked_tasks(&content, heading)
+        };
+        if !tasks.is_empty() {
+            sources.push(CarrySource {
+                path,
+                content,
+                tasks,
+                rollup,
+            });
+        }
     }
+    Ok(sources)
+}
 
-    Ok(path)
+pub fn open_periodic(kind: &PeriodicKind, date: NaiveDate) -> std::io::Result<()> {
+    let dir = notes_dir();
+    let path = create_periodic(&dir, kind, date)?;
+    crate::opener::open(&path, None)
+}
+
+/// The note one or more periods away from `filename`, created on demand.
+/// Notes that are not periodic step from today's daily note.
+pub fn create_adjacent(
+    notes_dir: &std::path::Path,
+    filename: Option<&str>,
+    offset: i64,
+) -> std::io::Result<PathBuf> {
+    let kinds = periodic_kinds(notes_dir);
+    let (kind, date) = match filename.and_then(|name| detect_periodic(&kinds, name)) {
+        Some((kind, date)) => (kind.clone(), date),
+        None => (named_kind(notes_dir, "daily"), today()),
+    };
+    let date = kind.step(date, offset).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "no note that far away")
+    })?;
+    create_periodic(notes_dir, &kind, date)
+}
+
+fn named_kind(notes_dir: &std::path::Path, name: &str) -> PeriodicKind {
+    periodic_kinds(notes_dir)
+        .into_iter()
+        .chain(PeriodicKind::builtin())
+        .find(|kind| kind.name == name)
+        .expect("built-in periodic kind")
+}
+
+pub fn create_daily(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
+    create_periodic(notes_dir, &named_kind(notes_dir, "daily"), today())
+}
+
+pub fn create_weekly(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
+    create_periodic(notes_dir, &named_kind(notes_dir, "weekly"), today())
+}
+
+pub fn create_monthly(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
+    create_periodic(notes_dir, &named_kind(notes_dir, "monthly"), today())
 }
 
 pub fn open_scratch() -> std::io::Result<()> {
     let dir = notes_dir();
     let path = create_scratch(&dir)?;
-    open_yazi(&path)
+    crate::opener::open(&path, None)
 }
 
 pub fn create_scratch(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
@@ -96,15 +513,19 @@ pub fn create_scratch(notes_dir: &std::path::Path) -> std::io::Result<PathBuf> {
     fs::create_dir_all(notes_dir)?;
 
     let cfg = config::load_config(notes_dir);
-    let content = cfg.resolve_schema(notes_dir, &filename).unwrap_or_default();
+    let content = schema_content(notes_dir, &cfg, &filename);
     fs::write(&path, content)?;
 
     Ok(path)
 }
 
 pub fn list_notes() -> Vec<String> {
-    let dir = notes_dir();
-    let entries = match fs::read_dir(&dir) {
+    list_notes_in(&notes_dir())
+}
+
+/// Markdown notes in `dir`, excluding schema files, sorted by name.
+pub fn list_notes_in(dir: &std::path::Path) -> Vec<String> {
+    let entries = match fs::read_dir(dir) {
         Ok(e) => e,
         Err(_) => return Vec::new(),
     };
@@ -113,7 +534,7 @@ pub fn list_notes() -> Vec<String> {
         .filter_map(|e| e.ok())
         .filter(|e| {
             let ft = e.file_type().ok();
-            ft.map_or(false, |ft| ft.is_file())
+            ft.is_some_and(|ft| ft.is_file())
         })
         .filter_map(|e| {
             let name = e.file_name().into_string().ok()?;
@@ -130,7 +551,11 @@ pub fn list_notes() -> Vec<String> {
 
 pub fn open_note(filename: &str) -> std::io::Result<()> {
     let path = notes_dir().join(filename);
-    open_yazi(&path)
+    crate::opener::open(&path, None)
+}
+
+pub fn open_note_path(path: &std::path::Path) -> std::io::Result<()> {
+    crate::opener::open(path, None)
 }
 
 pub fn create_and_open_note(name: &str) -> std::io::Result<()> {
@@ -145,19 +570,9 @@ pub fn create_and_open_note(name: &str) -> std::io::Result<()> {
     if !path.exists() {
         fs::create_dir_all(&dir)?;
         let cfg = config::load_config(&dir);
-        let content = cfg.resolve_schema(&dir, &filename).unwrap_or_default();
+        let content = schema_content(&dir, &cfg, &filename);
         fs::write(&path, content)?;
     }
 
-    open_yazi(&path)
-}
-
-fn open_yazi(path: &std::path::Path) -> std::io::Result<()> {
-    Command::new("yazi")
-        .arg(path)
-        .stdin(std::process::Stdio::inherit())
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .status()?;
-    Ok(())
+    crate::opener::open(&path, None)
 }
@@ -0,0 +1,147 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::global_config::{GlobalConfig, Origin};
+
+/// The program used to open notes and prompts, as a command template such
+/// as `nvim +{line} {path}` or `code --wait --goto {path}:{line}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opener {
+    template: String,
+    words: Vec<String>,
+    /// Where the command came from, shown in error messages.
+    pub origin: Origin,
+}
+
+impl Opener {
+    pub fn parse(template: &str, origin: Origin) -> Result<Opener, String> {
+        let words = split_words(template)?;
+        if words.is_empty() {
+            return Err("the opener command is empty".to_string());
+        }
+        Ok(Opener {
+            template: template.to_string(),
+            words,
+            origin,
+        })
+    }
+
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// The program and arguments that open `path`. `{path}` and `{line}`
+    /// (1 when no line is given) are substituted in every word; the path is
+    /// appended when the template has no `{path}`.
+    pub fn command(&self, path: &Path, line: Option<usize>) -> (String, Vec<String>) {
+        let path = path.display().to_string();
+        let line = line.unwrap_or(1).to_string();
+        let mut words: Vec<String> = self
+            .words
+            .iter()
+            .map(|word| word.replace("{path}", &path).replace("{line}", &line))
+            .collect();
+        if !self.words.iter().any(|word| word.contains("{path}")) {
+            words.push(path);
+        }
+        let program = words.remove(0);
+        (program, words)
+    }
+
+    /// Runs the opener on `path` in the foreground and waits for it.
+    pub fn open(&self, path: &Path, line: Option<usize>) -> io::Result<()> {
+        let (program, args) = self.command(path, line);
+        let status = Command::new(&program)
+            .args(&args)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .map_err(|err| {
+                if err.kind() == io::ErrorKind::NotFound {
+                    io::Error::new(
+                        err.kind(),
+                        format!(
+                            "cannot open {}: `{program}` (from {}) is not installed or not on PATH. \
+                             Set another with: axon config set opener 'nvim +{{line}} {{path}}'",
+                            path.display(),
+                            self.origin
+                        ),
+                    )
+                } else {
+                    io::Error::new(err.kind(), format!("cannot run `{program}`: {err}"))
+                }
+            })?;
+        if !status.success() {
+            return Err(io::Error::other(format!("`{program}` exited with {status}")));
+        }
+        Ok(())
+    }
+}
+
+/// The `opener` setting, then `$VISUAL`, then `$EDITOR`, then yazi.
+pub fn resolve(cfg: &GlobalConfig) -> Result<Opener, String> {
+    if let Some(template) = &cfg.opener {
+        return Opener::parse(template, Origin::File(crate::global_config::config_path()));
+    }
+    for var in ["VISUAL", "EDITOR"] {
+        if let Ok(command) = std::env::var(var)
+            && !command.trim().is_empty()
+        {
+            return Opener::parse(&command, Origin::Env(var));
+        }
+    }
+    Opener::parse("yazi", Origin::BuiltIn)
+}
+
+/// Opens `path` with the configured opener.
+pub fn open(path: &Path, line: Option<usize>) -> io::Result<()> {
+    let opener = resolve(&crate::global_config::load()).map_err(io::Error::other)?;
+    opener.open(path, line)
+}
+
+/// Splits a command line into words, honoring single quotes, double quotes,
+/// and backslash escapes.
+pub fn split_words(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(format!("unterminated quote in `{command}`"));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
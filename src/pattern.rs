@@ -24,6 +24,9 @@ pub struct SopFilename {
 }
 
 impl ParsedFilename {
+    /// Every value of [`ParsedFilename::category`].
+    pub const CATEGORIES: [&'static str; 2] = ["feat", "sop"];
+
     pub fn repo(&self) -> &str {
         match self {
             ParsedFilename::Feat(f) => &f.repo,
@@ -44,6 +47,18 @@ impl ParsedFilename {
             ParsedFilename::Sop(s) => s.version,
         }
     }
+
+    /// The filename without its version, shared by every version of a prompt:
+    /// `myapp.feat.auth.spec.initial.v2.md` -> `myapp.feat.auth.spec.initial`.
+    pub fn identity(&self) -> String {
+        match self {
+            ParsedFilename::Feat(f) => format!(
+                "{}.feat.{}.{}.{}",
+                f.repo, f.feature, f.doc_type, f.variant
+            ),
+            ParsedFilename::Sop(s) => format!("{}.sop.{}", s.repo, s.name),
+        }
+    }
 }
 
 static FEAT_REGEX: OnceLock<Regex> = OnceLock::new();
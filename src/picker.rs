@@ -0,0 +1,325 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    style::{self, Stylize},
+    terminal::{self, ClearType},
+    ExecutableCommand, QueueableCommand,
+};
+
+use crate::fuzzy::{self, Match};
+use crate::global_config::GlobalConfig;
+
+/// Which fuzzy finder the TUI's lists use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// The picker built into the TUI.
+    #[default]
+    Builtin,
+    /// `fzf`, falling back to the built-in picker when it's not installed.
+    Fzf,
+}
+
+impl Backend {
+    pub const NAMES: [&'static str; 2] = ["builtin", "fzf"];
+
+    pub fn parse(name: &str) -> Result<Backend, String> {
+        match name {
+            "builtin" => Ok(Backend::Builtin),
+            "fzf" => Ok(Backend::Fzf),
+            _ => Err(format!(
+                "unknown picker '{name}' (expected {})",
+                Backend::NAMES.join(" or ")
+            )),
+        }
+    }
+
+    /// The `picker` setting; the default when unset or invalid.
+    pub fn from_config(cfg: &GlobalConfig) -> Backend {
+        cfg.picker
+            .as_deref()
+            .and_then(|name| Backend::parse(name).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Builtin => "builtin",
+            Backend::Fzf => "fzf",
+        }
+    }
+}
+
+/// A list filtered by a fuzzy query as it's typed.
+#[derive(Debug, Clone)]
+pub struct PickerState {
+    items: Vec<String>,
+    query: String,
+    matches: Vec<Match>,
+    cursor: usize,
+}
+
+impl PickerState {
+    pub fn new(items: Vec<String>) -> Self {
+        let matches = fuzzy::rank("", &items);
+        Self {
+            items,
+            query: String::new(),
+            matches,
+            cursor: 0,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// Matching items, best first.
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    /// Index into [`PickerState::matches`] of the highlighted item.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.refilter();
+    }
+
+    pub fn pop(&mut self) {
+        if self.query.pop().is_some() {
+            self.refilter();
+        }
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() - 1;
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// What Enter picks: the highlighted item or, when nothing matches, the
+    /// query itself, so that a new name can be typed. `None` if both are
+    /// empty.
+    pub fn choice(&self) -> Option<String> {
+        if let Some(found) = self.matches.get(self.cursor) {
+            return Some(self.items[found.index].clone());
+        }
+        let query = self.query.trim();
+        (!query.is_empty()).then(|| query.to_string())
+    }
+
+    fn refilter(&mut self) {
+        self.matches = fuzzy::rank(&self.query, &self.items);
+        self.cursor = 0;
+    }
+}
+
+/// Lets the user pick one of `items`, or type a name that matches none,
+/// on the TUI's alternate screen. The highlighted item is previewed when
+/// it's a file in `dir`. Returns `None` if the user cancelled.
+pub fn pick(
+    stdout: &mut io::Stdout,
+    title: &str,
+    items: &[String],
+    dir: Option<&Path>,
+) -> io::Result<Option<String>> {
+    let mut state = PickerState::new(items.to_vec());
+    let mut preview_scroll: usize = 0;
+    let mut previewed: Option<String> = None;
+    stdout.execute(cursor::Show)?;
+    let result = loop {
+        // A new highlighted item is previewed from the top.
+        let current = state.choice();
+        if current != previewed {
+            preview_scroll = 0;
+            previewed = current;
+        }
+        draw_picker(stdout, title, &state, dir, &mut preview_scroll)?;
+        match event::read()? {
+            Event::Key(KeyEvent { code: KeyCode::Esc, .. })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => break None,
+            Event::Key(KeyEvent { code: KeyCode::Enter, .. }) => {
+                if let Some(choice) = state.choice() {
+                    break Some(choice);
+                }
+            }
+            Event::Key(KeyEvent { code: KeyCode::Down, .. })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => state.move_by(1),
+            Event::Key(KeyEvent { code: KeyCode::Up, .. })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => state.move_by(-1),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::PageDown,
+                ..
+            }) => preview_scroll += crate::tui::half_page()?,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::PageUp, ..
+            }) => preview_scroll = preview_scroll.saturating_sub(crate::tui::half_page()?),
+            Event::Key(KeyEvent { code: KeyCode::Backspace, .. }) => state.pop(),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            }) if !modifiers.contains(KeyModifiers::CONTROL) => state.push(c),
+            _ => {}
+        }
+    };
+    stdout.execute(cursor::Hide)?;
+    Ok(result)
+}
+
+fn draw_picker(
+    stdout: &mut io::Stdout,
+    title: &str,
+    state: &PickerState,
+    dir: Option<&Path>,
+    preview_scroll: &mut usize,
+) -> io::Result<()> {
+    let (cols, rows) = terminal::size()?;
+    let list_width = dir.and(crate::tui::list_width(cols));
+
+    stdout.queue(terminal::Clear(ClearType::All))?;
+
+    let title_col = cols.saturating_sub(title.len() as u16) / 2;
+    stdout.queue(cursor::MoveTo(title_col, 1))?;
+    stdout.queue(style::PrintStyledContent(title.bold()))?;
+
+    // Matches, scrolled to keep the highlighted one in view
+    let list_start_row: u16 = 3;
+    let prompt_row = rows.saturating_sub(3);
+    let available = prompt_row.saturating_sub(list_start_row + 1) as usize;
+    let scroll_offset = (state.cursor() + 1).saturating_sub(available);
+    let pad: u16 = 4;
+    let max_width = list_width.unwrap_or(cols).saturating_sub(pad + 2) as usize;
+
+    for (i, found) in state.matches().iter().skip(scroll_offset).take(available).enumerate() {
+        stdout.queue(cursor::MoveTo(pad, list_start_row + i as u16))?;
+        let is_cursor = scroll_offset + i == state.cursor();
+        for (pos, c) in state.items()[found.index].chars().take(max_width).enumerate() {
+            let text = c.to_string();
+            let styled = match (found.positions.binary_search(&pos).is_ok(), is_cursor) {
+                (true, _) => text.green().bold(),
+                (false, true) => text.cyan().bold(),
+                (false, false) => text.stylize(),
+            };
+            stdout.queue(style::PrintStyledContent(styled))?;
+        }
+    }
+
+    // Preview of the highlighted item, when it's a file and there is room
+    if let (Some(width), Some(dir), Some(found)) = (list_width, dir, state.matches().get(state.cursor())) {
+        let path = dir.join(&state.items()[found.index]);
+        if path.is_file() {
+            crate::tui::draw_preview_pane(stdout, &path, width, list_start_row, prompt_row, preview_scroll)?;
+        }
+    }
+
+    if state.matches().is_empty() && !state.query().trim().is_empty() {
+        stdout.queue(cursor::MoveTo(pad, list_start_row))?;
+        stdout.queue(style::PrintStyledContent("no matches".dark_grey()))?;
+    }
+
+    // Hint
+    let hint_row = rows.saturating_sub(2);
+    stdout.queue(cursor::MoveTo(4, hint_row))?;
+    stdout.queue(style::PrintStyledContent("enter".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" open / select  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("↑ ↓".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" move  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("esc".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" cancel".stylize()))?;
+
+    // Query, with a match count like fzf's
+    let prompt = "> ";
+    let count = format!("  {}/{}", state.matches().len(), state.items().len());
+    stdout.queue(cursor::MoveTo(4, prompt_row))?;
+    stdout.queue(style::PrintStyledContent(prompt.bold()))?;
+    stdout.queue(style::PrintStyledContent(state.query().stylize()))?;
+    stdout.queue(style::PrintStyledContent(count.dark_grey()))?;
+    let cursor_col = 4 + prompt.len() as u16 + state.query().chars().count() as u16;
+    stdout.queue(cursor::MoveTo(cursor_col, prompt_row))?;
+
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Runs fzf on `items`. Returns:
+/// - Ok(Some(name)) if user selected/typed something
+/// - Ok(None) if user escaped/cancelled
+///
+/// Fails with [`io::ErrorKind::NotFound`] when fzf is not installed.
+pub fn fzf_pick(items: &[String]) -> io::Result<Option<String>> {
+    use std::process::{Command, Stdio};
+
+    let input = items.join("\n");
+    let mut child = Command::new("fzf")
+        .args([
+            "--print-query",
+            "--header",
+            "enter: open / select  esc: cancel",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    let output = child.wait_with_output()?;
+
+    // fzf exit 130 = esc/ctrl-c, 1 = no match (but --print-query still prints query)
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut lines = text.lines();
+    let query = lines.next().unwrap_or("").trim().to_string();
+    let selection = lines.next().unwrap_or("").trim().to_string();
+
+    if output.status.code() == Some(130) {
+        return Ok(None);
+    }
+
+    // If user selected an existing match, prefer that
+    if !selection.is_empty() {
+        return Ok(Some(selection));
+    }
+    // Otherwise use the raw query (no match — will create)
+    if !query.is_empty() {
+        return Ok(Some(query));
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,279 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crossterm::{
+    cursor,
+    style::{self, Stylize},
+    QueueableCommand,
+};
+use regex::Regex;
+
+use crate::pattern::{parse_filename, ParsedFilename};
+
+/// How a piece of previewed text is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    /// A heading line, by level (1-6).
+    Heading(u8),
+    /// `-`, `*`, `+`, or `1.` starting a list item.
+    ListMarker,
+    /// `[ ]`
+    TaskOpen,
+    /// `[x]`
+    TaskDone,
+    /// The text of a finished task.
+    Done,
+    /// Inline code or a line in a code block.
+    Code,
+    /// A code block's opening or closing fence.
+    Fence,
+    Quote,
+    /// The name of a detail, such as `repo:`.
+    Label,
+    /// A detail's value, or a separator.
+    Meta,
+}
+
+/// A run of text in one style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    fn new(text: &str, style: Style) -> Span {
+        Span {
+            text: text.to_string(),
+            style,
+        }
+    }
+}
+
+/// One line of the preview.
+pub type Line = Vec<Span>;
+
+static LIST_ITEM_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Indent, marker, and an optional task checkbox.
+fn list_item_regex() -> &'static Regex {
+    LIST_ITEM_REGEX.get_or_init(|| {
+        Regex::new(r"^(\s*)([-*+]|\d+[.)])(\s+)(\[[ xX]\]\s)?").unwrap()
+    })
+}
+
+/// Styles `source` line by line: headings, list markers, task checkboxes,
+/// quotes, code blocks, and inline code. Tabs become four spaces.
+pub fn render_markdown(source: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for raw in source.lines() {
+        let line = raw.replace('\t', "    ");
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            lines.push(vec![Span::new(&line, Style::Fence)]);
+        } else if in_code {
+            lines.push(vec![Span::new(&line, Style::Code)]);
+        } else if let Some(level) = heading_level(trimmed) {
+            lines.push(vec![Span::new(&line, Style::Heading(level))]);
+        } else if trimmed.starts_with('>') {
+            lines.push(vec![Span::new(&line, Style::Quote)]);
+        } else if let Some(caps) = list_item_regex().captures(&line) {
+            let mut spans = vec![
+                Span::new(&caps[1], Style::Plain),
+                Span::new(&caps[2], Style::ListMarker),
+                Span::new(&caps[3], Style::Plain),
+            ];
+            let rest = &line[caps[0].len()..];
+            match caps.get(4) {
+                Some(task) if task.as_str().starts_with("[ ]") => {
+                    spans.push(Span::new(task.as_str(), Style::TaskOpen));
+                    spans.extend(inline_code(rest, Style::Plain));
+                }
+                Some(task) => {
+                    spans.push(Span::new(task.as_str(), Style::TaskDone));
+                    spans.push(Span::new(rest, Style::Done));
+                }
+                None => spans.extend(inline_code(rest, Style::Plain)),
+            }
+            lines.push(spans.into_iter().filter(|span| !span.text.is_empty()).collect());
+        } else {
+            lines.push(inline_code(&line, Style::Plain));
+        }
+    }
+    lines
+}
+
+fn heading_level(line: &str) -> Option<u8> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' '))).then_some(level as u8)
+}
+
+/// Splits `text` into `style` runs and `` `code` `` spans. An unclosed
+/// backtick is kept as text.
+fn inline_code(text: &str, style: Style) -> Line {
+    let mut spans = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('`') {
+        let Some(len) = rest[start + 1..].find('`') else {
+            break;
+        };
+        let end = start + 1 + len + 1;
+        if start > 0 {
+            spans.push(Span::new(&rest[..start], style));
+        }
+        spans.push(Span::new(&rest[start..end], Style::Code));
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() || spans.is_empty() {
+        spans.push(Span::new(rest, style));
+    }
+    spans
+}
+
+/// Breaks `line` into lines at most `width` characters wide, keeping each
+/// span's style. An empty line stays one empty line.
+pub fn wrap(line: &Line, width: usize) -> Vec<Line> {
+    let width = width.max(1);
+    let mut lines = vec![Vec::new()];
+    let mut used = 0;
+    for span in line {
+        let mut chars = span.text.chars().peekable();
+        while chars.peek().is_some() {
+            if used == width {
+                lines.push(Vec::new());
+                used = 0;
+            }
+            let text: String = chars.by_ref().take(width - used).collect();
+            used += text.chars().count();
+            lines.last_mut().unwrap().push(Span {
+                text,
+                style: span.style,
+            });
+        }
+    }
+    lines
+}
+
+/// Facts shown above the content: the parsed filename fields of a prompt,
+/// then the file's size and when it was last modified.
+pub fn details(path: &Path) -> Vec<(&'static str, String)> {
+    let mut details = Vec::new();
+    if let Some(name) = crate::fs_utils::file_name_string(path)
+        && let Ok(parsed) = parse_filename(&name)
+    {
+        details.push(("repo", parsed.repo().to_string()));
+        details.push(("category", parsed.category().to_string()));
+        match &parsed {
+            ParsedFilename::Feat(feat) => {
+                details.push(("feature", feat.feature.clone()));
+                details.push(("type", feat.doc_type.clone()));
+                details.push(("variant", feat.variant.clone()));
+            }
+            ParsedFilename::Sop(sop) => details.push(("name", sop.name.clone())),
+        }
+        details.push(("version", parsed.version().to_string()));
+    }
+    if let Ok(meta) = std::fs::metadata(path) {
+        details.push(("size", format_size(meta.len())));
+        if let Ok(modified) = meta.modified() {
+            let modified: chrono::DateTime<chrono::Local> = modified.into();
+            details.push(("modified", modified.format("%Y-%m-%d %H:%M").to_string()));
+        }
+    }
+    details
+}
+
+/// `812 B`, `4.2 KB`, or `1.3 MB`.
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// The preview of the file at `path` for a pane `width` characters wide:
+/// its [`details`], as many to a line as fit, a rule, and its content
+/// styled and wrapped.
+pub fn build(path: &Path, width: usize) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut current: Line = Vec::new();
+    let mut used = 0;
+    for (label, value) in details(path) {
+        let len = label.chars().count() + 2 + value.chars().count();
+        if used > 0 && used + 2 + len > width {
+            lines.extend(wrap(&current, width));
+            current = Vec::new();
+            used = 0;
+        }
+        if used > 0 {
+            current.push(Span::new("  ", Style::Plain));
+            used += 2;
+        }
+        current.push(Span::new(&format!("{label}: "), Style::Label));
+        current.push(Span::new(&value, Style::Meta));
+        used += len;
+    }
+    if !current.is_empty() {
+        lines.extend(wrap(&current, width));
+    }
+    lines.push(vec![Span::new(&"─".repeat(width), Style::Meta)]);
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            for line in render_markdown(&content) {
+                lines.extend(wrap(&line, width));
+            }
+        }
+        Err(err) => lines.push(vec![Span::new(&format!("(cannot read: {err})"), Style::Meta)]),
+    }
+    lines
+}
+
+/// Where on screen a preview is drawn.
+#[derive(Debug, Clone, Copy)]
+pub struct Area {
+    pub col: u16,
+    pub row: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Draws the preview of `path` into `area`, starting `scroll` lines down,
+/// with the area's last row saying how many lines are left below.
+/// `scroll` is clamped so the last line stays at the bottom of the area.
+pub fn draw(stdout: &mut io::Stdout, area: Area, path: &Path, scroll: &mut usize) -> io::Result<()> {
+    let lines = build(path, area.width as usize);
+    let height = area.height.saturating_sub(1) as usize;
+    *scroll = (*scroll).min(lines.len().saturating_sub(height));
+    for (i, line) in lines.iter().skip(*scroll).take(height).enumerate() {
+        stdout.queue(cursor::MoveTo(area.col, area.row + i as u16))?;
+        for span in line {
+            let text = span.text.as_str();
+            let styled = match span.style {
+                Style::Plain => text.stylize(),
+                Style::Heading(1) => text.bold().magenta().underlined(),
+                Style::Heading(2) => text.bold().magenta(),
+                Style::Heading(_) => text.bold(),
+                Style::ListMarker => text.cyan(),
+                Style::TaskOpen => text.bold().yellow(),
+                Style::TaskDone => text.green(),
+                Style::Done | Style::Fence | Style::Meta => text.dark_grey(),
+                Style::Code => text.dark_yellow(),
+                Style::Quote => text.italic(),
+                Style::Label => text.bold(),
+            };
+            stdout.queue(style::PrintStyledContent(styled))?;
+        }
+    }
+    if lines.len() > *scroll + height {
+        let more = format!("↓ {} more", lines.len() - *scroll - height);
+        stdout.queue(cursor::MoveTo(area.col, area.row + height as u16))?;
+        stdout.queue(style::PrintStyledContent(more.dark_grey()))?;
+    }
+    stdout.flush()
+}
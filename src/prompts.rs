@@ -1,6 +1,13 @@
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::global_config::GlobalConfig;
+use crate::pattern::parse_filename;
+use crate::runner::{Invocation, Runner};
+use crate::template::{self, Context};
 
 pub fn prompts_dir() -> PathBuf {
     let cfg = crate::global_config::load();
@@ -8,15 +15,19 @@ pub fn prompts_dir() -> PathBuf {
 }
 
 pub fn list_prompts() -> Vec<String> {
-    let dir = prompts_dir();
-    let entries = match fs::read_dir(&dir) {
+    list_prompts_in(&prompts_dir())
+}
+
+/// The prompt files in `dir`, sorted.
+pub fn list_prompts_in(dir: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(dir) {
         Ok(e) => e,
         Err(_) => return Vec::new(),
     };
 
     let mut files: Vec<String> = entries
         .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().ok().map_or(false, |ft| ft.is_file()))
+        .filter(|e| e.file_type().ok().is_some_and(|ft| ft.is_file()))
         .filter_map(|e| {
             let name = e.file_name().into_string().ok()?;
             if !name.ends_with(".md") {
@@ -47,54 +58,174 @@ pub fn create_and_open_prompt(name: &str) -> std::io::Result<()> {
         fs::write(&path, "")?;
     }
 
-    Command::new("yazi")
-        .arg(&path)
-        .stdin(std::process::Stdio::inherit())
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .status()?;
+    crate::opener::open(&path, None)
+}
 
-    Ok(())
+/// Runs `prompt_filename` from the prompts directory with `runner`, or
+/// starts a session with only `prompts.md` when it is `None`.
+pub fn run_prompt(prompt_filename: Option<&str>, runner: &Runner) -> std::io::Result<()> {
+    let cfg = crate::global_config::load();
+    let dir = std::path::absolute(cfg.prompts_dir())?;
+    runner.run(&invocation(&cfg, prompt_filename.map(|name| dir.join(name)))?)
 }
 
-pub fn open_claude_session() -> std::io::Result<()> {
-    let dir = prompts_dir();
-    let prompts_md = dir.join("prompts.md");
+/// What a runner is handed for `prompt_path`. Prompts whose `{repo}` segment
+/// is in the `[repos]` table run in that repo's directory. Prompts with
+/// includes are composed here, in memory; the runner writes the copy it
+/// hands the agent.
+pub fn invocation(cfg: &GlobalConfig, prompt_path: Option<PathBuf>) -> std::io::Result<Invocation> {
+    let dir = std::path::absolute(cfg.prompts_dir())?;
+    let cwd = prompt_path
+        .as_deref()
+        .and_then(|path| path.file_name()?.to_str())
+        .and_then(|name| parse_filename(name).ok())
+        .and_then(|parsed| cfg.repo_dir(parsed.repo()));
+    let composed = match prompt_path.as_deref() {
+        Some(path) => compose(path)
+            .map_err(|err| std::io::Error::other(format!("{}: {err}", path.display())))?,
+        None => None,
+    };
+    Ok(Invocation {
+        prompt_path,
+        prompts_md: dir.join("prompts.md"),
+        cwd,
+        composed,
+    })
+}
 
-    let instruction = format!(
-        "read {} and ask for next instruction",
-        prompts_md.display()
-    );
+/// Finds the prompt `query` names among `files`: the filename itself (`.md`
+/// optional), the latest version of a prompt identity such as
+/// `myapp.feat.auth.spec.initial`, or else the best fuzzy match among the
+/// latest version of each prompt.
+pub fn resolve_prompt(query: &str, files: &[String]) -> Result<String, String> {
+    if let Some(file) = find_named(query, files) {
+        return Ok(file.clone());
+    }
 
-    Command::new("claude")
-        .arg(&instruction)
-        .stdin(std::process::Stdio::inherit())
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .status()?;
+    let latest = latest_versions(files);
+    let mut matches: Vec<(i64, &String)> = latest
+        .into_iter()
+        .filter_map(|file| Some((crate::fuzzy::score(query, file)?, file)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    match matches.as_slice() {
+        [] => Err(format!("no prompt matches '{query}'")),
+        [(best, file), rest @ ..] => {
+            let tied: Vec<&str> = rest
+                .iter()
+                .take_while(|(score, _)| score == best)
+                .map(|(_, file)| file.as_str())
+                .collect();
+            if tied.is_empty() {
+                Ok((*file).clone())
+            } else {
+                Err(format!(
+                    "'{query}' matches several prompts equally well: {}, {}",
+                    file,
+                    tied.join(", ")
+                ))
+            }
+        }
+    }
+}
 
-    Ok(())
+/// The filename `name` (`.md` optional), or the latest version of the
+/// prompt identity `name`.
+fn find_named<'a>(name: &str, files: &'a [String]) -> Option<&'a String> {
+    let name = name.strip_suffix(".md").unwrap_or(name);
+    files
+        .iter()
+        .find(|file| file.strip_suffix(".md") == Some(name))
+        .or_else(|| {
+            files
+                .iter()
+                .filter_map(|file| {
+                    let parsed = parse_filename(file).ok()?;
+                    (parsed.identity() == name).then(|| (parsed.version(), file))
+                })
+                .max_by_key(|(version, _)| *version)
+                .map(|(_, file)| file)
+        })
 }
 
-pub fn run_prompt_with_claude(prompt_filename: &str) -> std::io::Result<()> {
-    let dir = prompts_dir();
-    let prompt_path = dir.join(prompt_filename);
-    let prompts_md = dir.join("prompts.md");
-
-    let instruction = format!(
-        "read {} and {} and ask for next instruction",
-        prompts_md.display(),
-        prompt_path.display()
-    );
-
-    Command::new("claude")
-        .arg(&instruction)
-        .stdin(std::process::Stdio::inherit())
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .status()?;
-
-    Ok(())
+static INCLUDE_COMMENT_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// `<!-- axon:include name -->`, the comment form of `{{> name}}`.
+fn include_comment_regex() -> &'static Regex {
+    INCLUDE_COMMENT_REGEX.get_or_init(|| {
+        Regex::new(r"<!--\s*axon:include\s+(\S+?)\s*-->").expect("invalid include comment regex")
+    })
+}
+
+/// Whether `source` includes other prompts.
+pub fn has_includes(source: &str) -> bool {
+    source.contains("{{>") || include_comment_regex().is_match(source)
+}
+
+/// Rewrites `<!-- axon:include name -->` as `{{> name}}`.
+pub fn expand_include_comments(source: &str) -> String {
+    include_comment_regex()
+        .replace_all(source, "{{> $1}}")
+        .into_owned()
+}
+
+/// The file in `dir` that `{{> name}}` includes: a path relative to `dir`,
+/// a filename with `.md` optional, or the latest version of a prompt named
+/// without its version.
+pub fn resolve_include(name: &str, dir: &Path) -> Result<String, String> {
+    if name.contains('/') && dir.join(name).is_file() {
+        return Ok(name.to_string());
+    }
+    find_named(name, &list_prompts_in(dir))
+        .cloned()
+        .ok_or_else(|| format!("cannot include '{name}': no such prompt in {}", dir.display()))
+}
+
+/// A template context resolving includes against the prompts in `dir`.
+pub fn include_context<'a>(dir: &Path) -> Context<'a> {
+    let dir = dir.to_path_buf();
+    Context::new().with_includes(move |name| {
+        let file = resolve_include(name, &dir)?;
+        let content = fs::read_to_string(dir.join(&file))
+            .map_err(|err| format!("cannot include '{name}': {err}"))?;
+        Ok((file, expand_include_comments(&content)))
+    })
+}
+
+/// The prompt at `path` with its includes expanded, or `None` if it has
+/// none. Variables are left as written.
+pub fn compose(path: &Path) -> Result<Option<String>, String> {
+    let source = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    if !has_includes(&source) {
+        return Ok(None);
+    }
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut ctx = include_context(dir);
+    if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+        ctx = ctx.with_root(name);
+    }
+    template::expand_includes(&expand_include_comments(&source), &ctx).map(Some)
+}
+
+/// `source` with its includes, resolved against `dir`, expanded.
+pub fn compose_source(source: &str, dir: &Path) -> Result<String, String> {
+    template::expand_includes(&expand_include_comments(source), &include_context(dir))
+}
+
+/// `files` without the versions a newer version of the same prompt replaces.
+fn latest_versions(files: &[String]) -> Vec<&String> {
+    let mut newest: std::collections::BTreeMap<String, u32> = std::collections::BTreeMap::new();
+    for parsed in files.iter().filter_map(|file| parse_filename(file).ok()) {
+        let version = newest.entry(parsed.identity()).or_default();
+        *version = (*version).max(parsed.version());
+    }
+    files
+        .iter()
+        .filter(|file| match parse_filename(file) {
+            Ok(parsed) => newest.get(&parsed.identity()) == Some(&parsed.version()),
+            Err(_) => true,
+        })
+        .collect()
 }
 
 pub const OPEN_SESSION: &str = "[open session]";
@@ -1,7 +1,9 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
-use std::path::Path;
+use std::path::{Component, Path};
+
+use crate::fs_utils::is_case_insensitive;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Placeholder(String);
@@ -51,7 +53,7 @@ pub fn parse_refactor_pattern(pattern: &str) -> Result<Vec<Token>, String> {
                 }
                 let mut name = String::new();
                 let mut closed = false;
-                while let Some((_, inner)) = chars.next() {
+                for (_, inner) in chars.by_ref() {
                     if inner == '}' {
                         closed = true;
                         break;
@@ -95,10 +97,10 @@ impl RefactorPattern {
         let tokens = parse_refactor_pattern(&normalized)?;
         let mut placeholders = BTreeSet::new();
         for token in &tokens {
-            if let Token::Placeholder(ph) = token {
-                if !placeholders.insert(ph.clone()) {
-                    return Err(format!("Duplicate placeholder {{{}}}", ph.name()));
-                }
+            if let Token::Placeholder(ph) = token
+                && !placeholders.insert(ph.clone())
+            {
+                return Err(format!("Duplicate placeholder {{{}}}", ph.name()));
             }
         }
         Ok(Self {
@@ -251,6 +253,24 @@ pub struct RenamePlan {
     pub to: String,
 }
 
+impl RenamePlan {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        RenamePlan {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+/// A whole-file content change made alongside renames, such as rewritten
+/// links. `file` is the path after the renames have been applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentEdit {
+    pub file: String,
+    pub before: String,
+    pub after: String,
+}
+
 pub fn match_files(files: &[&str], pattern: &str) -> Vec<String> {
     let normalized = normalize_pattern(pattern);
     let tokens = match parse_refactor_pattern(&normalized) {
@@ -259,10 +279,10 @@ pub fn match_files(files: &[&str], pattern: &str) -> Vec<String> {
     };
     let mut placeholders = BTreeSet::new();
     for token in &tokens {
-        if let Token::Placeholder(ph) = token {
-            if !placeholders.insert(ph.clone()) {
-                return Vec::new();
-            }
+        if let Token::Placeholder(ph) = token
+            && !placeholders.insert(ph.clone())
+        {
+            return Vec::new();
         }
     }
     let refactor_pattern = RefactorPattern {
@@ -391,13 +411,55 @@ pub fn check_existing_targets(
     Err(message)
 }
 
+/// Whether a rename changes nothing but the case of its path.
+pub fn is_case_only_rename(from: &str, to: &str) -> bool {
+    from != to && from.to_lowercase() == to.to_lowercase()
+}
+
+/// Whether the target of `rename` is taken by another file. On a
+/// case-insensitive filesystem the target of a case-only rename always
+/// "exists", because it names the source itself.
+pub fn target_conflicts(
+    rename: &RenamePlan,
+    exists: impl Fn(&str) -> bool,
+    folds_case: impl Fn(&str) -> bool,
+) -> bool {
+    if rename.from == rename.to || !exists(&rename.to) {
+        return false;
+    }
+    !(is_case_only_rename(&rename.from, &rename.to) && folds_case(&rename.to))
+}
+
+/// Whether the directory holding `path` is case-insensitive.
+pub fn folds_case_at(path: &Path) -> bool {
+    path.parent()
+        .map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+        .is_some_and(is_case_insensitive)
+}
+
 pub fn check_existing_target_paths(renames: &[RenamePlan]) -> Result<(), String> {
+    check_existing_target_paths_in(Path::new("."), renames)
+}
+
+/// Like [`check_existing_target_paths`], for paths relative to `root`.
+pub fn check_existing_target_paths_in(root: &Path, renames: &[RenamePlan]) -> Result<(), String> {
+    check_existing_target_paths_with(
+        renames,
+        |path| root.join(path).exists(),
+        |path| folds_case_at(&root.join(path)),
+    )
+}
+
+/// Checks targets against `exists`. A case-only rename whose target folds
+/// onto its own source (`folds_case`) is not a conflict.
+pub fn check_existing_target_paths_with(
+    renames: &[RenamePlan],
+    exists: impl Fn(&str) -> bool,
+    folds_case: impl Fn(&str) -> bool,
+) -> Result<(), String> {
     let mut conflicts = Vec::new();
     for rename in renames {
-        if rename.from == rename.to {
-            continue;
-        }
-        if Path::new(&rename.to).exists() {
+        if target_conflicts(rename, &exists, &folds_case) {
             conflicts.push((rename.from.clone(), rename.to.clone()));
         }
     }
@@ -424,28 +486,173 @@ pub fn build_rename_plans(
     for file in files {
         if let Some(values) = matcher.captures(file) {
             let target_name = apply_pattern(target, &values);
-            renames.push(RenamePlan {
-                from: file.to_string(),
-                to: target_name,
-            });
+            renames.push(RenamePlan::new(file.as_str(), target_name));
         }
     }
     Ok(renames)
 }
 
-pub fn write_journal(path: &Path, renames: &[RenamePlan]) -> Result<(), String> {
-    let payload = Journal { renames: renames.to_vec() };
+/// Writes renames still to be made, along with the content edits to apply
+/// once they are all done.
+pub fn write_journal(path: &Path, renames: &[RenamePlan], edits: &[ContentEdit]) -> Result<(), String> {
+    let payload = Journal {
+        renames: renames.to_vec(),
+        edits: edits.to_vec(),
+    };
     let contents = serde_json::to_string_pretty(&payload).map_err(|err| err.to_string())?;
     std::fs::write(path, contents).map_err(|err| err.to_string())
 }
 
-pub fn read_journal(path: &Path) -> Result<Vec<RenamePlan>, String> {
+pub fn read_journal(path: &Path) -> Result<(Vec<RenamePlan>, Vec<ContentEdit>), String> {
     let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
     let journal: Journal = serde_json::from_str(&contents).map_err(|err| err.to_string())?;
-    Ok(journal.renames)
+    Ok((journal.renames, journal.edits))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Journal {
     renames: Vec<RenamePlan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    edits: Vec<ContentEdit>,
+}
+
+/// Checks that every path in a plan is relative and stays inside the
+/// operation root, so an edited plan can't move files elsewhere.
+pub fn check_plan_paths(renames: &[RenamePlan]) -> Result<(), String> {
+    let escapes = |path: &str| {
+        path.is_empty()
+            || Path::new(path).components().any(|component| {
+                !matches!(component, Component::Normal(_) | Component::CurDir)
+            })
+    };
+    let outside: Vec<&str> = renames
+        .iter()
+        .flat_map(|rename| [rename.from.as_str(), rename.to.as_str()])
+        .filter(|path| escapes(path))
+        .collect();
+
+    if outside.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = String::from("Error: Plan paths must be relative and stay inside the operation root\n\n");
+    for path in outside {
+        message.push_str(&format!("  '{path}'\n"));
+    }
+    message.push_str("\nAborting. No files were renamed.");
+    Err(message)
+}
+
+pub fn check_plan_sources(root: &Path, renames: &[RenamePlan]) -> Result<(), String> {
+    let missing: Vec<&RenamePlan> = renames
+        .iter()
+        .filter(|rename| !root.join(&rename.from).exists())
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = String::from("Error: Source file does not exist\n\n");
+    for rename in missing {
+        message.push_str(&format!("  {}\n  (target: {})\n\n", rename.from, rename.to));
+    }
+    message.push_str("Aborting. No files were renamed.");
+    Err(message)
+}
+
+fn plan_delimiter(path: &Path) -> Option<char> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => Some(','),
+        Some("tsv") => Some('\t'),
+        _ => None,
+    }
+}
+
+/// Writes a rename plan for review. `.csv` and `.tsv` paths get a
+/// `from`/`to` table; anything else is written as JSON in the journal format.
+pub fn write_plan(path: &Path, renames: &[RenamePlan]) -> Result<(), String> {
+    let Some(delimiter) = plan_delimiter(path) else {
+        return write_journal(path, renames, &[]);
+    };
+    let mut contents = format!("from{delimiter}to\n");
+    for rename in renames {
+        contents.push_str(&quote_field(&rename.from, delimiter));
+        contents.push(delimiter);
+        contents.push_str(&quote_field(&rename.to, delimiter));
+        contents.push('\n');
+    }
+    std::fs::write(path, contents).map_err(|err| err.to_string())
+}
+
+/// Reads a rename plan written by [`write_plan`] or produced elsewhere. JSON
+/// plans may be a journal object or a bare array of `{from, to}` entries.
+pub fn read_plan(path: &Path) -> Result<Vec<RenamePlan>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let Some(delimiter) = plan_delimiter(path) else {
+        if let Ok(journal) = serde_json::from_str::<Journal>(&contents) {
+            return Ok(journal.renames);
+        }
+        return serde_json::from_str(&contents).map_err(|err| err.to_string());
+    };
+
+    let mut renames = Vec::new();
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = split_fields(line, delimiter)
+            .map_err(|err| format!("line {}: {err}", idx + 1))?;
+        if fields.len() != 2 {
+            return Err(format!(
+                "line {}: expected 2 columns (from, to), found {}",
+                idx + 1,
+                fields.len()
+            ));
+        }
+        if renames.is_empty() && fields[0] == "from" && fields[1] == "to" {
+            continue;
+        }
+        renames.push(RenamePlan::new(fields[0].trim(), fields[1].trim()));
+    }
+    Ok(renames)
+}
+
+fn quote_field(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn split_fields(line: &str, delimiter: char) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ch if ch == delimiter && !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+            }
+            ch => field.push(ch),
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quote".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
 }
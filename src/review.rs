@@ -0,0 +1,365 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    style::{self, Stylize},
+    terminal::{self, ClearType},
+    ExecutableCommand, QueueableCommand,
+};
+
+use crate::refactor::{folds_case_at, target_conflicts, RenamePlan};
+
+#[derive(Debug, Clone)]
+pub struct ReviewEntry {
+    pub plan: RenamePlan,
+    pub selected: bool,
+}
+
+/// Selection state for reviewing a refactor one file at a time.
+#[derive(Debug, Clone)]
+pub struct ReviewState {
+    pub entries: Vec<ReviewEntry>,
+    pub cursor: usize,
+}
+
+impl ReviewState {
+    pub fn new(renames: Vec<RenamePlan>) -> Self {
+        Self {
+            entries: renames
+                .into_iter()
+                .map(|plan| ReviewEntry {
+                    plan,
+                    selected: true,
+                })
+                .collect(),
+            cursor: 0,
+        }
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let last = self.entries.len() - 1;
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(entry) = self.entries.get_mut(self.cursor) {
+            entry.selected = !entry.selected;
+        }
+    }
+
+    /// Selects every entry, or deselects them all if all are selected.
+    pub fn toggle_all(&mut self) {
+        let select = !self.entries.iter().all(|entry| entry.selected);
+        for entry in &mut self.entries {
+            entry.selected = select;
+        }
+    }
+
+    pub fn set_target(&mut self, to: &str) {
+        if let Some(entry) = self.entries.get_mut(self.cursor) {
+            entry.plan.to = to.to_string();
+            entry.selected = true;
+        }
+    }
+
+    /// The directory part of the current entry's target, with a trailing
+    /// `/`, or `""` for a file in the root. Targets can only be renamed
+    /// within their directory.
+    pub fn target_dir(&self) -> &str {
+        self.entries.get(self.cursor).map_or("", |entry| {
+            let to = entry.plan.to.as_str();
+            to.rfind('/').map_or("", |idx| &to[..=idx])
+        })
+    }
+
+    /// Renames the current entry's target to the filename `name`, keeping
+    /// its directory.
+    pub fn set_target_name(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("the target name is empty".to_string());
+        }
+        if name.contains('/') || name == "." || name == ".." {
+            return Err(format!("'{name}' is not a filename; targets stay in their directory"));
+        }
+        let to = format!("{}{name}", self.target_dir());
+        self.set_target(&to);
+        Ok(())
+    }
+
+    /// Indices of selected entries whose target is shared with another
+    /// selected entry or, unless `force` is set, already exists. `folds_case`
+    /// reports whether a target lives on a case-insensitive filesystem.
+    pub fn conflicts(
+        &self,
+        force: bool,
+        exists: impl Fn(&str) -> bool,
+        folds_case: impl Fn(&str) -> bool,
+    ) -> BTreeSet<usize> {
+        let mut by_target: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if entry.selected {
+                by_target.entry(entry.plan.to.as_str()).or_default().push(idx);
+            }
+        }
+
+        let mut conflicts = BTreeSet::new();
+        for indices in by_target.into_values() {
+            if indices.len() > 1 {
+                conflicts.extend(indices);
+                continue;
+            }
+            let idx = indices[0];
+            if !force && target_conflicts(&self.entries[idx].plan, &exists, &folds_case) {
+                conflicts.insert(idx);
+            }
+        }
+        conflicts
+    }
+
+    pub fn selected(&self) -> Vec<RenamePlan> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.selected)
+            .map(|entry| entry.plan.clone())
+            .collect()
+    }
+}
+
+/// Shows every proposed rename in a full-screen list where entries can be
+/// deselected and targets edited. Paths are relative to `root`. Returns
+/// `None` if the review was cancelled.
+pub fn review_renames(
+    renames: Vec<RenamePlan>,
+    root: &Path,
+    force: bool,
+) -> io::Result<Option<Vec<RenamePlan>>> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    stdout.execute(terminal::EnterAlternateScreen)?;
+    stdout.execute(cursor::Hide)?;
+
+    let result = review_loop(&mut stdout, ReviewState::new(renames), root, force);
+
+    stdout.execute(cursor::Show)?;
+    stdout.execute(terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn review_loop(
+    stdout: &mut io::Stdout,
+    mut state: ReviewState,
+    root: &Path,
+    force: bool,
+) -> io::Result<Option<Vec<RenamePlan>>> {
+    let exists = |name: &str| root.join(name).exists();
+    let folds_case = |name: &str| folds_case_at(&root.join(name));
+    let mut message = String::new();
+
+    loop {
+        let conflicts = state.conflicts(force, exists, folds_case);
+        draw_review(stdout, &state, &conflicts, &message)?;
+        message.clear();
+
+        let page = (terminal::size()?.1 as isize - 6) / 2;
+        match event::read()? {
+            Event::Key(KeyEvent {
+                code: KeyCode::Esc | KeyCode::Char('q'),
+                ..
+            }) => return Ok(None),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => return Ok(None),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('j') | KeyCode::Down,
+                ..
+            }) => state.move_by(1),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('k') | KeyCode::Up,
+                ..
+            }) => state.move_by(-1),
+            Event::Key(KeyEvent {
+                code: KeyCode::PageDown,
+                ..
+            }) => state.move_by(page.max(1)),
+            Event::Key(KeyEvent {
+                code: KeyCode::PageUp,
+                ..
+            }) => state.move_by(-page.max(1)),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            }) => state.toggle(),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('a'),
+                ..
+            }) => state.toggle_all(),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('e'),
+                ..
+            }) => {
+                if let Some(edited) = edit_target(stdout, &state, &conflicts)?
+                    && let Err(err) = state.set_target_name(&edited)
+                {
+                    message = err;
+                }
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            }) => {
+                if !conflicts.is_empty() {
+                    message = format!("{} conflicts must be resolved first", conflicts.len());
+                    continue;
+                }
+                return Ok(Some(state.selected()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads a new filename for the current entry's target, shown after its
+/// directory. Returns `None` if the edit was cancelled.
+fn edit_target(
+    stdout: &mut io::Stdout,
+    state: &ReviewState,
+    conflicts: &BTreeSet<usize>,
+) -> io::Result<Option<String>> {
+    let dir = state.target_dir();
+    let mut input = state.entries[state.cursor].plan.to[dir.len()..].to_string();
+    stdout.execute(cursor::Show)?;
+
+    let result = loop {
+        draw_review(stdout, state, conflicts, "")?;
+        let (_, rows) = terminal::size()?;
+        let prompt_row = rows.saturating_sub(3);
+        let prompt = "target: ";
+        stdout.queue(cursor::MoveTo(2, prompt_row))?;
+        stdout.queue(terminal::Clear(ClearType::CurrentLine))?;
+        stdout.queue(cursor::MoveTo(2, prompt_row))?;
+        stdout.queue(style::PrintStyledContent(prompt.bold()))?;
+        stdout.queue(style::PrintStyledContent(dir.dark_grey()))?;
+        stdout.queue(style::PrintStyledContent(input.as_str().stylize()))?;
+        stdout.flush()?;
+
+        match event::read()? {
+            Event::Key(KeyEvent { code: KeyCode::Esc, .. }) => break None,
+            Event::Key(KeyEvent { code: KeyCode::Enter, .. }) => break Some(input),
+            Event::Key(KeyEvent { code: KeyCode::Backspace, .. }) => {
+                input.pop();
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            }) if !modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+            _ => {}
+        }
+    };
+
+    stdout.execute(cursor::Hide)?;
+    Ok(result)
+}
+
+fn draw_review(
+    stdout: &mut io::Stdout,
+    state: &ReviewState,
+    conflicts: &BTreeSet<usize>,
+    message: &str,
+) -> io::Result<()> {
+    let (cols, rows) = terminal::size()?;
+
+    stdout.queue(terminal::Clear(ClearType::All))?;
+
+    let title = "review renames";
+    let title_col = cols.saturating_sub(title.len() as u16) / 2;
+    stdout.queue(cursor::MoveTo(title_col, 1))?;
+    stdout.queue(style::PrintStyledContent(title.bold()))?;
+
+    // Each entry takes two rows: source, then target.
+    let list_start_row: u16 = 3;
+    let legend_row = rows.saturating_sub(2);
+    let available = (legend_row.saturating_sub(list_start_row + 2) / 2) as usize;
+    let max_width = cols.saturating_sub(10) as usize;
+
+    if available > 0 {
+        let scroll_offset = state.cursor.saturating_sub(available - 1);
+        let end = state.entries.len().min(scroll_offset + available);
+        for (i, entry) in state.entries[scroll_offset..end].iter().enumerate() {
+            let idx = scroll_offset + i;
+            let row = list_start_row + (i as u16) * 2;
+            let mark = if entry.selected { "[x] " } else { "[ ] " };
+            let from = truncate(&entry.plan.from, max_width);
+            let to = format!("-> {}", truncate(&entry.plan.to, max_width));
+
+            stdout.queue(cursor::MoveTo(2, row))?;
+            if idx == state.cursor {
+                stdout.queue(style::PrintStyledContent(mark.bold().cyan()))?;
+                stdout.queue(style::PrintStyledContent(from.bold().cyan()))?;
+            } else if entry.selected {
+                stdout.queue(style::PrintStyledContent(mark.stylize()))?;
+                stdout.queue(style::PrintStyledContent(from.stylize()))?;
+            } else {
+                stdout.queue(style::PrintStyledContent(mark.dark_grey()))?;
+                stdout.queue(style::PrintStyledContent(from.dark_grey()))?;
+            }
+
+            stdout.queue(cursor::MoveTo(8, row + 1))?;
+            if conflicts.contains(&idx) {
+                stdout.queue(style::PrintStyledContent(to.red()))?;
+                stdout.queue(style::PrintStyledContent(" (conflict)".red()))?;
+            } else if entry.selected {
+                stdout.queue(style::PrintStyledContent(to.green()))?;
+            } else {
+                stdout.queue(style::PrintStyledContent(to.dark_grey()))?;
+            }
+        }
+    }
+
+    let selected = state.entries.iter().filter(|entry| entry.selected).count();
+    let status = format!(
+        "{selected} of {} selected, {} conflicts",
+        state.entries.len(),
+        conflicts.len()
+    );
+    stdout.queue(cursor::MoveTo(2, legend_row.saturating_sub(1)))?;
+    if message.is_empty() {
+        stdout.queue(style::PrintStyledContent(status.dark_grey()))?;
+    } else {
+        stdout.queue(style::PrintStyledContent(message.red()))?;
+    }
+
+    stdout.queue(cursor::MoveTo(2, legend_row))?;
+    stdout.queue(style::PrintStyledContent("space".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" toggle  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("a".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" all  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("e".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" edit target  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("enter".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" confirm  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("esc".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" cancel".stylize()))?;
+
+    stdout.flush()?;
+    Ok(())
+}
+
+fn truncate(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        value.to_string()
+    } else {
+        value.chars().take(max).collect()
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::pattern::parse_filename;
+
+const RUN_LOG_FILE: &str = "runs.jsonl";
+
+/// One prompt run, as a line of the run log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub timestamp: String,
+    /// Prompt filename; `None` for a session with only `prompts.md`.
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// The filename without its version, shared by every version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    pub runner: String,
+    /// `None` when the runner was killed by a signal.
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_ms: u64,
+}
+
+impl RunRecord {
+    /// A record of running `prompt` (a filename) with `runner`, ending now.
+    pub fn new(
+        prompt: Option<&str>,
+        runner: &str,
+        exit_code: Option<i32>,
+        duration: std::time::Duration,
+    ) -> RunRecord {
+        let parsed = prompt.and_then(|name| parse_filename(name).ok());
+        RunRecord {
+            timestamp: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            prompt: prompt.map(str::to_string),
+            repo: parsed.as_ref().map(|parsed| parsed.repo().to_string()),
+            category: parsed.as_ref().map(|parsed| parsed.category().to_string()),
+            identity: parsed.as_ref().map(|parsed| parsed.identity()),
+            version: parsed.as_ref().map(|parsed| parsed.version()),
+            runner: runner.to_string(),
+            exit_code,
+            success: exit_code == Some(0),
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+/// `$XDG_STATE_HOME/axon`, or `~/.local/state/axon`: per-user state such
+/// as the run log.
+pub fn state_dir() -> PathBuf {
+    let state = match std::env::var("XDG_STATE_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(".local/state")
+        }
+    };
+    state.join("axon")
+}
+
+/// `runs.jsonl` in the state directory.
+pub fn log_path() -> PathBuf {
+    state_dir().join(RUN_LOG_FILE)
+}
+
+/// Appends `record` to the run log.
+pub fn record(record: &RunRecord) -> io::Result<()> {
+    let path = log_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(record).map_err(io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{line}")
+}
+
+/// Every record in the run log, oldest first. Lines that don't parse, such
+/// as one cut short by a crash, are skipped.
+pub fn load() -> io::Result<Vec<RunRecord>> {
+    let content = match fs::read_to_string(log_path()) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use crate::global_config::{GlobalConfig, RunnerConfig};
+use crate::opener::split_words;
+use crate::run_log::{self, RunRecord};
+
+/// The runner used when the config defines none of its own.
+pub const DEFAULT_RUNNER: &str = "claude";
+
+/// An agent CLI that runs prompts, e.g. `claude {instruction}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Runner {
+    pub name: String,
+    words: Vec<String>,
+    cwd: Option<PathBuf>,
+    env: BTreeMap<String, String>,
+}
+
+/// What a runner is asked to run: a prompt file, or just `prompts.md` to
+/// start a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub prompt_path: Option<PathBuf>,
+    pub prompts_md: PathBuf,
+    /// Working directory, instead of the runner's own.
+    pub cwd: Option<PathBuf>,
+    /// The prompt with its includes expanded. The runner is handed a copy
+    /// of it in place of `prompt_path`, written only while it runs.
+    pub composed: Option<String>,
+}
+
+impl Invocation {
+    /// The instruction handed to the agent.
+    pub fn instruction(&self) -> String {
+        match &self.prompt_path {
+            Some(prompt) => format!(
+                "read {} and {} and ask for next instruction",
+                self.prompts_md.display(),
+                prompt.display()
+            ),
+            None => format!("read {} and ask for next instruction", self.prompts_md.display()),
+        }
+    }
+}
+
+impl Runner {
+    pub fn from_config(name: &str, cfg: &RunnerConfig) -> Result<Runner, String> {
+        let Some(command) = &cfg.command else {
+            return Err(format!("runners.{name}: missing `command`"));
+        };
+        let mut words = split_words(command).map_err(|err| format!("runners.{name}: {err}"))?;
+        if words.is_empty() {
+            return Err(format!("runners.{name}: the command is empty"));
+        }
+        let args = cfg.args.as_deref().unwrap_or("{instruction}");
+        words.extend(split_words(args).map_err(|err| format!("runners.{name}: {err}"))?);
+        Ok(Runner {
+            name: name.to_string(),
+            words,
+            cwd: cfg.cwd.as_deref().map(crate::global_config::expand_tilde),
+            env: cfg.env.clone(),
+        })
+    }
+
+    /// `claude {instruction}`, available unless the config defines its own
+    /// `claude` runner.
+    pub fn builtin() -> Runner {
+        Runner {
+            name: DEFAULT_RUNNER.to_string(),
+            words: vec![DEFAULT_RUNNER.to_string(), "{instruction}".to_string()],
+            cwd: None,
+            env: BTreeMap::new(),
+        }
+    }
+
+    /// The program and arguments for `invocation`. Words that were only
+    /// placeholders and come out empty, such as `{prompt_path}` when
+    /// starting a session, are dropped. Fails when no word is left to run.
+    pub fn command(&self, invocation: &Invocation) -> io::Result<(String, Vec<String>)> {
+        let prompt_path = invocation
+            .prompt_path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let prompts_md = invocation.prompts_md.display().to_string();
+        let instruction = invocation.instruction();
+        let mut words: Vec<String> = self
+            .words
+            .iter()
+            .filter_map(|word| {
+                let substituted = word
+                    .replace("{prompt_path}", &prompt_path)
+                    .replace("{prompts_md}", &prompts_md)
+                    .replace("{instruction}", &instruction);
+                (word.is_empty() || !substituted.is_empty()).then_some(substituted)
+            })
+            .collect();
+        if words.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "runner '{}': the command is empty once its placeholders are filled in",
+                    self.name
+                ),
+            ));
+        }
+        let program = words.remove(0);
+        Ok((program, words))
+    }
+
+    /// Runs the agent in the foreground, waits for it, and records the run
+    /// in the run log.
+    pub fn run(&self, invocation: &Invocation) -> io::Result<()> {
+        let composed = match (&invocation.composed, &invocation.prompt_path) {
+            (Some(text), Some(path)) => Some(write_composed(path, text)?),
+            _ => None,
+        };
+        let result = self.spawn(invocation, composed.as_deref());
+        if let Some(path) = composed {
+            let _ = std::fs::remove_file(&path);
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::remove_dir(dir);
+            }
+        }
+        result
+    }
+
+    /// Runs the agent on `invocation`, with `composed` as its prompt file
+    /// when given.
+    fn spawn(&self, invocation: &Invocation, composed: Option<&Path>) -> io::Result<()> {
+        let handed = Invocation {
+            prompt_path: composed.map(Path::to_path_buf).or_else(|| invocation.prompt_path.clone()),
+            ..invocation.clone()
+        };
+        let (program, args) = self.command(&handed)?;
+        let started = Instant::now();
+        let mut command = Command::new(&program);
+        command
+            .args(&args)
+            .envs(&self.env)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+        let cwd = invocation.cwd.as_ref().or(self.cwd.as_ref());
+        if let Some(cwd) = cwd {
+            command.current_dir(cwd);
+        }
+        let status = command.status().map_err(|err| {
+            if err.kind() != io::ErrorKind::NotFound {
+                return io::Error::new(err.kind(), format!("cannot run `{program}`: {err}"));
+            }
+            let message = match cwd {
+                Some(cwd) if !cwd.is_dir() => format!(
+                    "runner '{}': working directory {} does not exist",
+                    self.name,
+                    cwd.display()
+                ),
+                _ => format!(
+                    "runner '{}': `{program}` is not installed or not on PATH. \
+                     Runners are configured in [runners.<name>] tables of {}",
+                    self.name,
+                    crate::global_config::config_path().display()
+                ),
+            };
+            io::Error::new(err.kind(), message)
+        })?;
+
+        let prompt = invocation
+            .prompt_path
+            .as_deref()
+            .and_then(|path| path.file_name()?.to_str());
+        let record = RunRecord::new(prompt, &self.name, status.code(), started.elapsed());
+        if let Err(err) = run_log::record(&record) {
+            eprintln!("Warning: cannot write {}: {err}", run_log::log_path().display());
+        }
+        if !status.success() {
+            return Err(io::Error::other(format!("`{program}` exited with {status}")));
+        }
+        Ok(())
+    }
+}
+
+/// Writes the composed copy of the prompt at `prompt` to a directory of
+/// this process under `composed/` in the state directory, so concurrent
+/// runs of prompts with the same name don't share a file.
+fn write_composed(prompt: &Path, text: &str) -> io::Result<PathBuf> {
+    let name = prompt.file_name().unwrap_or(prompt.as_os_str());
+    let dir = run_log::state_dir()
+        .join("composed")
+        .join(std::process::id().to_string());
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// The default runner's name: the `runner` setting, else `claude`.
+pub fn default_name(cfg: &GlobalConfig) -> &str {
+    cfg.runner.as_deref().unwrap_or(DEFAULT_RUNNER)
+}
+
+/// Every runner name, the default first.
+pub fn names(cfg: &GlobalConfig) -> Vec<String> {
+    let mut names = vec![default_name(cfg).to_string()];
+    let configured = cfg.runners.keys().cloned();
+    let builtin = (!cfg.runners.contains_key(DEFAULT_RUNNER)).then(|| DEFAULT_RUNNER.to_string());
+    for name in configured.chain(builtin) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// The runner called `name`, or the default one.
+pub fn resolve(cfg: &GlobalConfig, name: Option<&str>) -> Result<Runner, String> {
+    let name = name.unwrap_or_else(|| default_name(cfg));
+    match cfg.runners.get(name) {
+        Some(runner) => Runner::from_config(name, runner),
+        None if name == DEFAULT_RUNNER => Ok(Runner::builtin()),
+        None => {
+            let available: Vec<String> = names(cfg)
+                .into_iter()
+                .filter(|candidate| candidate != name)
+                .collect();
+            Err(format!(
+                "unknown runner '{name}' (available: {})",
+                available.join(", ")
+            ))
+        }
+    }
+}
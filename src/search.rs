@@ -0,0 +1,260 @@
+use regex::Regex;
+use serde::Serialize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::notes::{detect_periodic, PeriodicKind};
+use crate::pattern::{parse_filename, ParsedFilename};
+
+/// Snippets longer than this many bytes are cut around the first match.
+pub const SNIPPET_WIDTH: usize = 120;
+
+/// What to look for in file contents and names.
+#[derive(Debug, Clone)]
+pub struct Query {
+    regex: Regex,
+}
+
+impl Query {
+    /// `pattern` as a regex, or as literal text unless `regex` is set.
+    /// Matching ignores case unless the pattern has an uppercase letter or
+    /// `case_sensitive` is set.
+    pub fn new(pattern: &str, regex: bool, case_sensitive: bool) -> Result<Query, String> {
+        if pattern.is_empty() {
+            return Err("the search query is empty".to_string());
+        }
+        let source = if regex { pattern.to_string() } else { regex::escape(pattern) };
+        let ignore_case = !case_sensitive && !pattern.chars().any(char::is_uppercase);
+        regex::RegexBuilder::new(&source)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(|regex| Query { regex })
+            .map_err(|err| {
+                // Syntax errors span several lines; the last one says what's wrong.
+                let err = err.to_string();
+                let reason = err.lines().last().unwrap_or_default();
+                format!("invalid regex '{pattern}': {}", reason.trim_start_matches("error: "))
+            })
+    }
+
+    /// Byte ranges of the non-empty matches in `text`.
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .filter(|found| !found.is_empty())
+            .map(|found| found.range())
+            .collect()
+    }
+}
+
+/// Whether a file is a note or a prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Note,
+    Prompt,
+}
+
+/// Restricts which files are searched. Note kinds only match notes and
+/// prompt fields only match prompts, so setting one excludes the other.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub source: Option<Source>,
+    /// A periodic note kind such as `daily`, `scratch`, or `other`.
+    pub kind: Option<String>,
+    pub repo: Option<String>,
+    pub category: Option<String>,
+    pub feature: Option<String>,
+    pub doc_type: Option<String>,
+    pub variant: Option<String>,
+}
+
+impl Filter {
+    fn has_prompt_fields(&self) -> bool {
+        self.repo.is_some()
+            || self.category.is_some()
+            || self.feature.is_some()
+            || self.doc_type.is_some()
+            || self.variant.is_some()
+    }
+
+    pub fn matches(&self, source: Source, name: &str, kinds: &[PeriodicKind]) -> bool {
+        if self.source.is_some_and(|wanted| wanted != source) {
+            return false;
+        }
+        match source {
+            Source::Note => {
+                !self.has_prompt_fields()
+                    && self.kind.as_ref().is_none_or(|kind| *kind == note_kind(kinds, name))
+            }
+            Source::Prompt => self.kind.is_none() && self.matches_prompt(name),
+        }
+    }
+
+    fn matches_prompt(&self, name: &str) -> bool {
+        if !self.has_prompt_fields() {
+            return true;
+        }
+        let Ok(parsed) = parse_filename(name) else {
+            return false;
+        };
+        let (feature, doc_type, variant) = match &parsed {
+            ParsedFilename::Feat(feat) => (
+                Some(feat.feature.as_str()),
+                Some(feat.doc_type.as_str()),
+                Some(feat.variant.as_str()),
+            ),
+            ParsedFilename::Sop(sop) => (Some(sop.name.as_str()), None, None),
+        };
+        let field = |wanted: &Option<String>, value: Option<&str>| {
+            wanted.as_deref().is_none_or(|wanted| Some(wanted) == value)
+        };
+        field(&self.repo, Some(parsed.repo()))
+            && field(&self.category, Some(parsed.category()))
+            && field(&self.feature, feature)
+            && field(&self.doc_type, doc_type)
+            && field(&self.variant, variant)
+    }
+}
+
+/// The kind of a note: its periodic kind's name, `scratch`, or `other`.
+pub fn note_kind(kinds: &[PeriodicKind], filename: &str) -> String {
+    if let Some((kind, _)) = detect_periodic(kinds, filename) {
+        kind.name.clone()
+    } else if filename.starts_with("scratch.") {
+        "scratch".to_string()
+    } else {
+        "other".to_string()
+    }
+}
+
+/// A matching line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hit {
+    /// 1-based.
+    pub line: usize,
+    /// The line, cut to [`SNIPPET_WIDTH`] around the first match.
+    pub text: String,
+    /// Byte ranges of the matches within `text`.
+    pub matches: Vec<Range<usize>>,
+}
+
+/// A file whose name or contents match.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileResult {
+    pub file: String,
+    pub path: PathBuf,
+    pub source: Source,
+    /// Whether the filename itself matches.
+    pub name_match: bool,
+    pub hits: Vec<Hit>,
+    #[serde(skip)]
+    modified: Option<SystemTime>,
+}
+
+impl FileResult {
+    /// Files whose name matches come first, then those with more matching
+    /// lines, then the most recently modified.
+    fn rank(&self) -> (bool, usize, Option<SystemTime>) {
+        (self.name_match, self.hits.len(), self.modified)
+    }
+}
+
+/// Searches `files`, ranked best first. Unreadable files are skipped.
+pub fn search(query: &Query, files: &[(Source, PathBuf)]) -> Vec<FileResult> {
+    let mut results: Vec<FileResult> = files
+        .iter()
+        .filter_map(|(source, path)| search_file(query, *source, path))
+        .collect();
+    results.sort_by(|a, b| b.rank().cmp(&a.rank()).then_with(|| a.file.cmp(&b.file)));
+    results
+}
+
+fn search_file(query: &Query, source: Source, path: &Path) -> Option<FileResult> {
+    let file = crate::fs_utils::file_name_string(path)?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let hits: Vec<Hit> = content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let matches = query.find(line);
+            (!matches.is_empty()).then(|| snippet(idx + 1, line, matches))
+        })
+        .collect();
+    let name_match = !query.find(&file).is_empty();
+    if hits.is_empty() && !name_match {
+        return None;
+    }
+    Some(FileResult {
+        file,
+        path: path.to_path_buf(),
+        source,
+        name_match,
+        hits,
+        modified: std::fs::metadata(path).and_then(|meta| meta.modified()).ok(),
+    })
+}
+
+/// Cuts `line` to [`SNIPPET_WIDTH`] bytes, keeping some context before the
+/// first match, and shifts the match ranges to fit.
+fn snippet(line_number: usize, line: &str, matches: Vec<Range<usize>>) -> Hit {
+    let trimmed = line.trim_end();
+    if trimmed.len() <= SNIPPET_WIDTH {
+        let matches = matches
+            .into_iter()
+            .filter(|range| range.end <= trimmed.len())
+            .collect();
+        return Hit {
+            line: line_number,
+            text: trimmed.to_string(),
+            matches,
+        };
+    }
+    let first = matches.first().map_or(0, |range| range.start);
+    let mut start = first.saturating_sub(SNIPPET_WIDTH / 3);
+    while !trimmed.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + SNIPPET_WIDTH).min(trimmed.len());
+    while !trimmed.is_char_boundary(end) {
+        end -= 1;
+    }
+    let matches = matches
+        .into_iter()
+        .filter(|range| range.start >= start && range.end <= end)
+        .map(|range| range.start - start..range.end - start)
+        .collect();
+    Hit {
+        line: line_number,
+        text: trimmed[start..end].to_string(),
+        matches,
+    }
+}
+
+/// The notes and prompts to search, from the configured directories. When
+/// both are the same directory, files named like prompts count as prompts.
+pub fn default_files() -> Vec<(Source, PathBuf)> {
+    let notes_dir = crate::notes::notes_dir();
+    let prompts_dir = crate::prompts::prompts_dir();
+    let shared = prompts_dir == notes_dir;
+    let mut files: Vec<(Source, PathBuf)> = crate::notes::list_notes_in(&notes_dir)
+        .into_iter()
+        .map(|name| {
+            let source = if shared && parse_filename(&name).is_ok() {
+                Source::Prompt
+            } else {
+                Source::Note
+            };
+            (source, notes_dir.join(name))
+        })
+        .collect();
+    if !shared {
+        files.extend(
+            crate::prompts::list_prompts_in(&prompts_dir)
+                .into_iter()
+                .map(|name| (Source::Prompt, prompts_dir.join(name))),
+        );
+    }
+    files
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::global_config::GlobalConfig;
+
+/// How big a prompt is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Size {
+    pub bytes: usize,
+    pub words: usize,
+    pub lines: usize,
+    /// Approximate, from the configured [`Tokenizer`].
+    pub tokens: usize,
+}
+
+impl Size {
+    pub fn measure(text: &str, tokenizer: Tokenizer) -> Size {
+        Size {
+            bytes: text.len(),
+            words: text.split_whitespace().count(),
+            lines: text.lines().count(),
+            tokens: tokenizer.estimate(text),
+        }
+    }
+
+    pub fn add(&mut self, other: &Size) {
+        self.bytes += other.bytes;
+        self.words += other.words;
+        self.lines += other.lines;
+        self.tokens += other.tokens;
+    }
+}
+
+/// Heuristics approximating how many tokens a model sees. None of them
+/// match a real tokenizer exactly; they are close enough to compare
+/// prompts and enforce limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tokenizer {
+    /// One token per 4 characters.
+    #[default]
+    Chars,
+    /// Four tokens per 3 words, plus one per punctuation mark.
+    Words,
+}
+
+impl Tokenizer {
+    pub const NAMES: [&'static str; 2] = ["chars", "words"];
+
+    pub fn parse(name: &str) -> Result<Tokenizer, String> {
+        match name {
+            "chars" => Ok(Tokenizer::Chars),
+            "words" => Ok(Tokenizer::Words),
+            _ => Err(format!(
+                "unknown tokenizer '{name}' (expected {})",
+                Tokenizer::NAMES.join(" or ")
+            )),
+        }
+    }
+
+    /// The `size.tokenizer` setting; the default when unset or invalid.
+    pub fn from_config(cfg: &GlobalConfig) -> Tokenizer {
+        cfg.size
+            .tokenizer
+            .as_deref()
+            .and_then(|name| Tokenizer::parse(name).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tokenizer::Chars => "chars",
+            Tokenizer::Words => "words",
+        }
+    }
+
+    pub fn estimate(self, text: &str) -> usize {
+        match self {
+            Tokenizer::Chars => text.chars().count().div_ceil(4),
+            Tokenizer::Words => {
+                let words = text.split_whitespace().count();
+                let punctuation = text.chars().filter(|c| c.is_ascii_punctuation()).count();
+                (words * 4).div_ceil(3) + punctuation
+            }
+        }
+    }
+}
+
+/// The size of a prompt file, as written and with its includes expanded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileSize {
+    pub file: String,
+    #[serde(flatten)]
+    pub size: Size,
+    /// `None` when an include can't be resolved.
+    pub expanded: Option<Size>,
+}
+
+pub fn measure_file(path: &Path, tokenizer: Tokenizer) -> std::io::Result<FileSize> {
+    let source = std::fs::read_to_string(path)?;
+    let size = Size::measure(&source, tokenizer);
+    let expanded = if crate::prompts::has_includes(&source) {
+        crate::prompts::compose(path)
+            .ok()
+            .flatten()
+            .map(|text| Size::measure(&text, tokenizer))
+    } else {
+        Some(size)
+    };
+    Ok(FileSize {
+        file: path.display().to_string(),
+        size,
+        expanded,
+    })
+}
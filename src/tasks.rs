@@ -0,0 +1,191 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Marker replacing `[ ]` on a task that was carried into a newer note.
+pub const CARRIED_MARK: &str = "[>]";
+
+static UNCHECKED_REGEX: OnceLock<Regex> = OnceLock::new();
+static OPEN_REGEX: OnceLock<Regex> = OnceLock::new();
+static LIST_ITEM_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn unchecked_regex() -> &'static Regex {
+    UNCHECKED_REGEX.get_or_init(|| {
+        Regex::new(r"^(\s*[-*+] )\[ \] (.*\S)\s*$").expect("invalid task regex")
+    })
+}
+
+fn list_item_regex() -> &'static Regex {
+    LIST_ITEM_REGEX.get_or_init(|| Regex::new(r"^\s*([-*+]|\d+[.)])\s").expect("invalid list item regex"))
+}
+
+/// Unchecked or carried: not done yet.
+fn open_regex() -> &'static Regex {
+    OPEN_REGEX.get_or_init(|| {
+        Regex::new(r"^(\s*[-*+] )\[[ >]\] (.*\S)\s*$").expect("invalid task regex")
+    })
+}
+
+/// A `- [ ]` task, or a `- [>]` one from [`open_tasks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    /// Zero-based line index in the note.
+    pub line: usize,
+    /// Leading whitespace relative to the task it is nested under, empty
+    /// unless that task is found too.
+    pub indent: String,
+    pub text: String,
+}
+
+/// The level and text of a markdown heading line.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = line[level..].strip_prefix(' ')?;
+    Some((level, text.trim()))
+}
+
+/// Line range `(heading, end)` of the section under the first heading named
+/// `name` (case-insensitive). The section ends at the next heading of the
+/// same or a higher level.
+fn section_range(lines: &[&str], name: &str) -> Option<(usize, usize)> {
+    let (start, level) = lines.iter().enumerate().find_map(|(idx, line)| {
+        let (level, text) = heading(line)?;
+        text.eq_ignore_ascii_case(name).then_some((idx, level))
+    })?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| heading(line).is_some_and(|(other, _)| other <= level))
+        .map(|offset| start + 1 + offset)
+        .unwrap_or(lines.len());
+    Some((start, end))
+}
+
+/// Unchecked tasks in `content`, optionally only those under `under`.
+pub fn unchecked_tasks(content: &str, under: Option<&str>) -> Vec<Task> {
+    find_tasks(content, under, unchecked_regex())
+}
+
+/// Tasks in `content` that are not done, whether or not they were carried
+/// into a newer note, optionally only those under `under`.
+pub fn open_tasks(content: &str, under: Option<&str>) -> Vec<Task> {
+    find_tasks(content, under, open_regex())
+}
+
+fn find_tasks(content: &str, under: Option<&str>, regex: &Regex) -> Vec<Task> {
+    let lines: Vec<&str> = content.lines().collect();
+    let range = match under {
+        Some(name) => match section_range(&lines, name) {
+            Some((start, end)) => start + 1..end,
+            None => return Vec::new(),
+        },
+        None => 0..lines.len(),
+    };
+
+    let mut in_fence = false;
+    let mut tasks = Vec::new();
+    // The list items enclosing the current line: their indent width, and
+    // the relative indent of the ones that are found tasks.
+    let mut parents: Vec<(usize, Option<String>)> = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || !range.contains(&idx) || line.trim().is_empty() {
+            continue;
+        }
+        let width = line.len() - line.trim_start().len();
+        if !list_item_regex().is_match(line) {
+            if width == 0 {
+                parents.clear();
+            }
+            continue;
+        }
+        while parents.last().is_some_and(|(parent, _)| *parent >= width) {
+            parents.pop();
+        }
+        let Some(caps) = regex.captures(line) else {
+            parents.push((width, None));
+            continue;
+        };
+        let indent = match parents.last() {
+            Some((parent, Some(indent))) => format!("{indent}{}", &line[*parent..width]),
+            _ => String::new(),
+        };
+        parents.push((width, Some(indent.clone())));
+        tasks.push(Task {
+            line: idx,
+            indent,
+            text: caps[2].to_string(),
+        });
+    }
+    tasks
+}
+
+/// Marks the tasks on `lines` as carried (`- [>] task`).
+pub fn mark_carried(content: &str, lines: &[usize]) -> String {
+    let mut output = String::with_capacity(content.len());
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        if lines.contains(&idx)
+            && let Some(caps) = unchecked_regex().captures(line.trim_end_matches(['\n', '\r']))
+        {
+            let rest = &line[caps[1].len() + 3..];
+            output.push_str(&caps[1]);
+            output.push_str(CARRIED_MARK);
+            output.push_str(rest);
+        } else {
+            output.push_str(line);
+        }
+    }
+    output
+}
+
+/// Adds `tasks` as unchecked items at the end of the section named
+/// `section`, creating the section at the end of the note if it is missing.
+/// Leading whitespace in a task nests it under the one before.
+pub fn insert_tasks(content: &str, section: &str, tasks: &[String]) -> String {
+    if tasks.is_empty() {
+        return content.to_string();
+    }
+    let items: Vec<String> = tasks
+        .iter()
+        .map(|task| {
+            let text = task.trim_start();
+            format!("{}- [ ] {text}", &task[..task.len() - text.len()])
+        })
+        .collect();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+    match section_range(&borrowed, section) {
+        Some((start, end)) => {
+            // After the last non-blank line of the section, dropping an empty
+            // `- [ ]` placeholder left by the schema.
+            let mut at = end;
+            while at > start + 1 && lines[at - 1].trim().is_empty() {
+                at -= 1;
+            }
+            if at > start + 1 && matches!(lines[at - 1].trim(), "- [ ]" | "* [ ]") {
+                lines.remove(at - 1);
+                at -= 1;
+            }
+            for (offset, item) in items.into_iter().enumerate() {
+                lines.insert(at + offset, item);
+            }
+        }
+        None => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("## {section}"));
+            lines.push(String::new());
+            lines.extend(items);
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
@@ -0,0 +1,518 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::NaiveDate;
+
+/// A value available to templates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    /// A date shown as `display` by `{{name}}` and formatted by
+    /// `{{name:%A, %B %d}}`.
+    Date { date: NaiveDate, display: String },
+}
+
+impl Value {
+    pub fn date(date: NaiveDate) -> Value {
+        Value::Date {
+            date,
+            display: date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Value::Text(text) => text.is_empty(),
+            Value::Date { .. } => false,
+        }
+    }
+}
+
+type Resolver<'a> = Box<dyn Fn(&str) -> Result<(String, String), String> + 'a>;
+
+/// Variables and include resolution for rendering a template.
+///
+/// `{{env.NAME}}` reads environment variables; every other name must be set
+/// on the context.
+#[derive(Default)]
+pub struct Context<'a> {
+    values: BTreeMap<String, Value>,
+    resolver: Option<Resolver<'a>>,
+    root: Option<String>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: impl Into<String>) {
+        self.values.insert(name.to_string(), Value::Text(value.into()));
+    }
+
+    pub fn set_value(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    /// Resolves `{{> name}}` with `resolve`, which returns a key identifying
+    /// the included template (used to detect cycles) and its source.
+    pub fn with_includes(
+        mut self,
+        resolve: impl Fn(&str) -> Result<(String, String), String> + 'a,
+    ) -> Self {
+        self.resolver = Some(Box::new(resolve));
+        self
+    }
+
+    /// Renders the template as the include `key`, so that a template
+    /// including it back is reported as a cycle starting from it.
+    pub fn with_root(mut self, key: impl Into<String>) -> Self {
+        self.root = Some(key.into());
+        self
+    }
+
+    /// Resolves `{{> name}}` to the file `name` in `dir`.
+    pub fn with_include_dir(self, dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        self.with_includes(move |name| {
+            let path = dir.join(name);
+            std::fs::read_to_string(&path)
+                .map(|content| (name.to_string(), content))
+                .map_err(|err| format!("cannot include {}: {err}", path.display()))
+        })
+    }
+
+    fn lookup(&self, name: &str) -> Option<Value> {
+        match name.strip_prefix("env.") {
+            Some(var) => std::env::var(var).ok().map(Value::Text),
+            None => self.values.get(name).cloned(),
+        }
+    }
+}
+
+/// The output of a template and the variables it referenced but the context
+/// did not define. Unknown variables are left in the output as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub text: String,
+    pub unknown: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Var {
+        name: String,
+        format: Option<String>,
+        raw: String,
+    },
+    If {
+        name: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Include {
+        name: String,
+        standalone: bool,
+    },
+}
+
+/// Renders `source`:
+///
+/// - `{{name}}` and `{{name:%A, %B %d}}` (chrono format, dates only)
+/// - `{{#if name}}...{{else}}...{{/if}}`, true when `name` is set and not
+///   empty; `{{#if !name}}` negates
+/// - `{{> name}}` includes another template
+///
+/// Other `{{...}}` text, such as Jinja or Go template code, is kept as
+/// written; `\{{` keeps text that looks like a tag. Malformed block and include tags, include failures, and include
+/// cycles are errors.
+pub fn render(source: &str, ctx: &Context) -> Result<Rendered, String> {
+    let mut output = Rendered {
+        text: String::new(),
+        unknown: Vec::new(),
+    };
+    let nodes = parse(source)?;
+    let mut stack: Vec<String> = ctx.root.iter().cloned().collect();
+    render_nodes(&nodes, ctx, &mut stack, &mut output)?;
+    Ok(output)
+}
+
+/// The variables `source` and its includes use, in order of first use.
+/// Variables only tested by `{{#if}}`, or only substituted where such a test
+/// guarantees they are set, are optional. Environment variables
+/// are left out.
+pub fn variables(source: &str, ctx: &Context) -> Result<Vec<Variable>, String> {
+    let nodes = parse(source)?;
+    let mut found = Vec::new();
+    let mut stack: Vec<String> = ctx.root.iter().cloned().collect();
+    collect_variables(&nodes, ctx, &mut stack, &mut Vec::new(), &mut found)?;
+    Ok(found)
+}
+
+/// A variable a template uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    /// Whether `{{name}}` is ever substituted, rather than only tested.
+    pub required: bool,
+}
+
+fn collect_variables(
+    nodes: &[Node],
+    ctx: &Context,
+    stack: &mut Vec<String>,
+    guarded: &mut Vec<String>,
+    found: &mut Vec<Variable>,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Var { name, .. } => add_variable(found, name, !guarded.contains(name)),
+            Node::If {
+                name,
+                negate,
+                then,
+                otherwise,
+            } => {
+                add_variable(found, name, false);
+                let (set, unset) = if *negate { (otherwise, then) } else { (then, otherwise) };
+                guarded.push(name.clone());
+                collect_variables(set, ctx, stack, guarded, found)?;
+                guarded.pop();
+                collect_variables(unset, ctx, stack, guarded, found)?;
+            }
+            Node::Include { name, .. } => {
+                let (key, source) = resolve_include(ctx, name, stack)?;
+                let nodes = parse(&source).map_err(|err| format!("{key}: {err}"))?;
+                stack.push(key);
+                collect_variables(&nodes, ctx, stack, guarded, found)?;
+                stack.pop();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_variable(found: &mut Vec<Variable>, name: &str, required: bool) {
+    if name.starts_with("env.") {
+        return;
+    }
+    match found.iter_mut().find(|variable| variable.name == name) {
+        Some(variable) => variable.required |= required,
+        None => found.push(Variable {
+            name: name.to_string(),
+            required,
+        }),
+    }
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    ctx: &Context,
+    stack: &mut Vec<String>,
+    output: &mut Rendered,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.text.push_str(text),
+            Node::Var { name, format, raw } => match (ctx.lookup(name), format) {
+                (None, _) => {
+                    if !output.unknown.contains(name) {
+                        output.unknown.push(name.clone());
+                    }
+                    output.text.push_str(raw);
+                }
+                (Some(Value::Text(text)), None) => output.text.push_str(&text),
+                (Some(Value::Date { display, .. }), None) => output.text.push_str(&display),
+                (Some(Value::Date { date, .. }), Some(format)) => {
+                    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                        return Err(format!("invalid date format in {raw}"));
+                    }
+                    // Time fields such as `%H` fail: a date has no time of day.
+                    write!(output.text, "{}", date.format(format))
+                        .map_err(|_| format!("{raw}: '{format}' can't be formatted from a date alone"))?;
+                }
+                (Some(Value::Text(_)), Some(_)) => {
+                    return Err(format!("{raw}: only dates take a format"));
+                }
+            },
+            Node::If {
+                name,
+                negate,
+                then,
+                otherwise,
+            } => {
+                let set = ctx.lookup(name).is_some_and(|value| !value.is_empty());
+                let branch = if set != *negate { then } else { otherwise };
+                render_nodes(branch, ctx, stack, output)?;
+            }
+            Node::Include { name, standalone } => {
+                let (key, source) = resolve_include(ctx, name, stack)?;
+                let nodes = parse(&source).map_err(|err| format!("{key}: {err}"))?;
+                stack.push(key);
+                render_nodes(&nodes, ctx, stack, output)?;
+                stack.pop();
+                if *standalone && !output.text.is_empty() && !output.text.ends_with('\n') {
+                    output.text.push('\n');
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The key and source of the include `name`, unless including it from the
+/// templates on `stack` would be a cycle.
+fn resolve_include(ctx: &Context, name: &str, stack: &mut Vec<String>) -> Result<(String, String), String> {
+    let Some(resolve) = &ctx.resolver else {
+        return Err(format!("cannot include '{name}': includes are not available here"));
+    };
+    let (key, source) = resolve(name)?;
+    if stack.contains(&key) {
+        stack.push(key);
+        return Err(format!("include cycle: {}", stack.join(" -> ")));
+    }
+    Ok((key, source))
+}
+
+/// `source` with its `{{> name}}` includes expanded, and everything else,
+/// variables and conditionals included, left as written. Include failures
+/// and cycles are errors.
+pub fn expand_includes(source: &str, ctx: &Context) -> Result<String, String> {
+    let mut stack: Vec<String> = ctx.root.iter().cloned().collect();
+    let mut output = String::new();
+    expand_into(source, ctx, &mut stack, &mut output)?;
+    Ok(output)
+}
+
+fn expand_into(source: &str, ctx: &Context, stack: &mut Vec<String>, output: &mut String) -> Result<(), String> {
+    let mut pos = 0;
+    let mut search = 0;
+    while let Some(found) = source[search..].find("{{") {
+        let start = search + found;
+        let Some(len) = source[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        let name = match source[start + 2..end - 2].trim().strip_prefix('>') {
+            Some(name) if !source[..start].ends_with('\\') => name.trim(),
+            _ => {
+                search = start + 2;
+                continue;
+            }
+        };
+        if name.is_empty() {
+            let line = source[..start].matches('\n').count() + 1;
+            return Err(format!("line {line}: missing include name"));
+        }
+
+        // As when rendering, an include alone on its line leaves no line behind.
+        let line_start = source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = source[end..].find('\n').map_or(source.len(), |idx| end + idx);
+        let standalone = source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty();
+        let (text_end, next) = if standalone {
+            (line_start.max(pos), (line_end + 1).min(source.len()))
+        } else {
+            (start, end)
+        };
+        output.push_str(&source[pos..text_end]);
+        pos = next;
+        search = next;
+
+        let (key, included) = resolve_include(ctx, name, stack)?;
+        stack.push(key);
+        expand_into(&included, ctx, stack, output)?;
+        stack.pop();
+        if standalone && !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+    }
+    output.push_str(&source[pos..]);
+    Ok(())
+}
+
+enum Tag {
+    If { name: String, negate: bool },
+    Else,
+    EndIf,
+    Include(String),
+    Var { name: String, format: Option<String> },
+}
+
+/// The tag `inner` spells, or `None` when it isn't one of ours. Our own
+/// block and include tags with a bad argument are errors.
+fn parse_tag(inner: &str) -> Result<Option<Tag>, String> {
+    let inner = inner.trim();
+    if let Some(condition) = inner.strip_prefix("#if") {
+        let condition = condition.trim();
+        let (negate, name) = match condition.strip_prefix('!') {
+            Some(name) => (true, name.trim()),
+            None => (false, condition),
+        };
+        if !is_name(name) {
+            return Err(format!("invalid condition '{condition}'"));
+        }
+        return Ok(Some(Tag::If {
+            name: name.to_string(),
+            negate,
+        }));
+    }
+    if inner == "else" {
+        return Ok(Some(Tag::Else));
+    }
+    if inner == "/if" {
+        return Ok(Some(Tag::EndIf));
+    }
+    if let Some(name) = inner.strip_prefix('>') {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("missing include name".to_string());
+        }
+        return Ok(Some(Tag::Include(name.to_string())));
+    }
+    let (name, format) = match inner.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format.to_string())),
+        None => (inner, None),
+    };
+    if !is_name(name) {
+        return Ok(None);
+    }
+    Ok(Some(Tag::Var {
+        name: name.to_string(),
+        format,
+    }))
+}
+
+/// A variable name, such as `date` or `env.HOME`. Names start with a
+/// letter or `_`, so Go template fields like `.Field` aren't variables.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// An open `{{#if}}` block while parsing.
+struct Block {
+    name: String,
+    negate: bool,
+    line: usize,
+    then: Option<Vec<Node>>,
+}
+
+fn parse(source: &str) -> Result<Vec<Node>, String> {
+    let line_of = |offset: usize| source[..offset].matches('\n').count() + 1;
+
+    let mut blocks: Vec<Block> = Vec::new();
+    // Nodes of the enclosing scopes while inside `{{#if}}` blocks.
+    let mut outer: Vec<Vec<Node>> = Vec::new();
+    let mut current: Vec<Node> = Vec::new();
+    let mut pos = 0;
+
+    let mut search = 0;
+    while let Some(found) = source[search..].find("{{") {
+        let start = search + found;
+        // `\{{` is a literal `{{`.
+        if source[..start].ends_with('\\') {
+            push_text(&mut current, &source[pos..start - 1]);
+            pos = start;
+            search = start + 2;
+            continue;
+        }
+        // Braces that don't form a tag, such as Jinja or Go template code
+        // in a prompt, are kept as text.
+        let Some(len) = source[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        let raw = &source[start..end];
+        let tag = match parse_tag(&source[start + 2..end - 2]) {
+            Ok(Some(tag)) => tag,
+            Ok(None) => {
+                search = start + 2;
+                continue;
+            }
+            Err(err) => return Err(format!("line {}: {err}", line_of(start))),
+        };
+
+        // Block tags and includes alone on their line don't leave the line
+        // behind in the output.
+        let line_start = source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = source[end..].find('\n').map_or(source.len(), |idx| end + idx);
+        let standalone = !matches!(tag, Tag::Var { .. })
+            && source[line_start..start].trim().is_empty()
+            && source[end..line_end].trim().is_empty();
+        let (text_end, next) = if standalone {
+            (line_start.max(pos), (line_end + 1).min(source.len()))
+        } else {
+            (start, end)
+        };
+        if text_end > pos {
+            push_text(&mut current, &source[pos..text_end]);
+        }
+        pos = next;
+        search = next;
+
+        match tag {
+            Tag::Var { name, format } => current.push(Node::Var {
+                name,
+                format,
+                raw: raw.to_string(),
+            }),
+            Tag::Include(name) => current.push(Node::Include { name, standalone }),
+            Tag::If { name, negate } => {
+                blocks.push(Block {
+                    name,
+                    negate,
+                    line: line_of(start),
+                    then: None,
+                });
+                outer.push(std::mem::take(&mut current));
+            }
+            Tag::Else => {
+                let Some(block) = blocks.last_mut() else {
+                    return Err(format!("line {}: {{{{else}}}} outside {{{{#if}}}}", line_of(start)));
+                };
+                if block.then.is_some() {
+                    return Err(format!("line {}: duplicate {{{{else}}}}", line_of(start)));
+                }
+                block.then = Some(std::mem::take(&mut current));
+            }
+            Tag::EndIf => {
+                let Some(block) = blocks.pop() else {
+                    return Err(format!("line {}: {{{{/if}}}} without {{{{#if}}}}", line_of(start)));
+                };
+                let (then, otherwise) = match block.then {
+                    Some(then) => (then, std::mem::take(&mut current)),
+                    None => (std::mem::take(&mut current), Vec::new()),
+                };
+                current = outer.pop().unwrap_or_default();
+                current.push(Node::If {
+                    name: block.name,
+                    negate: block.negate,
+                    then,
+                    otherwise,
+                });
+            }
+        }
+    }
+
+    if let Some(block) = blocks.last() {
+        return Err(format!("line {}: unclosed {{{{#if {}}}}}", block.line, block.name));
+    }
+    if pos < source.len() {
+        push_text(&mut current, &source[pos..]);
+    }
+    Ok(current)
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    match nodes.last_mut() {
+        Some(Node::Text(existing)) => existing.push_str(text),
+        _ => nodes.push(Node::Text(text.to_string())),
+    }
+}
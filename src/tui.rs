@@ -9,8 +9,10 @@ use crossterm::{
 };
 
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-use crate::notes;
+use crate::notes::{self, PeriodicKind};
+use crate::{picker, preview, search};
 
 pub fn run() -> Result<(), crate::error::CliError> {
     if let Err(e) = enter_tui() {
@@ -22,53 +24,6 @@ pub fn run() -> Result<(), crate::error::CliError> {
     Ok(())
 }
 
-/// Shell out to fzf with the note list. Returns:
-/// - Ok(Some(name)) if user selected/typed something
-/// - Ok(None) if user escaped/cancelled
-fn fzf_pick(files: &[String]) -> io::Result<Option<String>> {
-    use std::process::{Command, Stdio};
-
-    let input = files.join("\n");
-    let mut child = Command::new("fzf")
-        .args([
-            "--print-query",
-            "--header",
-            "enter: open / select  esc: cancel",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::inherit())
-        .spawn()?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write as _;
-        let _ = stdin.write_all(input.as_bytes());
-    }
-
-    let output = child.wait_with_output()?;
-
-    // fzf exit 130 = esc/ctrl-c, 1 = no match (but --print-query still prints query)
-    let text = String::from_utf8_lossy(&output.stdout);
-    let mut lines = text.lines();
-    let query = lines.next().unwrap_or("").trim().to_string();
-    let selection = lines.next().unwrap_or("").trim().to_string();
-
-    if output.status.code() == Some(130) {
-        return Ok(None);
-    }
-
-    // If user selected an existing match, prefer that
-    if !selection.is_empty() {
-        return Ok(Some(selection));
-    }
-    // Otherwise use the raw query (no match — will create)
-    if !query.is_empty() {
-        return Ok(Some(query));
-    }
-
-    Ok(None)
-}
-
 fn enter_tui() -> io::Result<()> {
     let mut stdout = io::stdout();
     terminal::enable_raw_mode()?;
@@ -101,21 +56,131 @@ fn shell_out(stdout: &mut io::Stdout, f: impl FnOnce()) -> io::Result<()> {
     Ok(())
 }
 
-fn refresh_after_shell(stdout: &mut io::Stdout, files: &mut Vec<String>, selected: &mut usize) -> io::Result<()> {
+/// Reloads the note list after a child program may have changed it.
+fn refresh_after_shell(files: &mut Vec<String>, selected: &mut usize) {
     *files = notes::list_notes();
     if *selected >= files.len() {
         *selected = files.len().saturating_sub(1);
     }
-    draw_screen(stdout, files, *selected)
+}
+
+/// How far Ctrl+D and Ctrl+U scroll a preview.
+pub(crate) fn half_page() -> io::Result<usize> {
+    Ok((terminal::size()?.1 / 2).max(1) as usize)
+}
+
+/// Below this many columns the preview pane is hidden.
+const PREVIEW_MIN_COLS: u16 = 80;
+
+/// The width of the list column when the screen is wide enough for a
+/// preview pane to its right.
+pub(crate) fn list_width(cols: u16) -> Option<u16> {
+    (cols >= PREVIEW_MIN_COLS).then(|| (cols * 2 / 5).max(32))
+}
+
+/// Draws a vertical rule at `col` and the preview of `path` to its right,
+/// between `top` and `bottom` (exclusive).
+pub(crate) fn draw_preview_pane(
+    stdout: &mut io::Stdout,
+    path: &Path,
+    col: u16,
+    top: u16,
+    bottom: u16,
+    scroll: &mut usize,
+) -> io::Result<()> {
+    let (cols, _) = terminal::size()?;
+    for row in top..bottom {
+        stdout.queue(cursor::MoveTo(col, row))?;
+        stdout.queue(style::PrintStyledContent("│".dark_grey()))?;
+    }
+    let area = preview::Area {
+        col: col + 2,
+        row: top,
+        width: cols.saturating_sub(col + 4),
+        height: bottom.saturating_sub(top),
+    };
+    preview::draw(stdout, area, path, scroll)
+}
+
+/// Picks one of `items`, or a typed name, with the configured picker:
+/// the built-in one, or fzf when it's selected and installed. Items that
+/// are files in `dir` are previewed.
+fn pick(
+    stdout: &mut io::Stdout,
+    title: &str,
+    items: &[String],
+    dir: Option<&Path>,
+) -> io::Result<Option<String>> {
+    if picker::Backend::from_config(&crate::global_config::load()) == picker::Backend::Fzf {
+        let mut result = Ok(None);
+        shell_out(stdout, || result = picker::fzf_pick(items))?;
+        match result {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            result => return result,
+        }
+    }
+    picker::pick(stdout, title, items, dir)
+}
+
+/// Asks which runner to use when more than one is available, the default
+/// listed first. Returns `None` if the user cancelled.
+fn pick_runner(stdout: &mut io::Stdout) -> io::Result<Option<crate::runner::Runner>> {
+    let cfg = crate::global_config::load();
+    let names = crate::runner::names(&cfg);
+    let name = if names.len() > 1 {
+        match pick(stdout, "runner", &names, None)? {
+            Some(name) => name,
+            None => return Ok(None),
+        }
+    } else {
+        crate::runner::default_name(&cfg).to_string()
+    };
+    crate::runner::resolve(&cfg, Some(&name))
+        .map(Some)
+        .map_err(io::Error::other)
+}
+
+/// Records the error of an action run outside the TUI, to show it on the
+/// message line.
+fn report(message: &mut String, result: io::Result<()>) {
+    if let Err(err) = result {
+        *message = err.to_string();
+    }
+}
+
+/// Keys with a fixed meaning, never bound to a periodic note kind.
+const RESERVED_KEYS: &[char] = &['q', '/', 'g', 'j', 'k', '[', ']', 's', 'n', 'p'];
+
+/// Periodic note kinds that have a usable shortcut key.
+fn keyed_kinds() -> Vec<PeriodicKind> {
+    notes::periodic_kinds(&notes::notes_dir())
+        .into_iter()
+        .filter(|kind| kind.key.is_some_and(|key| !RESERVED_KEYS.contains(&key)))
+        .collect()
 }
 
 fn event_loop(stdout: &mut io::Stdout) -> io::Result<()> {
     let mut files = notes::list_notes();
     let mut selected: usize = 0;
-
-    draw_screen(stdout, &files, selected)?;
+    let kinds = keyed_kinds();
+    // The warnings printed before the TUI started are hidden behind it.
+    let mut message = match crate::config_check::ignored_settings().len() {
+        0 => String::new(),
+        count => format!("{count} config problems ignored; run `axon config check`"),
+    };
+    let mut preview_scroll: usize = 0;
+    let mut previewed: Option<String> = None;
 
     loop {
+        // A new selection is previewed from the top.
+        let current = files.get(selected).cloned();
+        if current != previewed {
+            preview_scroll = 0;
+            previewed = current;
+        }
+        draw_screen(stdout, &files, selected, &kinds, &message, &mut preview_scroll)?;
+        message.clear();
+
         match event::read()? {
             Event::Key(KeyEvent {
                 code: KeyCode::Char('q'),
@@ -127,100 +192,127 @@ fn event_loop(stdout: &mut io::Stdout) -> io::Result<()> {
                 ..
             }) => break,
 
-            // Fuzzy search via fzf
+            // Fuzzy search of note names
             Event::Key(KeyEvent {
                 code: KeyCode::Char('/'),
                 ..
             }) => {
-                let pick = {
-                    let f = files.clone();
-                    let mut result = None;
-                    shell_out(stdout, || {
-                        result = fzf_pick(&f).ok().flatten();
-                    })?;
-                    result
-                };
+                let pick = pick(stdout, "notes", &files, Some(&notes::notes_dir())).unwrap_or_else(|err| {
+                    message = err.to_string();
+                    None
+                });
 
                 if let Some(name) = pick {
                     if files.contains(&name) {
-                        shell_out(stdout, || {
-                            let _ = notes::open_note(&name);
-                        })?;
+                        shell_out(stdout, || report(&mut message, notes::open_note(&name)))?;
                     } else {
                         shell_out(stdout, || {
-                            let _ = notes::create_and_open_note(&name);
+                            report(&mut message, notes::create_and_open_note(&name))
                         })?;
                     }
                 }
 
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
+                refresh_after_shell(&mut files, &mut selected);
+            }
+
+            // Full-text search of notes and prompts
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers,
+                ..
+            }) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some((path, line)) = search_mode(stdout)? {
+                    shell_out(stdout, || report(&mut message, crate::opener::open(&path, line)))?;
+                }
+                refresh_after_shell(&mut files, &mut selected);
             }
 
             // Navigation
             Event::Key(KeyEvent {
                 code: KeyCode::Char('j') | KeyCode::Down,
                 ..
-            }) => {
-                if !files.is_empty() && selected < files.len() - 1 {
-                    selected += 1;
-                    draw_screen(stdout, &files, selected)?;
-                }
+            }) if !files.is_empty() && selected < files.len() - 1 => {
+                selected += 1;
             }
             Event::Key(KeyEvent {
                 code: KeyCode::Char('k') | KeyCode::Up,
                 ..
-            }) => {
-                if selected > 0 {
-                    selected = selected.saturating_sub(1);
-                    draw_screen(stdout, &files, selected)?;
-                }
+            }) if selected > 0 => {
+                selected = selected.saturating_sub(1);
             }
 
+            // Scroll the preview by half a screen
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::PageDown,
+                ..
+            }) => preview_scroll += half_page()?,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::PageUp, ..
+            }) => preview_scroll = preview_scroll.saturating_sub(half_page()?),
+
             // Open selected note
             Event::Key(KeyEvent {
                 code: KeyCode::Enter,
                 ..
             }) => {
                 if let Some(filename) = files.get(selected).cloned() {
-                    shell_out(stdout, || {
-                        let _ = notes::open_note(&filename);
-                    })?;
-                    refresh_after_shell(stdout, &mut files, &mut selected)?;
+                    shell_out(stdout, || report(&mut message, notes::open_note(&filename)))?;
+                    refresh_after_shell(&mut files, &mut selected);
                 }
             }
 
-            // Shortcut keys
+            // Shortcut keys, one per periodic note kind
             Event::Key(KeyEvent {
-                code: KeyCode::Char('d'),
+                code: KeyCode::Char(c),
                 modifiers,
                 ..
-            }) if !modifiers.contains(KeyModifiers::CONTROL) => {
-                shell_out(stdout, || { let _ = notes::open_daily(); })?;
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
+            }) if !modifiers.contains(KeyModifiers::CONTROL)
+                && let Some(kind) = kinds.iter().find(|kind| kind.key == Some(c)) =>
+            {
+                shell_out(stdout, || {
+                    report(&mut message, notes::open_periodic(kind, notes::today()))
+                })?;
+                refresh_after_shell(&mut files, &mut selected);
             }
             Event::Key(KeyEvent {
-                code: KeyCode::Char('w'),
-                modifiers,
-                ..
-            }) if !modifiers.contains(KeyModifiers::CONTROL) => {
-                shell_out(stdout, || { let _ = notes::open_weekly(); })?;
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('m'),
+                code: KeyCode::Char('s'),
                 modifiers,
                 ..
             }) if !modifiers.contains(KeyModifiers::CONTROL) => {
-                shell_out(stdout, || { let _ = notes::open_monthly(); })?;
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
+                shell_out(stdout, || report(&mut message, notes::open_scratch()))?;
+                refresh_after_shell(&mut files, &mut selected);
             }
+
+            // Previous / next periodic note, relative to the selected one
             Event::Key(KeyEvent {
-                code: KeyCode::Char('s'),
-                modifiers,
+                code: code @ (KeyCode::Char('[') | KeyCode::Char(']')),
                 ..
-            }) if !modifiers.contains(KeyModifiers::CONTROL) => {
-                shell_out(stdout, || { let _ = notes::open_scratch(); })?;
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
+            }) => {
+                let offset = if code == KeyCode::Char('[') { -1 } else { 1 };
+                let dir = notes::notes_dir();
+                let current = files.get(selected).cloned();
+                match notes::create_adjacent(&dir, current.as_deref(), offset) {
+                    Ok(path) => {
+                        shell_out(stdout, || report(&mut message, notes::open_note_path(&path)))?;
+                        files = notes::list_notes();
+                        let name = crate::fs_utils::file_name_string(&path);
+                        if let Some(idx) = files.iter().position(|file| Some(file) == name.as_ref()) {
+                            selected = idx;
+                        }
+                    }
+                    Err(err) => message = err.to_string(),
+                }
+                refresh_after_shell(&mut files, &mut selected);
             }
 
             // New note with schema
@@ -231,10 +323,10 @@ fn event_loop(stdout: &mut io::Stdout) -> io::Result<()> {
             }) if !modifiers.contains(KeyModifiers::CONTROL) => {
                 if let Some(name) = prompt_new_note(stdout)? {
                     shell_out(stdout, || {
-                        let _ = notes::create_and_open_note(&name);
+                        report(&mut message, notes::create_and_open_note(&name))
                     })?;
                 }
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
+                refresh_after_shell(&mut files, &mut selected);
             }
 
             // Prompts menu
@@ -244,42 +336,250 @@ fn event_loop(stdout: &mut io::Stdout) -> io::Result<()> {
                 ..
             }) if !modifiers.contains(KeyModifiers::CONTROL) => {
                 let prompt_files = crate::prompts::list_prompts();
-                let mut fzf_list = vec![crate::prompts::OPEN_SESSION.to_string()];
-                fzf_list.extend(prompt_files.clone());
-                let pick = {
-                    let mut result = None;
-                    shell_out(stdout, || {
-                        result = fzf_pick(&fzf_list).ok().flatten();
-                    })?;
-                    result
-                };
+                let mut items = vec![crate::prompts::OPEN_SESSION.to_string()];
+                items.extend(prompt_files.clone());
+                let dir = crate::prompts::prompts_dir();
+                let pick = pick(stdout, "prompts", &items, Some(&dir)).unwrap_or_else(|err| {
+                    message = err.to_string();
+                    None
+                });
 
                 if let Some(name) = pick {
-                    if name == crate::prompts::OPEN_SESSION {
-                        shell_out(stdout, || {
-                            let _ = crate::prompts::open_claude_session();
-                        })?;
-                    } else if prompt_files.contains(&name) {
-                        shell_out(stdout, || {
-                            let _ = crate::prompts::run_prompt_with_claude(&name);
-                        })?;
+                    if name == crate::prompts::OPEN_SESSION || prompt_files.contains(&name) {
+                        let prompt = (name != crate::prompts::OPEN_SESSION).then_some(name.as_str());
+                        match pick_runner(stdout) {
+                            Ok(Some(runner)) => shell_out(stdout, || {
+                                report(&mut message, crate::prompts::run_prompt(prompt, &runner))
+                            })?,
+                            Ok(None) => {}
+                            Err(err) => message = err.to_string(),
+                        }
                     } else {
                         shell_out(stdout, || {
-                            let _ = crate::prompts::create_and_open_prompt(&name);
+                            report(&mut message, crate::prompts::create_and_open_prompt(&name))
                         })?;
                     }
                 }
 
-                refresh_after_shell(stdout, &mut files, &mut selected)?;
+                refresh_after_shell(&mut files, &mut selected);
             }
 
-            Event::Resize(_, _) => {
-                draw_screen(stdout, &files, selected)?;
+            // Redrawn at the new size, with or without the preview pane
+            Event::Resize(_, _) => {}
+
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// One line of search results: a matching line, or a file whose name
+/// matches.
+struct SearchRow {
+    file: String,
+    path: PathBuf,
+    line: Option<usize>,
+    text: String,
+    matches: Vec<std::ops::Range<usize>>,
+}
+
+/// Searches notes and prompts as the query is typed. Returns the file and
+/// line to open, or `None` if the user cancelled.
+fn search_mode(stdout: &mut io::Stdout) -> io::Result<Option<(PathBuf, Option<usize>)>> {
+    let files = search::default_files();
+    let mut input = String::new();
+    let mut regex = false;
+    let mut rows: Vec<SearchRow> = Vec::new();
+    let mut error = String::new();
+    let mut selected: usize = 0;
+
+    stdout.execute(cursor::Show)?;
+    loop {
+        draw_search_screen(stdout, &input, regex, &rows, selected, &error)?;
+        let mut changed = false;
+        match event::read()? {
+            Event::Key(KeyEvent { code: KeyCode::Esc, .. })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => {
+                stdout.execute(cursor::Hide)?;
+                return Ok(None);
+            }
+            Event::Key(KeyEvent { code: KeyCode::Enter, .. }) => {
+                if let Some(row) = rows.get(selected) {
+                    stdout.execute(cursor::Hide)?;
+                    return Ok(Some((row.path.clone(), row.line)));
+                }
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Down, ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) if selected + 1 < rows.len() => selected += 1,
+            Event::Key(KeyEvent { code: KeyCode::Up, .. })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => selected = selected.saturating_sub(1),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => {
+                regex = !regex;
+                changed = true;
+            }
+            Event::Key(KeyEvent { code: KeyCode::Backspace, .. }) => {
+                changed = input.pop().is_some();
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            }) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                input.push(c);
+                changed = true;
             }
             _ => {}
         }
+
+        if changed {
+            selected = 0;
+            error.clear();
+            rows.clear();
+            if !input.is_empty() {
+                match search::Query::new(&input, regex, false) {
+                    Ok(query) => rows = search_rows(&search::search(&query, &files)),
+                    Err(err) => error = err,
+                }
+            }
+        }
+    }
+}
+
+fn search_rows(results: &[search::FileResult]) -> Vec<SearchRow> {
+    let mut rows = Vec::new();
+    for result in results {
+        if result.hits.is_empty() {
+            rows.push(SearchRow {
+                file: result.file.clone(),
+                path: result.path.clone(),
+                line: None,
+                text: String::new(),
+                matches: Vec::new(),
+            });
+        }
+        for hit in &result.hits {
+            rows.push(SearchRow {
+                file: result.file.clone(),
+                path: result.path.clone(),
+                line: Some(hit.line),
+                text: hit.text.clone(),
+                matches: hit.matches.clone(),
+            });
+        }
+    }
+    rows
+}
+
+fn draw_search_screen(
+    stdout: &mut io::Stdout,
+    input: &str,
+    regex: bool,
+    rows: &[SearchRow],
+    selected: usize,
+    error: &str,
+) -> io::Result<()> {
+    let (cols, term_rows) = terminal::size()?;
+
+    stdout.queue(terminal::Clear(ClearType::All))?;
+
+    let title = "search";
+    let title_col = cols.saturating_sub(title.len() as u16) / 2;
+    stdout.queue(cursor::MoveTo(title_col, 1))?;
+    stdout.queue(style::PrintStyledContent(title.bold()))?;
+
+    // Results, scrolled to keep the selection in view
+    let list_start_row: u16 = 3;
+    let prompt_row = term_rows.saturating_sub(3);
+    let available = prompt_row.saturating_sub(list_start_row + 1) as usize;
+    let scroll_offset = (selected + 1).saturating_sub(available);
+    let pad: u16 = 4;
+    let max_width = cols.saturating_sub(pad + 2) as usize;
+
+    for (i, row) in rows.iter().skip(scroll_offset).take(available).enumerate() {
+        stdout.queue(cursor::MoveTo(pad, list_start_row + i as u16))?;
+        let location = match row.line {
+            Some(line) => format!("{}:{line}: ", row.file),
+            None => row.file.clone(),
+        };
+        let location: String = location.chars().take(max_width).collect();
+        let mut width = max_width.saturating_sub(location.chars().count());
+        if scroll_offset + i == selected {
+            stdout.queue(style::PrintStyledContent(location.bold().cyan()))?;
+        } else {
+            stdout.queue(style::PrintStyledContent(location.dark_grey()))?;
+        }
+
+        let mut pos = 0;
+        let mut segments: Vec<(&str, bool)> = Vec::new();
+        for range in &row.matches {
+            segments.push((&row.text[pos..range.start], false));
+            segments.push((&row.text[range.clone()], true));
+            pos = range.end;
+        }
+        segments.push((&row.text[pos..], false));
+        for (text, is_match) in segments {
+            let text: String = text.chars().take(width).collect();
+            width -= text.chars().count();
+            if is_match {
+                stdout.queue(style::PrintStyledContent(text.red().bold()))?;
+            } else {
+                stdout.queue(style::PrintStyledContent(text.stylize()))?;
+            }
+        }
     }
 
+    if !input.is_empty() && rows.is_empty() {
+        let (text, color) = if error.is_empty() {
+            ("no matches", style::Color::DarkGrey)
+        } else {
+            (error, style::Color::Red)
+        };
+        let text: String = text.chars().take(max_width).collect();
+        stdout.queue(cursor::MoveTo(pad, list_start_row))?;
+        stdout.queue(style::PrintStyledContent(text.with(color)))?;
+    }
+
+    // Hint
+    let hint_row = term_rows.saturating_sub(2);
+    stdout.queue(cursor::MoveTo(4, hint_row))?;
+    stdout.queue(style::PrintStyledContent("enter".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" open  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("↑ ↓".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" select  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("ctrl-r".bold().cyan()))?;
+    let mode = if regex { " literal  " } else { " regex  " };
+    stdout.queue(style::PrintStyledContent(mode.stylize()))?;
+    stdout.queue(style::PrintStyledContent("esc".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" cancel".stylize()))?;
+
+    // Input prompt, with the cursor at the end of the query
+    let prompt = if regex { "regex: " } else { "text: " };
+    stdout.queue(cursor::MoveTo(4, prompt_row))?;
+    stdout.queue(style::PrintStyledContent(prompt.bold()))?;
+    stdout.queue(style::PrintStyledContent(input.stylize()))?;
+    let cursor_col = 4 + prompt.len() as u16 + input.chars().count() as u16;
+    stdout.queue(cursor::MoveTo(cursor_col, prompt_row))?;
+
+    stdout.flush()?;
     Ok(())
 }
 
@@ -388,8 +688,12 @@ fn draw_screen(
     stdout: &mut io::Stdout,
     files: &[String],
     selected: usize,
+    kinds: &[PeriodicKind],
+    message: &str,
+    preview_scroll: &mut usize,
 ) -> io::Result<()> {
     let (cols, rows) = terminal::size()?;
+    let list_width = list_width(cols);
 
     stdout.queue(terminal::Clear(ClearType::All))?;
 
@@ -399,10 +703,10 @@ fn draw_screen(
     stdout.queue(cursor::MoveTo(title_col, 1))?;
     stdout.queue(style::PrintStyledContent(title.bold()))?;
 
-    // File list
+    // File list, above the message line
     let list_start_row: u16 = 3;
     let legend_row = rows.saturating_sub(2);
-    let available = legend_row.saturating_sub(list_start_row) as usize;
+    let available = legend_row.saturating_sub(list_start_row + 1) as usize;
 
     if !files.is_empty() && available > 0 {
         let half = available / 2;
@@ -422,12 +726,8 @@ fn draw_screen(
             let row = list_start_row + i as u16;
             stdout.queue(cursor::MoveTo(pad, row))?;
 
-            let max_width = (cols.saturating_sub(pad + 2)) as usize;
-            let display: &str = if name.len() > max_width {
-                &name[..max_width]
-            } else {
-                name
-            };
+            let max_width = (list_width.unwrap_or(cols).saturating_sub(pad + 2)) as usize;
+            let display: String = name.chars().take(max_width).collect();
 
             if abs_idx == selected {
                 stdout.queue(style::PrintStyledContent(display.bold().cyan()))?;
@@ -437,16 +737,36 @@ fn draw_screen(
         }
     }
 
+    // Preview of the selected note, when there is room for it
+    if let Some(width) = list_width
+        && let Some(name) = files.get(selected)
+    {
+        let path = notes::notes_dir().join(name);
+        draw_preview_pane(stdout, &path, width, list_start_row, legend_row.saturating_sub(1), preview_scroll)?;
+    }
+
+    // Message line, e.g. why a note could not be opened
+    if !message.is_empty() {
+        let max_width = cols.saturating_sub(4) as usize;
+        let display: String = message.chars().take(max_width).collect();
+        stdout.queue(cursor::MoveTo(2, legend_row.saturating_sub(1)))?;
+        stdout.queue(style::PrintStyledContent(display.red()))?;
+    }
+
     // Legend
     stdout.queue(cursor::MoveTo(2, legend_row))?;
     stdout.queue(style::PrintStyledContent("/".bold().cyan()))?;
     stdout.queue(style::PrintStyledContent(" search  ".stylize()))?;
-    stdout.queue(style::PrintStyledContent("d".bold().cyan()))?;
-    stdout.queue(style::PrintStyledContent(" daily  ".stylize()))?;
-    stdout.queue(style::PrintStyledContent("w".bold().cyan()))?;
-    stdout.queue(style::PrintStyledContent(" weekly  ".stylize()))?;
-    stdout.queue(style::PrintStyledContent("m".bold().cyan()))?;
-    stdout.queue(style::PrintStyledContent(" monthly  ".stylize()))?;
+    stdout.queue(style::PrintStyledContent("g".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" grep  ".stylize()))?;
+    for kind in kinds {
+        if let Some(key) = kind.key {
+            stdout.queue(style::PrintStyledContent(key.to_string().bold().cyan()))?;
+            stdout.queue(style::PrintStyledContent(format!(" {}  ", kind.name).stylize()))?;
+        }
+    }
+    stdout.queue(style::PrintStyledContent("[ ]".bold().cyan()))?;
+    stdout.queue(style::PrintStyledContent(" prev/next  ".stylize()))?;
     stdout.queue(style::PrintStyledContent("s".bold().cyan()))?;
     stdout.queue(style::PrintStyledContent(" scratch  ".stylize()))?;
     stdout.queue(style::PrintStyledContent("n".bold().cyan()))?;
@@ -0,0 +1,20 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `git` in `dir`, failing the test if it fails, and returns stdout.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Makes `dir` a git repo with a committer identity.
+pub fn init_git_repo(dir: &Path) {
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "axon@example.com"]);
+    git(dir, &["config", "user.name", "axon"]);
+}
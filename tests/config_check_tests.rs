@@ -0,0 +1,155 @@
+use std::fs;
+
+use axon::config_check::{check_global_source, check_notes, globs_overlap, Severity};
+use axon::global_config::GlobalConfig;
+use tempfile::TempDir;
+
+fn check(source: &str) -> Vec<(Option<usize>, Severity, String)> {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir(tmp.path().join("templates")).unwrap();
+    fs::write(tmp.path().join("templates/daily.md"), "").unwrap();
+    fs::write(tmp.path().join("axon.toml"), source).unwrap();
+    check_notes(tmp.path())
+        .into_iter()
+        .map(|issue| (issue.line, issue.severity, issue.message))
+        .collect()
+}
+
+#[test]
+fn test_check_valid_config() {
+    let issues = check(
+        "[schemas]\n\"daily.*.md\" = \"templates/daily.md\"\n\n[periodic.yearly]\nformat = \"yearly.%Y\"\nperiod = \"year\"\n",
+    );
+    assert!(issues.is_empty(), "{issues:?}");
+    assert!(check_notes(TempDir::new().unwrap().path()).is_empty());
+}
+
+#[test]
+fn test_check_reports_syntax_errors_with_location() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("axon.toml"), "[schemas]\n\"daily.*\" = \n").unwrap();
+    let issues = check_notes(tmp.path());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Error);
+    assert_eq!((issues[0].line, issues[0].column), (Some(2), Some(13)));
+    assert!(issues[0].message.ends_with("(the whole file is ignored)"));
+}
+
+#[test]
+fn test_check_reports_types_and_unknown_keys() {
+    let issues = check("[schemas]\n\"weekly.*\" = 3\n\n[schema]\n");
+    assert_eq!(
+        issues,
+        vec![
+            (
+                Some(2),
+                Severity::Error,
+                "`schemas.weekly.*` must be a string (the whole file is ignored)".to_string()
+            ),
+            (
+                Some(4),
+                Severity::Warning,
+                "unknown key `schema` (did you mean `schemas`?)".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_check_reports_schema_problems() {
+    let issues = check(
+        "[schemas]\n\"daily.*.md\" = \"templates/daily.md\"\n\"daily.2025.01.*.md\" = \"templates/january.md\"\n",
+    );
+    assert_eq!(issues.len(), 2);
+    assert!(issues.iter().all(|(line, severity, _)| *line == Some(3) && *severity == Severity::Warning));
+    assert!(issues[0].2.contains("`templates/january.md`"));
+    assert!(issues[1].2.contains("overlaps `daily.*.md`"));
+}
+
+#[test]
+fn test_check_reports_periodic_problems() {
+    let issues = check(
+        "[periodic.quarterly]\nformat = \"q.%Y.Q%q\"\nperiod = \"quater\"\n\n[carry_over.weekly]\nfrom = \"dayly\"\n",
+    );
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].0, Some(1));
+    assert_eq!(issues[0].1, Severity::Error);
+    assert!(issues[0].2.contains("unknown period 'quater'"));
+    assert_eq!(issues[1].0, Some(6));
+    assert!(issues[1].2.contains("unknown periodic note kind `dayly`"));
+
+    let issues = check("[periodic.hourly]\nformat = \"h.%Y.%m.%d.%H\"\nperiod = \"day\"\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!((issues[0].0, issues[0].1), (Some(1), Severity::Error));
+    assert!(issues[0].2.contains("format 'h.%Y.%m.%d.%H'"), "{}", issues[0].2);
+}
+
+#[test]
+fn test_check_reports_ignored_tables() {
+    let issues = check(
+        "[periodic.yearly]\nformat = \"yearly.%Y\"\n\n[periodic.x]\nformat = 3\nperiod = \"year\"\n\n[carry_over.daily]\nheading = \"Tasks\"\n",
+    );
+    assert_eq!(
+        issues,
+        vec![
+            (
+                Some(1),
+                Severity::Error,
+                "periodic.yearly: missing field `period` (the table is ignored)".to_string()
+            ),
+            (
+                Some(5),
+                Severity::Error,
+                "`periodic.x.format` must be a string (the table is ignored)".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_global_config_drops_only_invalid_settings() {
+    let source = "notes_dir = \"~/n\"\npicker = 3\n\n[runners.codex]\ncommand = \"codex\"\n\n[runners.bad]\ncommand = 3\n\n[size]\ntokenizer = \"words\"\nmax_tokens = { feat = \"many\", sop = 500 }\n";
+    let cfg: GlobalConfig = toml::from_str(source).unwrap();
+    assert_eq!(cfg.notes_dir.as_deref(), Some("~/n"));
+    assert_eq!(cfg.picker, None);
+    assert_eq!(cfg.runners.keys().collect::<Vec<_>>(), ["codex"]);
+    assert_eq!(cfg.size.tokenizer.as_deref(), Some("words"));
+    assert_eq!(cfg.size.max_tokens.get("sop"), Some(&500));
+    assert!(!cfg.size.max_tokens.contains_key("feat"));
+
+    let issues: Vec<(Option<usize>, Severity, String)> =
+        check_global_source(std::path::Path::new("config.toml"), source)
+            .into_iter()
+            .map(|issue| (issue.line, issue.severity, issue.message))
+            .collect();
+    assert_eq!(
+        issues,
+        vec![
+            (
+                Some(2),
+                Severity::Error,
+                "`picker` must be a string (the setting is ignored)".to_string()
+            ),
+            (
+                Some(8),
+                Severity::Error,
+                "`runners.bad.command` must be a string (the table is ignored)".to_string()
+            ),
+            (
+                Some(12),
+                Severity::Error,
+                "`size.max_tokens.feat` must be an integer (the setting is ignored)".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_globs_overlap() {
+    assert!(globs_overlap("daily.*.md", "daily.2025.01.*.md"));
+    assert!(globs_overlap("*.md", "daily.*"));
+    assert!(globs_overlap("a*c", "*b*"));
+    assert!(!globs_overlap("daily.*", "weekly.*"));
+    assert!(!globs_overlap("*.md", "*.txt"));
+    assert!(!globs_overlap("daily.md", "daily.txt"));
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use axon::git::{find_repo_root, FileStatus, GitIndex};
+use common::{git, init_git_repo};
+use tempfile::TempDir;
+
+fn init_repo() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path());
+    tmp
+}
+
+#[test]
+fn test_find_repo_root() {
+    let tmp = init_repo();
+    let nested = tmp.path().join("notes/daily");
+    std::fs::create_dir_all(&nested).unwrap();
+    let root = std::path::absolute(tmp.path()).unwrap();
+    assert_eq!(find_repo_root(&nested), Some(root));
+}
+
+#[test]
+fn test_git_index_status_and_stage_renames() {
+    let tmp = init_repo();
+    let root = std::path::absolute(tmp.path()).unwrap();
+    std::fs::write(root.join(".gitignore"), "*.tmp.md\n").unwrap();
+    std::fs::write(root.join("tracked.md"), "one\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-qm", "init"]);
+    std::fs::write(root.join("untracked.md"), "").unwrap();
+    std::fs::write(root.join("scratch.tmp.md"), "").unwrap();
+
+    let files = vec![
+        root.join("tracked.md"),
+        root.join("untracked.md"),
+        root.join("scratch.tmp.md"),
+    ];
+    let index = GitIndex::load(&files).unwrap();
+    assert_eq!(index.status(&files[0]), FileStatus::Tracked);
+    assert_eq!(index.status(&files[1]), FileStatus::Untracked);
+    assert_eq!(index.status(&files[2]), FileStatus::Ignored);
+
+    let renames = vec![
+        (root.join("tracked.md"), root.join("renamed.md")),
+        (root.join("untracked.md"), root.join("other.md")),
+    ];
+    for (from, to) in &renames {
+        std::fs::rename(from, to).unwrap();
+    }
+    index.stage_renames(&renames).unwrap();
+
+    let status = git(&root, &["status", "--porcelain"]);
+    assert!(status.contains("R  tracked.md -> renamed.md"));
+    assert!(status.contains("?? other.md"));
+}
@@ -0,0 +1,80 @@
+use axon::history::{verify_renames, EntryStatus, History, Operation, MAX_ENTRIES};
+use axon::refactor::RenamePlan;
+use tempfile::TempDir;
+
+fn operation() -> Operation {
+    Operation {
+        command: "refactor".to_string(),
+        from_pattern: Some("{a}.{b}".to_string()),
+        to_pattern: Some("{b}.{a}".to_string()),
+        method: "fs".to_string(),
+    }
+}
+
+#[test]
+fn test_history_record_assigns_sequential_ids() {
+    let mut history = History::default();
+    assert_eq!(history.record(&operation(), &[RenamePlan::new("a.b.md", "b.a.md")], &[]), 1);
+    assert_eq!(history.record(&operation(), &[RenamePlan::new("c.d.md", "d.c.md")], &[]), 2);
+    assert_eq!(history.last_applied().unwrap().id, 2);
+}
+
+#[test]
+fn test_history_undo_redo_stack() {
+    let mut history = History::default();
+    history.record(&operation(), &[RenamePlan::new("a.b.md", "b.a.md")], &[]);
+    history.record(&operation(), &[RenamePlan::new("c.d.md", "d.c.md")], &[]);
+
+    history.mark_undone(2);
+    assert_eq!(history.get(2).unwrap().status, EntryStatus::Undone);
+    assert_eq!(history.last_applied().unwrap().id, 1);
+    assert_eq!(history.next_redo().unwrap().id, 2);
+
+    history.mark_redone(2);
+    assert!(history.next_redo().is_none());
+
+    history.mark_undone(2);
+    history.record(&operation(), &[RenamePlan::new("e.f.md", "f.e.md")], &[]);
+    assert!(history.next_redo().is_none());
+}
+
+#[test]
+fn test_history_entry_reversed() {
+    let mut history = History::default();
+    history.record(
+        &operation(),
+        &[RenamePlan::new("a.b.md", "b.a.md"), RenamePlan::new("c.d.md", "d.c.md")],
+        &[],
+    );
+    let reversed = history.get(1).unwrap().reversed();
+    assert_eq!(reversed[0].from, "d.c.md");
+    assert_eq!(reversed[0].to, "c.d.md");
+    assert_eq!(reversed[1].from, "b.a.md");
+}
+
+#[test]
+fn test_verify_renames_reports_mismatches() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.md"), "").unwrap();
+    std::fs::write(tmp.path().join("b.md"), "").unwrap();
+
+    assert!(verify_renames(tmp.path(), &[RenamePlan::new("a.md", "c.md")], false).is_ok());
+
+    let err = verify_renames(tmp.path(), &[RenamePlan::new("a.md", "b.md")], false).unwrap_err();
+    assert!(err.contains("b.md already exists"));
+    assert!(verify_renames(tmp.path(), &[RenamePlan::new("a.md", "b.md")], true).is_ok());
+
+    let err = verify_renames(tmp.path(), &[RenamePlan::new("x.md", "y.md")], false).unwrap_err();
+    assert!(err.contains("x.md is missing"));
+}
+
+#[test]
+fn test_history_record_drops_the_oldest_entries() {
+    let mut history = History::default();
+    for _ in 0..MAX_ENTRIES + 2 {
+        history.record(&operation(), &[RenamePlan::new("a.b.md", "b.a.md")], &[]);
+    }
+    assert_eq!(history.entries.len(), MAX_ENTRIES);
+    assert_eq!(history.entries[0].id, 3);
+    assert_eq!(history.last_applied().unwrap().id, MAX_ENTRIES as u64 + 2);
+}
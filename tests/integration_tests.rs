@@ -1,6 +1,10 @@
+mod common;
+
 use std::process::Command;
 use tempfile::TempDir;
 
+use common::{git, init_git_repo};
+
 #[test]
 fn test_refactor_dry_run_no_changes() {
     let tmp = TempDir::new().unwrap();
@@ -131,3 +135,1299 @@ fn test_refactor_empty_dir_exit_code() {
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("No markdown files found"));
 }
+
+fn run_refactor(dir: &std::path::Path, from: &str, to: &str) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["refactor", "--from", from, "--to", to, "--yes", "--no-git"])
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_refactor_records_history_and_undo_redo() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "").unwrap();
+
+    let output = run_refactor(
+        tmp.path(),
+        "{repo}.{feature}.{type}.{variant}.v{N}",
+        "{repo}.{type}.{feature}.{variant}.v{N}",
+    );
+    assert!(output.status.success());
+    assert!(tmp.path().join("foo.specs.bar.initial.v1.md").exists());
+    assert!(tmp.path().join(".axon/history.json").exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["history"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("#1"));
+    assert!(stdout.contains("{repo}.{feature}.{type}.{variant}.v{N}"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(tmp.path().join("foo.bar.specs.initial.v1.md").exists());
+    assert!(!tmp.path().join("foo.specs.bar.initial.v1.md").exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["redo", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!tmp.path().join("foo.bar.specs.initial.v1.md").exists());
+    assert!(tmp.path().join("foo.specs.bar.initial.v1.md").exists());
+}
+
+#[test]
+fn test_undo_multiple_levels_by_id() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "").unwrap();
+
+    assert!(run_refactor(tmp.path(), "{a}.{b}.{c}.{d}.v{N}", "{a}.{c}.{b}.{d}.v{N}")
+        .status
+        .success());
+    assert!(run_refactor(tmp.path(), "{a}.{b}.{c}.{d}.v{N}", "{a}.{b}.{c}.{d}.v{N}.x")
+        .status
+        .success());
+    assert!(tmp.path().join("foo.specs.bar.initial.v1.x.md").exists());
+
+    // Undoing the first operation cannot succeed while the second still holds its file.
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "1", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(tmp.path().join("foo.specs.bar.initial.v1.x.md").exists());
+
+    for _ in 0..2 {
+        let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args(["undo", "--no-git"])
+            .current_dir(tmp.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+    assert!(tmp.path().join("foo.bar.specs.initial.v1.md").exists());
+}
+
+#[test]
+fn test_undo_rolls_back_when_a_rename_fails_partway() {
+    use axon::history::{History, Operation};
+    use axon::refactor::RenamePlan;
+
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("y.md"), "").unwrap();
+    std::fs::write(tmp.path().join("z.md"), "").unwrap();
+    let operation = Operation {
+        command: "refactor".to_string(),
+        from_pattern: None,
+        to_pattern: None,
+        method: "fs".to_string(),
+    };
+    let renames = [("gone/a.md", "z.md"), ("x.md", "y.md")].map(|(from, to)| RenamePlan::new(from, to));
+    let mut history = History::default();
+    history.record(&operation, &renames, &[]);
+    axon::history::save(tmp.path(), &history).unwrap();
+
+    // y.md moves back first; z.md can't, since gone/ no longer exists.
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Rolled back 1 completed renames"));
+    assert!(tmp.path().join("y.md").exists());
+    assert!(!tmp.path().join("x.md").exists());
+    let history = axon::history::load(tmp.path()).unwrap();
+    assert!(history.last_applied().is_some());
+}
+
+#[test]
+fn test_undo_refuses_when_files_changed() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "").unwrap();
+
+    assert!(run_refactor(
+        tmp.path(),
+        "{repo}.{feature}.{type}.{variant}.v{N}",
+        "{repo}.{type}.{feature}.{variant}.v{N}",
+    )
+    .status
+    .success());
+    std::fs::remove_file(tmp.path().join("foo.specs.bar.initial.v1.md")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("foo.specs.bar.initial.v1.md is missing"));
+}
+
+#[test]
+fn test_refactor_rewrite_links_and_undo_restores_contents() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "# Bar\n").unwrap();
+    std::fs::write(
+        tmp.path().join("foo.baz.specs.initial.v1.md"),
+        "see [[foo.bar.specs.initial.v1]]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        tmp.path().join("prompts.md"),
+        "- [bar](foo.bar.specs.initial.v1.md)\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args([
+            "refactor",
+            "--from",
+            "{repo}.{feature}.{type}.{variant}.v{N}",
+            "--to",
+            "{repo}.{type}.{feature}.{variant}.v{N}",
+            "--rewrite-links",
+            "--dry-run",
+            "--no-git",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Link rewrites:"));
+    assert!(stdout.contains("prompts.md:1"));
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("prompts.md")).unwrap(),
+        "- [bar](foo.bar.specs.initial.v1.md)\n"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args([
+            "refactor",
+            "--from",
+            "{repo}.{feature}.{type}.{variant}.v{N}",
+            "--to",
+            "{repo}.{type}.{feature}.{variant}.v{N}",
+            "--rewrite-links",
+            "--yes",
+            "--no-git",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("prompts.md")).unwrap(),
+        "- [bar](foo.specs.bar.initial.v1.md)\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("foo.specs.baz.initial.v1.md")).unwrap(),
+        "see [[foo.specs.bar.initial.v1]]\n"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("prompts.md")).unwrap(),
+        "- [bar](foo.bar.specs.initial.v1.md)\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("foo.baz.specs.initial.v1.md")).unwrap(),
+        "see [[foo.bar.specs.initial.v1]]\n"
+    );
+}
+
+#[test]
+fn test_links_command_reports_broken_links() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.sop.style.v2.md"), "").unwrap();
+    std::fs::write(tmp.path().join("prompts.md"), "use a.sop.style.v1\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .arg("links")
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("prompts.md:1: a.sop.style.v1.md (did you mean a.sop.style.v2.md?)"));
+}
+
+#[test]
+fn test_health_links_rule() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.sop.style.v1.md"), "see [[a.sop.other.v1]]\n").unwrap();
+
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .arg("health")
+            .args(extra)
+            .env("AXON_PROMPTS_DIR", tmp.path().join("none"))
+            .env("AXON_NOTES_DIR", tmp.path().join("none"))
+            .current_dir(tmp.path())
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&[]).status.success());
+    let output = run(&["--links"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("a.sop.style.v1.md:1: a.sop.other.v1"));
+}
+
+#[test]
+fn test_health_reports_broken_includes() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.sop.style.v1.md"), "style\n").unwrap();
+    std::fs::write(
+        tmp.path().join("a.feat.auth.spec.initial.v1.md"),
+        "<!-- axon:include a.sop.style -->\n",
+    )
+    .unwrap();
+
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .arg("health")
+            .env("AXON_PROMPTS_DIR", tmp.path().join("none"))
+            .env("AXON_NOTES_DIR", tmp.path().join("none"))
+            .current_dir(tmp.path())
+            .output()
+            .unwrap()
+    };
+
+    assert!(run().status.success());
+    std::fs::write(
+        tmp.path().join("a.feat.auth.spec.initial.v1.md"),
+        "<!-- axon:include a.sop.style -->\n{{> a.sop.tone}}\n",
+    )
+    .unwrap();
+    let output = run();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Broken includes: 1"), "{stdout}");
+    assert!(stdout.contains("a.feat.auth.spec.initial.v1.md (error: cannot include 'a.sop.tone': no such prompt in"));
+}
+
+#[test]
+fn test_refactor_plan_out_and_apply() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("foo.a.specs.initial.v1.md"), "").unwrap();
+    std::fs::write(tmp.path().join("foo.b.specs.initial.v1.md"), "").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args([
+            "refactor",
+            "--from",
+            "{repo}.{feature}.{type}.{variant}.v{N}",
+            "--to",
+            "{repo}.{type}.{feature}.{variant}.v{N}",
+            "--plan-out",
+            "plan.tsv",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(tmp.path().join("foo.a.specs.initial.v1.md").exists());
+    let plan = std::fs::read_to_string(tmp.path().join("plan.tsv")).unwrap();
+    assert!(plan.contains("foo.a.specs.initial.v1.md\tfoo.specs.a.initial.v1.md"));
+
+    // Drop one rename and retarget the other before applying.
+    std::fs::write(
+        tmp.path().join("plan.tsv"),
+        "from\tto\nfoo.a.specs.initial.v1.md\tfoo.a.edited.v1.md\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["refactor", "--apply", "plan.tsv", "--yes", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(tmp.path().join("foo.a.edited.v1.md").exists());
+    assert!(tmp.path().join("foo.b.specs.initial.v1.md").exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["refactor", "--apply", "plan.tsv", "--yes", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(tmp.path().join("foo.a.specs.initial.v1.md").exists());
+}
+
+#[test]
+fn test_refactor_apply_refuses_paths_outside_the_root() {
+    let tmp = TempDir::new().unwrap();
+    let notes = tmp.path().join("notes");
+    std::fs::create_dir(&notes).unwrap();
+    std::fs::write(notes.join("a.md"), "").unwrap();
+
+    for target in ["../a.md", "/tmp/a.md"] {
+        std::fs::write(notes.join("plan.tsv"), format!("from\tto\na.md\t{target}\n")).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args(["refactor", "--apply", "plan.tsv", "--yes", "--no-git"])
+            .current_dir(&notes)
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains(target));
+        assert!(notes.join("a.md").exists());
+    }
+    assert!(!tmp.path().join("a.md").exists());
+}
+
+#[test]
+fn test_refactor_retry_rewrites_links_after_partial_failure() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.md"), "").unwrap();
+    std::fs::write(tmp.path().join("b.md"), "").unwrap();
+    std::fs::write(tmp.path().join("index.md"), "[[a]] and [[b]]\n").unwrap();
+    // b.md can't move until sub/ exists.
+    std::fs::write(tmp.path().join("plan.tsv"), "from\tto\na.md\ta2.md\nb.md\tsub/b2.md\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["refactor", "--apply", "plan.tsv", "--rewrite-links", "--yes", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    assert!(tmp.path().join("a2.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("index.md")).unwrap(),
+        "[[a]] and [[b]]\n"
+    );
+
+    std::fs::create_dir(tmp.path().join("sub")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["refactor", "--retry", "--no-git"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(tmp.path().join("sub/b2.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("index.md")).unwrap(),
+        "[[a2]] and [[b2]]\n"
+    );
+    assert!(!tmp.path().join(".axon-retry.json").exists());
+}
+
+#[test]
+fn test_refactor_multiple_dirs_and_undo_from_root() {
+    let tmp = TempDir::new().unwrap();
+    let prompts = tmp.path().join("prompts");
+    let notes = tmp.path().join("notes");
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::create_dir_all(&notes).unwrap();
+    std::fs::write(prompts.join("foo.bar.specs.initial.v1.md"), "").unwrap();
+    std::fs::write(
+        notes.join("daily.2026.03.02.md"),
+        "see [[foo.bar.specs.initial.v1]]\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args([
+            "refactor",
+            "--from",
+            "{repo}.{feature}.{type}.{variant}.v{N}",
+            "--to",
+            "{repo}.{type}.{feature}.{variant}.v{N}",
+            "--dir",
+            "prompts",
+            "--dir",
+            "notes",
+            "--rewrite-links",
+            "--yes",
+            "--no-git",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(prompts.join("foo.specs.bar.initial.v1.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(notes.join("daily.2026.03.02.md")).unwrap(),
+        "see [[foo.specs.bar.initial.v1]]\n"
+    );
+
+    let history = std::fs::read_to_string(tmp.path().join(".axon/history.json")).unwrap();
+    assert!(history.contains("prompts/foo.bar.specs.initial.v1.md"));
+    assert!(history.contains("notes/daily.2026.03.02.md"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["undo", "--no-git", "--root"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(prompts.join("foo.bar.specs.initial.v1.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(notes.join("daily.2026.03.02.md")).unwrap(),
+        "see [[foo.bar.specs.initial.v1]]\n"
+    );
+}
+
+#[test]
+fn test_refactor_commit_creates_single_commit() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path());
+    std::fs::write(tmp.path().join(".gitignore"), ".axon/\n").unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "# Bar\n").unwrap();
+    std::fs::write(
+        tmp.path().join("prompts.md"),
+        "- [bar](foo.bar.specs.initial.v1.md)\n",
+    )
+    .unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-qm", "init"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args([
+            "refactor",
+            "--from",
+            "{repo}.{feature}.{type}.{variant}.v{N}",
+            "--to",
+            "{repo}.{type}.{feature}.{variant}.v{N}",
+            "--rewrite-links",
+            "--commit",
+            "--yes",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(git(tmp.path(), &["status", "--porcelain"]).is_empty());
+
+    let log = git(tmp.path(), &["log", "-1", "--format=%B"]);
+    assert!(log.starts_with(
+        "Rename 1 files: {repo}.{feature}.{type}.{variant}.v{N} -> {repo}.{type}.{feature}.{variant}.v{N}"
+    ));
+    assert!(log.contains("- foo.bar.specs.initial.v1.md -> foo.specs.bar.initial.v1.md"));
+    let files = git(tmp.path(), &["show", "--name-status", "--format=", "HEAD"]);
+    assert!(files.contains("foo.specs.bar.initial.v1.md"));
+    assert!(files.contains("prompts.md"));
+}
+
+#[test]
+fn test_refactor_case_only_rename_with_git() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path());
+    std::fs::write(tmp.path().join(".gitignore"), ".axon/\nplan.tsv\n").unwrap();
+    std::fs::write(tmp.path().join("Notes.md"), "# Notes\n").unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-qm", "init"]);
+    std::fs::write(tmp.path().join("plan.tsv"), "from\tto\nNotes.md\tnotes.md\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["refactor", "--apply", "plan.tsv", "--git", "--yes"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let files = git(tmp.path(), &["ls-files"]);
+    assert!(files.lines().any(|file| file == "notes.md"));
+    assert!(!files.lines().any(|file| file == "Notes.md"));
+    assert_eq!(
+        git(tmp.path(), &["status", "--porcelain"]),
+        "R  Notes.md -> notes.md\n"
+    );
+}
+
+#[test]
+fn test_refactor_commit_leaves_unstaged_edits_out() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path());
+    std::fs::write(tmp.path().join(".gitignore"), ".axon/\n").unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "# Bar\n").unwrap();
+    std::fs::write(tmp.path().join("prompts.md"), "- [bar](foo.bar.specs.initial.v1.md)\n").unwrap();
+    std::fs::write(tmp.path().join("notes.md"), "- [bar](foo.bar.specs.initial.v1.md)\n").unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-qm", "init"]);
+    let draft = "- [bar](foo.bar.specs.initial.v1.md)\ndraft\n";
+    std::fs::write(tmp.path().join("prompts.md"), draft).unwrap();
+
+    let refactor = |dir: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args([
+                "refactor",
+                "--from",
+                "{repo}.{feature}.{type}.{variant}.v{N}",
+                "--to",
+                "{repo}.{type}.{feature}.{variant}.v{N}",
+                "--rewrite-links",
+                "--commit",
+                "--yes",
+            ])
+            .current_dir(dir)
+            .output()
+            .unwrap()
+    };
+    let output = refactor(tmp.path());
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("prompts.md"));
+    assert!(tmp.path().join("foo.bar.specs.initial.v1.md").exists());
+    assert_eq!(std::fs::read_to_string(tmp.path().join("prompts.md")).unwrap(), draft);
+    assert_eq!(git(tmp.path(), &["rev-list", "--count", "HEAD"]).trim(), "1");
+
+    // Edits in files without links to rewrite stay out of the commit.
+    git(tmp.path(), &["checkout", "-q", "prompts.md"]);
+    std::fs::write(tmp.path().join("other.md"), "draft\n").unwrap();
+    git(tmp.path(), &["add", "other.md"]);
+    git(tmp.path(), &["commit", "-qm", "other"]);
+    std::fs::write(tmp.path().join("other.md"), "draft 2\n").unwrap();
+    let output = refactor(tmp.path());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        git(tmp.path(), &["show", "HEAD:prompts.md"]),
+        "- [bar](foo.specs.bar.initial.v1.md)\n"
+    );
+    assert_eq!(git(tmp.path(), &["status", "--porcelain"]), " M other.md\n");
+}
+
+#[test]
+fn test_refactor_commit_refuses_staged_changes() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path());
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "").unwrap();
+    std::fs::write(tmp.path().join("notes.txt"), "").unwrap();
+    git(tmp.path(), &["add", "notes.txt"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args([
+            "refactor",
+            "--from",
+            "{repo}.{feature}.{type}.{variant}.v{N}",
+            "--to",
+            "{repo}.{type}.{feature}.{variant}.v{N}",
+            "--commit",
+            "--yes",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("notes.txt"));
+    assert!(tmp.path().join("foo.bar.specs.initial.v1.md").exists());
+}
+
+#[test]
+fn test_periodic_commands_generated_from_config() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(
+        tmp.path().join("axon.toml"),
+        "[periodic.yearly]\nformat = \"yearly.%Y\"\nperiod = \"year\"\nkey = \"y\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .arg("--help")
+        .env("AXON_NOTES_DIR", tmp.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("daily"));
+    assert!(stdout.contains("yearly"));
+    assert!(stdout.contains("[aliases: y]"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["y", "not-a-date"])
+        .env("AXON_NOTES_DIR", tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+
+    for offset in ["999999999999", "3000000"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args(["d", "--offset", offset])
+            .env("AXON_NOTES_DIR", tmp.path())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("out of range"));
+    }
+    let notes: Vec<_> = std::fs::read_dir(tmp.path()).unwrap().collect();
+    assert_eq!(notes.len(), 1);
+}
+
+fn run_config(home: &std::path::Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_axon"))
+        .arg("config")
+        .args(args)
+        .env("HOME", home)
+        .env("AXON_NOTES_DIR", home.join("notes"))
+        .env_remove("AXON_PROMPTS_DIR")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_config_check_and_warnings() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir(tmp.path().join("notes")).unwrap();
+    std::fs::write(
+        tmp.path().join("notes/axon.toml"),
+        "[schemas]\n\"daily.*\" = 3\n",
+    )
+    .unwrap();
+
+    let output = run_config(tmp.path(), &["check"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("axon.toml:2:13: error: `schemas.daily.*` must be a string"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["validate", "myapp.sop.coding-style.v1.md"])
+        .env("HOME", tmp.path())
+        .env("AXON_NOTES_DIR", tmp.path().join("notes"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning:"));
+    assert!(stderr.contains("axon config check"));
+
+    // Unknown keys are worth a warning too, and so is the bare TUI command
+    // (which fails here without a terminal, after warning).
+    std::fs::write(
+        tmp.path().join("notes/axon.toml"),
+        "[schemas]\n\"daily.*\" = \"daily.md\"\n[carry_over.daily]\nheadng = \"Tasks\"\n",
+    )
+    .unwrap();
+    for args in [&["validate", "myapp.sop.coding-style.v1.md"][..], &[]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("AXON_NOTES_DIR", tmp.path().join("notes"))
+            .stdin(std::process::Stdio::null())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("unknown key `carry_over.daily.headng`"), "{stderr}");
+    }
+}
+
+#[test]
+fn test_config_init_set_get_show() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+
+    assert!(run_config(home, &["init", "--notes"]).status.success());
+    assert_eq!(run_config(home, &["init", "--notes"]).status.code(), Some(3));
+    let notes_toml = home.join("notes/axon.toml");
+    let starter = std::fs::read_to_string(&notes_toml).unwrap();
+    assert!(starter.starts_with("# axon notes configuration"));
+
+    std::fs::write(
+        &notes_toml,
+        "# mine\n[schemas]\n\"daily.*.md\" = \"daily.md\"  # keep me\n",
+    )
+    .unwrap();
+    assert!(run_config(home, &["set", "schemas.\"daily.*.md\"", "templates/daily.md"]).status.success());
+    // Half a periodic table wouldn't load, so it isn't written.
+    let output = run_config(home, &["set", "periodic.yearly.format", "yearly.%Y"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing field `period`"));
+    assert!(!std::fs::read_to_string(&notes_toml).unwrap().contains("yearly"));
+    let output = run_config(
+        home,
+        &["set", "periodic.yearly.format", "yearly.%Y", "periodic.yearly.period", "year"],
+    );
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(run_config(home, &["set", "periodic.yearly.period", "fortnight"]).status.code(), Some(2));
+    assert_eq!(run_config(home, &["set", "periodic.yearly.period"]).status.code(), Some(2));
+    assert!(run_config(home, &["set", "prompts_dir", "~/my-prompts"]).status.success());
+    assert_eq!(run_config(home, &["set", "bogus", "x"]).status.code(), Some(2));
+    assert_eq!(
+        std::fs::read_to_string(&notes_toml).unwrap(),
+        "# mine\n[schemas]\n\"daily.*.md\" = \"templates/daily.md\"  # keep me\n\n[periodic.yearly]\nformat = \"yearly.%Y\"\nperiod = \"year\"\n"
+    );
+
+    let output = run_config(home, &["get", "prompts_dir"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        home.join("my-prompts").display().to_string()
+    );
+    let output = run_config(home, &["get", "carry_over.daily.heading"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run_config(home, &["show"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("# env AXON_NOTES_DIR"));
+    assert!(stdout.contains(&format!("# {}", home.join(".config/axon/config.toml").display())));
+    assert!(stdout.contains("periodic.daily.format = \"daily.%Y.%m.%d\""));
+    assert!(stdout.contains("periodic.yearly.period = \"year\""));
+    assert!(stdout.contains("picker = \"builtin\""));
+
+    let output = run_config(home, &["set", "picker", "skim"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown picker 'skim'"));
+    let global_toml = home.join(".config/axon/config.toml");
+    assert!(!std::fs::read_to_string(&global_toml).unwrap().contains("skim"));
+    std::fs::write(&global_toml, "picker = \"skim\"\n").unwrap();
+    let output = run_config(home, &["check"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("error: unknown picker 'skim' (expected builtin or fzf)"), "{stdout}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_periodic_note_uses_configured_opener() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    std::fs::create_dir_all(home.join(".config/axon")).unwrap();
+    let script = home.join("open.sh");
+    std::fs::write(&script, "#!/bin/sh\necho \"$@\" > \"$HOME/opened\"\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        home.join(".config/axon/config.toml"),
+        format!("opener = \"{} --line {{line}} {{path}}\"\n", script.display()),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["d", "2026-03-02"])
+        .env("HOME", home)
+        .env("AXON_NOTES_DIR", home.join("notes"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let opened = std::fs::read_to_string(home.join("opened")).unwrap();
+    assert_eq!(
+        opened.trim(),
+        format!("--line 1 {}", home.join("notes/daily.2026.03.02.md").display())
+    );
+
+    std::fs::write(
+        home.join(".config/axon/config.toml"),
+        "opener = \"axon-no-such-editor {path}\"\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["d", "2026-03-02"])
+        .env("HOME", home)
+        .env("AXON_NOTES_DIR", home.join("notes"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("`axon-no-such-editor`"));
+    assert!(stderr.contains("not installed or not on PATH"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_prompt_with_configured_runner() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    std::fs::create_dir_all(home.join(".config/axon")).unwrap();
+    std::fs::create_dir_all(home.join("prompts")).unwrap();
+    std::fs::create_dir_all(home.join("work")).unwrap();
+    std::fs::write(home.join("prompts/review.md"), "Review this.\n").unwrap();
+    let script = home.join("agent.sh");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\n{ pwd; echo \"$AGENT_MODE\"; for arg in \"$@\"; do echo \"$arg\"; done; } > \"$HOME/ran\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        home.join(".config/axon/config.toml"),
+        format!(
+            "runner = \"stub\"\n\n[runners.stub]\ncommand = \"{}\"\nargs = \"--file {{prompt_path}} {{instruction}}\"\ncwd = \"~/work\"\nenv = {{ AGENT_MODE = \"test\" }}\n",
+            script.display()
+        ),
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .arg("run")
+            .args(args)
+            .env("HOME", home)
+            .env_remove("XDG_STATE_HOME")
+            .env("AXON_NOTES_DIR", home.join("notes"))
+            .env("AXON_PROMPTS_DIR", home.join("prompts"))
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["review"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let ran = std::fs::read_to_string(home.join("ran")).unwrap();
+    let prompts = home.join("prompts");
+    assert_eq!(
+        ran.lines().collect::<Vec<_>>(),
+        vec![
+            home.join("work").display().to_string(),
+            "test".to_string(),
+            "--file".to_string(),
+            prompts.join("review.md").display().to_string(),
+            format!(
+                "read {} and {} and ask for next instruction",
+                prompts.join("prompts.md").display(),
+                prompts.join("review.md").display()
+            ),
+        ]
+    );
+
+    // Without a prompt, the runner starts a session with prompts.md only.
+    let output = run(&[]);
+    assert!(output.status.success());
+    let ran = std::fs::read_to_string(home.join("ran")).unwrap();
+    assert_eq!(ran.lines().count(), 4);
+    assert!(!ran.contains("--file\n/"));
+
+    let output = run(&["review", "--runner", "codex"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown runner 'codex' (available: stub, claude)"));
+
+    let output = run(&["missing"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no prompt matches 'missing'"));
+
+    std::fs::write(
+        home.join(".config/axon/config.toml"),
+        "runner = \"codex\"\n\n[runners.bad]\nargs = \"{instruction}\"\n",
+    )
+    .unwrap();
+    let output = run_config(home, &["check"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(":1:1: error: `runner` is 'codex', but there is no [runners.codex] table"));
+    assert!(stdout.contains(":3:10: error: runners.bad: missing `command`"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_resolves_prompt_and_repo_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let prompts = home.join("prompts");
+    std::fs::create_dir_all(home.join(".config/axon")).unwrap();
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::create_dir_all(home.join("code/myapp")).unwrap();
+    for name in [
+        "myapp.feat.auth.spec.initial.v1.md",
+        "myapp.feat.auth.spec.initial.v2.md",
+        "other.sop.deploy.v1.md",
+    ] {
+        std::fs::write(prompts.join(name), "").unwrap();
+    }
+    let script = home.join("agent.sh");
+    std::fs::write(&script, "#!/bin/sh\n{ pwd; echo \"$1\"; cat \"$1\"; } > \"$HOME/ran\"\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        home.join(".config/axon/config.toml"),
+        format!(
+            "runner = \"stub\"\n\n[runners.stub]\ncommand = \"{}\"\nargs = \"{{prompt_path}}\"\n\n[repos]\nmyapp = \"~/code/myapp\"\n",
+            script.display()
+        ),
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .arg("run")
+            .args(args)
+            .env("HOME", home)
+            .env_remove("XDG_STATE_HOME")
+            .env("AXON_NOTES_DIR", home.join("notes"))
+            .env("AXON_PROMPTS_DIR", &prompts)
+            .current_dir(home)
+            .output()
+            .unwrap()
+    };
+
+    // The identity runs the latest version, in the repo's directory.
+    let output = run(&["myapp.feat.auth.spec.initial"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let ran = std::fs::read_to_string(home.join("ran")).unwrap();
+    assert_eq!(
+        ran.lines().collect::<Vec<_>>(),
+        vec![
+            home.join("code/myapp").display().to_string(),
+            prompts.join("myapp.feat.auth.spec.initial.v2.md").display().to_string(),
+        ]
+    );
+
+    // Repos without a directory run where the runner does.
+    let output = run(&["deploy"]);
+    assert!(output.status.success());
+    let ran = std::fs::read_to_string(home.join("ran")).unwrap();
+    assert_eq!(ran.lines().next().unwrap(), home.display().to_string());
+
+    let output = run(&["auth", "--print"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        format!(
+            "read {} and {} and ask for next instruction",
+            prompts.join("prompts.md").display(),
+            prompts.join("myapp.feat.auth.spec.initial.v2.md").display()
+        )
+    );
+
+    // Prompts with includes are composed into a file the runner reads.
+    std::fs::write(prompts.join("other.sop.deploy.v2.md"), "<!-- axon:include other.sop.deploy.v1.md -->\ndeploy\n").unwrap();
+    std::fs::write(prompts.join("other.sop.deploy.v1.md"), "context\n").unwrap();
+    let composed_dir = home.join(".local/state/axon/composed");
+    let output = run(&["other.sop.deploy", "--print"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&prompts.join("other.sop.deploy.v2.md").display().to_string()));
+    assert!(!composed_dir.exists());
+
+    let output = run(&["other.sop.deploy"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let ran = std::fs::read_to_string(home.join("ran")).unwrap();
+    let lines: Vec<&str> = ran.lines().collect();
+    assert!(lines[1].starts_with(&composed_dir.display().to_string()));
+    assert!(lines[1].ends_with("/other.sop.deploy.v2.md"));
+    assert_eq!(lines[2..], ["context", "deploy"]);
+    // The copy only lives as long as the run.
+    assert_eq!(std::fs::read_dir(&composed_dir).unwrap().count(), 0);
+
+    let output = run(&["zzz", "--print"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "no prompt matches 'zzz' in {}",
+        prompts.display()
+    )));
+}
+
+#[test]
+fn test_render_prompt_variables() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let prompts = home.join("prompts");
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::write(
+        prompts.join("myapp.feat.fix.prompt.initial.v1.md"),
+        "Fix {{ticket}} on {{branch}}.\n{{#if notes}}\nNotes: {{notes}}\n{{/if}}\n",
+    )
+    .unwrap();
+    std::fs::write(home.join("vars.toml"), "ticket = \"ABC-1\"\nbranch = \"main\"\n").unwrap();
+
+    let render = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .arg("render")
+            .args(args)
+            .env("HOME", home)
+            .env("AXON_NOTES_DIR", home.join("notes"))
+            .env("AXON_PROMPTS_DIR", &prompts)
+            .current_dir(home)
+            .output()
+            .unwrap()
+    };
+
+    let output = render(&["fix", "--list-vars"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ticket\nbranch\nnotes (optional)\n");
+
+    let output = render(&["fix", "--var", "ticket=ABC-2"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing variables: branch"));
+
+    // --var overrides --vars-file.
+    let output = render(&["fix", "--vars-file", "vars.toml", "--var", "ticket=ABC-2"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Fix ABC-2 on main.\n");
+
+    let output = render(&["fix", "--vars-file", "vars.toml", "--var", "notes=urgent", "--out", "out.md"]);
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(home.join("out.md")).unwrap(),
+        "Fix ABC-1 on main.\nNotes: urgent\n"
+    );
+
+    let output = render(&["fix", "--var", "ticket"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_log_runs_and_stats() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let prompts = home.join("prompts");
+    std::fs::create_dir_all(home.join(".config/axon")).unwrap();
+    std::fs::create_dir_all(&prompts).unwrap();
+    for name in [
+        "myapp.feat.auth.spec.initial.v1.md",
+        "myapp.sop.deploy.v1.md",
+        "toolkit.sop.release.v1.md",
+    ] {
+        std::fs::write(prompts.join(name), "").unwrap();
+    }
+    // Fails for the deploy prompt.
+    let script = home.join("agent.sh");
+    std::fs::write(&script, "#!/bin/sh\ncase \"$1\" in *deploy*) exit 3;; esac\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        home.join(".config/axon/config.toml"),
+        format!("runner = \"stub\"\n\n[runners.stub]\ncommand = \"{}\"\nargs = \"{{prompt_path}}\"\n", script.display()),
+    )
+    .unwrap();
+
+    let axon = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args(args)
+            .env("HOME", home)
+            .env_remove("XDG_STATE_HOME")
+            .env("AXON_NOTES_DIR", home.join("notes"))
+            .env("AXON_PROMPTS_DIR", &prompts)
+            .current_dir(&prompts)
+            .output()
+            .unwrap()
+    };
+
+    let output = axon(&["runs"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "No runs recorded.");
+
+    assert!(axon(&["run", "myapp.feat.auth.spec.initial.v1"]).status.success());
+    assert!(axon(&["run", "auth"]).status.success());
+    assert_eq!(axon(&["run", "deploy"]).status.code(), Some(1));
+    // --print doesn't run anything.
+    assert!(axon(&["run", "release", "--print"]).status.success());
+
+    let log = std::fs::read_to_string(home.join(".local/state/axon/runs.jsonl")).unwrap();
+    assert_eq!(log.lines().count(), 3);
+    let first: serde_json::Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+    assert_eq!(first["prompt"], "myapp.feat.auth.spec.initial.v1.md");
+    assert_eq!(first["repo"], "myapp");
+    assert_eq!(first["category"], "feat");
+    assert_eq!(first["identity"], "myapp.feat.auth.spec.initial");
+    assert_eq!(first["version"], 1);
+    assert_eq!(first["runner"], "stub");
+    assert_eq!(first["exit_code"], 0);
+
+    let output = axon(&["runs"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("myapp.sop.deploy.v1.md") && lines[0].contains("exit 3"), "{stdout}");
+    assert!(lines[2].contains("myapp.feat.auth.spec.initial.v1.md") && lines[2].contains(" ok "));
+
+    let output = axon(&["runs", "--failed", "--json"]);
+    let failed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(failed.as_array().unwrap().len(), 1);
+    assert_eq!(failed[0]["prompt"], "myapp.sop.deploy.v1.md");
+    let output = axon(&["runs", "--prompt", "auth", "-n", "1", "--json"]);
+    let runs: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(runs.as_array().unwrap().len(), 1);
+    assert_eq!(axon(&["runs", "--repo", "toolkit"]).stdout, b"No runs recorded.\n");
+    assert_eq!(axon(&["runs", "--since", "bad"]).status.code(), Some(2));
+
+    let output = axon(&["stats", "--json"]);
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["most_run"][0]["prompt"], "myapp.feat.auth.spec.initial.v1.md");
+    assert_eq!(stats["most_run"][0]["runs"], 2);
+    assert_eq!(stats["never_run"], serde_json::json!(["toolkit.sop.release.v1.md"]));
+    let stdout = String::from_utf8(axon(&["stats"]).stdout).unwrap();
+    assert!(stdout.contains("Most run:\n  myapp.feat.auth.spec.initial.v1.md: 2 runs\n"), "{stdout}");
+    assert!(stdout.contains("  myapp.sop.deploy.v1.md            : 1 runs\n"));
+    assert!(stdout.contains("Never run:\n  toolkit.sop.release.v1.md\n"));
+}
+
+#[test]
+fn test_size_and_max_tokens() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.sop.style.v1.md"), "Use short sentences.\n").unwrap();
+    std::fs::write(
+        tmp.path().join("a.feat.auth.spec.initial.v1.md"),
+        "Build login.\n<!-- axon:include a.sop.style -->\n",
+    )
+    .unwrap();
+
+    let axon = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("AXON_PROMPTS_DIR", tmp.path())
+            .env("AXON_NOTES_DIR", tmp.path().join("notes"))
+            .current_dir(tmp.path())
+            .output()
+            .unwrap()
+    };
+
+    let output = axon(&["size", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["tokenizer"], "chars");
+    assert_eq!(json["files"][0]["file"], "a.feat.auth.spec.initial.v1.md");
+    assert_eq!(json["files"][0]["words"], 6);
+    assert_eq!(json["files"][0]["expanded"]["words"], 5);
+    assert_eq!(json["total"]["lines"], 3);
+
+    let output = axon(&["size", "--tokenizer", "bpe"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = axon(&["stats", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["sizes"][1]["file"], "a.sop.style.v1.md");
+    assert_eq!(json["sizes"][1]["tokens"], 6);
+
+    assert!(axon(&["health"]).status.success());
+    let output = run_config(tmp.path(), &["set", "size.max_tokens.feat", "5"]);
+    assert!(output.status.success());
+    let config = std::fs::read_to_string(tmp.path().join(".config/axon/config.toml")).unwrap();
+    assert!(config.contains("[size.max_tokens]\nfeat = 5\n"), "{config}");
+    let output = run_config(tmp.path(), &["set", "size.max_tokens.feat", "many"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = axon(&["health"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Oversized: 1 files"), "{stdout}");
+    assert!(stdout.contains("a.feat.auth.spec.initial.v1.md (error: ~9 tokens, more than the feat maximum of 5)"), "{stdout}");
+}
+
+#[test]
+fn test_search_notes_and_prompts() {
+    let tmp = TempDir::new().unwrap();
+    let notes = tmp.path().join("notes");
+    let prompts = tmp.path().join("prompts");
+    std::fs::create_dir_all(&notes).unwrap();
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::write(notes.join("ideas.md"), "# Ideas\nrotate the auth tokens\naudit auth\n").unwrap();
+    std::fs::write(prompts.join("myapp.feat.auth.spec.initial.v1.md"), "Build auth.\n").unwrap();
+    std::fs::write(prompts.join("myapp.sop.deploy.v1.md"), "Check auth first.\n").unwrap();
+
+    let search = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .arg("search")
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("AXON_NOTES_DIR", &notes)
+            .env("AXON_PROMPTS_DIR", &prompts)
+            .output()
+            .unwrap()
+    };
+
+    let output = search(&["auth"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        [
+            "myapp.feat.auth.spec.initial.v1.md:1: Build auth.",
+            "ideas.md:2: rotate the auth tokens",
+            "ideas.md:3: audit auth",
+            "myapp.sop.deploy.v1.md:1: Check auth first.",
+        ]
+    );
+
+    let output = search(&["auth", "--category", "sop", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["source"], "prompt");
+    assert_eq!(json[0]["hits"][0]["matches"][0]["start"], 6);
+
+    let output = search(&["--regex", "auth t\\w+", "--notes"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "ideas.md:2: rotate the auth tokens\n");
+
+    let output = search(&["missing"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No matches for 'missing'"));
+    assert_eq!(search(&["--regex", "("]).status.code(), Some(2));
+}
+
+#[test]
+fn test_next_daily_note_leaves_todays_tasks_alone() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let notes = home.join("notes");
+    std::fs::create_dir_all(home.join(".config/axon")).unwrap();
+    std::fs::create_dir_all(&notes).unwrap();
+    std::fs::write(home.join(".config/axon/config.toml"), "opener = \"true\"\n").unwrap();
+    std::fs::write(notes.join("axon.toml"), "[carry_over.daily]\n").unwrap();
+    let today = chrono::Local::now().date_naive();
+    let today_note = notes.join(format!("daily.{}.md", today.format("%Y.%m.%d")));
+    std::fs::write(&today_note, "- [ ] open task\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_axon"))
+        .args(["d", "--next"])
+        .env("HOME", home)
+        .env("AXON_NOTES_DIR", &notes)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let tomorrow = today + chrono::Duration::days(1);
+    let next_note = notes.join(format!("daily.{}.md", tomorrow.format("%Y.%m.%d")));
+    assert_eq!(std::fs::read_to_string(next_note).unwrap(), "");
+    assert_eq!(std::fs::read_to_string(&today_note).unwrap(), "- [ ] open task\n");
+}
+
+#[test]
+fn test_refactor_without_git_installed_falls_back_to_mv() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir(tmp.path().join(".git")).unwrap();
+    std::fs::write(tmp.path().join("foo.bar.specs.initial.v1.md"), "").unwrap();
+    let empty_path = TempDir::new().unwrap();
+
+    let refactor = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_axon"))
+            .args([
+                "refactor",
+                "--from",
+                "{repo}.{feature}.{type}.{variant}.v{N}",
+                "--to",
+                "{repo}.{type}.{feature}.{variant}.v{N}",
+                "--yes",
+            ])
+            .args(extra)
+            .current_dir(tmp.path())
+            .env("PATH", empty_path.path())
+            .output()
+            .unwrap()
+    };
+
+    let output = refactor(&["--git"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("git is not installed"));
+
+    let output = refactor(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(tmp.path().join("foo.specs.bar.initial.v1.md").exists());
+    assert!(!tmp.path().join("foo.bar.specs.initial.v1.md").exists());
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+
+use axon::links::{
+    check_links, collect_scope, edit_distance, extract_links, rewrite_references, suggest_name,
+    LinkKind,
+};
+use axon::refactor::RenamePlan;
+use tempfile::TempDir;
+
+#[test]
+fn test_rewrite_bare_markdown_and_wiki_links() {
+    let content = "see myapp.feat.auth.spec.initial.v2.md\n\
+                   [auth](./myapp.feat.auth.spec.initial.v2.md)\n\
+                   [[myapp.feat.auth.spec.initial.v2]] and [[myapp.feat.auth.spec.initial.v2|alias]]\n";
+    let renames = vec![RenamePlan::new(
+        "myapp.feat.auth.spec.initial.v2.md",
+        "myapp.feat.auth.initial.spec.v2.md",
+    )];
+    let (output, changes) = rewrite_references(content, &renames).unwrap();
+    assert_eq!(
+        output,
+        "see myapp.feat.auth.initial.spec.v2.md\n\
+         [auth](./myapp.feat.auth.initial.spec.v2.md)\n\
+         [[myapp.feat.auth.initial.spec.v2]] and [[myapp.feat.auth.initial.spec.v2|alias]]\n"
+    );
+    assert_eq!(changes.len(), 4);
+    assert_eq!(changes[0].line, 1);
+    assert_eq!(changes[1].line, 2);
+    assert_eq!(changes[2].line, 3);
+}
+
+#[test]
+fn test_rewrite_ignores_longer_names() {
+    let content = "a.b.v12.md a.b.v1.x.md xa.b.v1.md a.b.v1.mdx\n";
+    let renames = vec![RenamePlan::new("a.b.v1.md", "b.a.v1.md")];
+    assert!(rewrite_references(content, &renames).is_none());
+}
+
+#[test]
+fn test_rewrite_end_of_sentence() {
+    let content = "Based on a.b.v1. Also a.b.v1.md.";
+    let renames = vec![RenamePlan::new("a.b.v1.md", "b.a.v1.md")];
+    let (output, _) = rewrite_references(content, &renames).unwrap();
+    assert_eq!(output, "Based on b.a.v1. Also b.a.v1.md.");
+}
+
+#[test]
+fn test_rewrite_does_not_chain_renames() {
+    let content = "a.v1.md then b.v1.md";
+    let renames = vec![
+        RenamePlan::new("a.v1.md", "b.v1.md"),
+        RenamePlan::new("b.v1.md", "c.v1.md"),
+    ];
+    let (output, changes) = rewrite_references(content, &renames).unwrap();
+    assert_eq!(output, "b.v1.md then c.v1.md");
+    assert_eq!(changes.len(), 2);
+}
+
+#[test]
+fn test_extract_links_kinds() {
+    let content = "intro [spec](./a.feat.b.spec.initial.v1.md#goals) and [site](https://example.com)\n\
+                   [[daily.2026.03.02|monday]] mentions a.sop.style.v2 here\n\
+                   ```\n\
+                   [[ignored-in-code]]\n\
+                   ```\n\
+                   not-a-prompt.v1 or version.v2.md\n";
+    let links = extract_links(content);
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[0].kind, LinkKind::Markdown);
+    assert_eq!(links[0].target, "./a.feat.b.spec.initial.v1.md");
+    assert_eq!(links[1].kind, LinkKind::Wiki);
+    assert_eq!(links[1].target, "daily.2026.03.02");
+    assert_eq!(links[1].line, 2);
+    assert_eq!(links[2].kind, LinkKind::Bare);
+    assert_eq!(links[2].target, "a.sop.style.v2.md");
+}
+
+#[test]
+fn test_bare_name_inside_link_counted_once() {
+    let links = extract_links("[x](a.sop.style.v2.md) [[a.sop.style.v2]]");
+    assert_eq!(links.len(), 2);
+    assert!(links.iter().all(|link| link.kind != LinkKind::Bare));
+}
+
+#[test]
+fn test_suggest_newest_version() {
+    let known: BTreeSet<String> = [
+        "a.feat.b.spec.initial.v2.md",
+        "a.feat.b.spec.initial.v3.md",
+        "a.feat.c.spec.initial.v1.md",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    assert_eq!(
+        suggest_name("a.feat.b.spec.initial.v1.md", &known).unwrap(),
+        "a.feat.b.spec.initial.v3.md"
+    );
+    assert_eq!(
+        suggest_name("a.feat.c.spec.intial.v1.md", &known).unwrap(),
+        "a.feat.c.spec.initial.v1.md"
+    );
+    assert!(suggest_name("unrelated.md", &known).is_none());
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("same", "same"), 0);
+}
+
+#[test]
+fn test_check_links_reports_broken_with_location() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.sop.style.v2.md"), "").unwrap();
+    std::fs::write(
+        tmp.path().join("notes.md"),
+        "ok [[a.sop.style.v2]]\nbroken a.sop.style.v1\n[gone](missing.md)\n",
+    )
+    .unwrap();
+
+    let (files, known) = collect_scope(&[tmp.path().to_path_buf()]).unwrap();
+    let report = check_links(&files, &known);
+    assert_eq!(report.files, 2);
+    assert_eq!(report.links, 3);
+    assert_eq!(report.broken.len(), 2);
+    assert_eq!(report.broken[0].line, 2);
+    assert_eq!(report.broken[0].suggestion.as_deref(), Some("a.sop.style.v2.md"));
+    assert_eq!(report.broken[1].target, "missing.md");
+    assert!(report.broken[1].file.ends_with("notes.md"));
+}
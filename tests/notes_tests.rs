@@ -1,7 +1,12 @@
 use std::fs;
 
 use axon::config::{glob_match, load_config};
-use axon::notes::create_daily;
+use axon::config::PeriodicConfig;
+use axon::notes::{
+    create_adjacent, create_daily, create_periodic, create_periodic_on, detect_periodic, parse_date, periodic_kinds,
+    PeriodicKind,
+};
+use chrono::NaiveDate;
 use tempfile::TempDir;
 
 #[test]
@@ -141,3 +146,364 @@ fn test_create_daily_no_config() {
     let content = fs::read_to_string(&path).unwrap();
     assert_eq!(content, "");
 }
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn builtin(name: &str) -> PeriodicKind {
+    PeriodicKind::builtin()
+        .into_iter()
+        .find(|kind| kind.name == name)
+        .unwrap()
+}
+
+fn configured(name: &str, format: &str, period: &str) -> PeriodicKind {
+    let cfg = PeriodicConfig {
+        format: format.to_string(),
+        period: period.to_string(),
+        ..PeriodicConfig::default()
+    };
+    PeriodicKind::from_config(name, &cfg).unwrap()
+}
+
+#[test]
+fn test_periodic_filenames_and_steps() {
+    // 2026-03-04 is a Wednesday.
+    let day = date(2026, 3, 4);
+    assert_eq!(builtin("daily").filename(day), "daily.2026.03.04.md");
+    assert_eq!(builtin("weekly").filename(day), "weekly.2026.03.02.md");
+    assert_eq!(builtin("monthly").filename(day), "monthly.2026.03.md");
+
+    assert_eq!(builtin("daily").step(day, -4), Some(date(2026, 2, 28)));
+    assert_eq!(builtin("weekly").step(day, 1), Some(date(2026, 3, 9)));
+    assert_eq!(builtin("monthly").step(day, -3), Some(date(2025, 12, 1)));
+}
+
+#[test]
+fn test_periodic_step_out_of_range() {
+    let day = date(2026, 3, 4);
+    for kind in ["daily", "weekly", "monthly"] {
+        assert_eq!(builtin(kind).step(day, 999_999_999_999), None);
+        assert_eq!(builtin(kind).step(day, i64::MIN), None);
+    }
+    // Still a valid date, but not one a filename can hold.
+    assert_eq!(builtin("daily").step(day, 3_000_000), None);
+    assert_eq!(configured("yearly", "yearly.%Y", "year").step(day, i64::MAX / 6), None);
+    assert_eq!(builtin("daily").step(date(9999, 12, 31), 0), Some(date(9999, 12, 31)));
+}
+
+#[test]
+fn test_periodic_parse_filename() {
+    let kinds = PeriodicKind::builtin();
+    let (kind, start) = detect_periodic(&kinds, "daily.2026.03.02.md").unwrap();
+    assert_eq!((kind.name.as_str(), start), ("daily", date(2026, 3, 2)));
+    let (kind, start) = detect_periodic(&kinds, "monthly.2026.03.md").unwrap();
+    assert_eq!((kind.name.as_str(), start), ("monthly", date(2026, 3, 1)));
+    assert!(detect_periodic(&kinds, "daily.2026.13.02.md").is_none());
+    // A weekly note must be named after the first day of its week.
+    assert!(detect_periodic(&kinds, "weekly.2026.03.04.md").is_none());
+    assert!(detect_periodic(&kinds, "scratch.1700000000.md").is_none());
+
+    assert_eq!(parse_date("2026-03-02").unwrap(), date(2026, 3, 2));
+    assert_eq!(parse_date("2026.03.02").unwrap(), date(2026, 3, 2));
+    assert!(parse_date("yesterday").is_err());
+}
+
+#[test]
+fn test_configured_periodic_kinds() {
+    let quarterly = configured("quarterly", "q.%Y.Q%q", "quarter");
+    assert_eq!(quarterly.filename(date(2026, 8, 20)), "q.2026.Q3.md");
+    assert_eq!(quarterly.step(date(2026, 1, 5), -1), Some(date(2025, 10, 1)));
+    assert_eq!(quarterly.parse_filename("q.2026.Q3.md"), Some(date(2026, 7, 1)));
+
+    let yearly = configured("yearly", "yearly.%Y", "year");
+    assert_eq!(yearly.filename(date(2026, 8, 20)), "yearly.2026.md");
+    assert_eq!(yearly.parse_filename("yearly.2027.md"), Some(date(2027, 1, 1)));
+
+    let iso = configured("weekly", "weekly.%G-W%V", "week");
+    assert_eq!(iso.filename(date(2026, 10, 18)), "weekly.2026-W42.md");
+    assert_eq!(iso.parse_filename("weekly.2026-W42.md"), Some(date(2026, 10, 12)));
+
+    let sunday = PeriodicKind::from_config(
+        "weekly",
+        &PeriodicConfig {
+            format: "weekly.%Y.%m.%d".to_string(),
+            period: "week".to_string(),
+            week_start: Some("sunday".to_string()),
+            key: Some("w".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(sunday.filename(date(2026, 3, 4)), "weekly.2026.03.01.md");
+
+    let bad = PeriodicConfig {
+        format: "x.%Y".to_string(),
+        period: "fortnight".to_string(),
+        ..PeriodicConfig::default()
+    };
+    assert!(PeriodicKind::from_config("x", &bad).is_err());
+
+    let format = |format: &str, period: &str| {
+        let cfg = PeriodicConfig {
+            format: format.to_string(),
+            period: period.to_string(),
+            ..PeriodicConfig::default()
+        };
+        PeriodicKind::from_config("x", &cfg)
+    };
+    // Time fields can't be rendered from a date.
+    let err = format("x.%Y.%H", "year").unwrap_err();
+    assert!(err.contains("time of day"), "{err}");
+    assert!(format("x.%Y.%m.%d.%p", "day").is_err());
+    // Every day of a year would share one filename.
+    let err = format("x.%Y", "day").unwrap_err();
+    assert!(err.contains("uniquely"), "{err}");
+    assert!(format("x.%m.%d", "day").is_err());
+    assert!(format("x.%Y.%m", "week").is_err());
+    assert!(format("x.%Y.%m.%d", "month").is_ok());
+}
+
+#[test]
+fn test_periodic_kinds_from_axon_toml() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        r#"
+[periodic.weekly]
+format = "weekly.%G-W%V"
+period = "week"
+key = "w"
+
+[periodic.yearly]
+format = "yearly.%Y"
+period = "year"
+key = "y"
+"#,
+    )
+    .unwrap();
+
+    let kinds = periodic_kinds(tmp.path());
+    let names: Vec<&str> = kinds.iter().map(|kind| kind.name.as_str()).collect();
+    assert_eq!(names, vec!["daily", "weekly", "monthly", "yearly"]);
+    assert_eq!(kinds[1].format, "weekly.%G-W%V");
+    assert_eq!(kinds[3].key, Some('y'));
+}
+
+#[test]
+fn test_incomplete_periodic_table_leaves_the_rest_of_the_config() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        r#"
+[schemas]
+"daily.*.md" = "schema.daily.md"
+
+[periodic.yearly]
+format = "yearly.%Y"
+
+[periodic.quarterly]
+format = "q.%Y.Q%q"
+period = "quarter"
+
+[carry_over.daily]
+heading = 4
+
+[carry_over.weekly]
+from = "daily"
+"#,
+    )
+    .unwrap();
+
+    let cfg = load_config(tmp.path());
+    assert_eq!(cfg.schema_file("daily.2026.03.02.md"), Some("schema.daily.md"));
+    assert_eq!(cfg.periodic.keys().collect::<Vec<_>>(), vec!["quarterly"]);
+    assert_eq!(cfg.carry_over.keys().collect::<Vec<_>>(), vec!["weekly"]);
+    let names: Vec<String> = periodic_kinds(tmp.path()).into_iter().map(|kind| kind.name).collect();
+    assert_eq!(names, vec!["daily", "weekly", "monthly", "quarterly"]);
+}
+
+#[test]
+fn test_create_adjacent_periodic_note() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        r#"
+[schemas]
+"weekly.*" = "schema.weekly"
+"#,
+    )
+    .unwrap();
+    fs::write(tmp.path().join("schema.weekly"), "# Week\n").unwrap();
+
+    let path = create_periodic(tmp.path(), &builtin("weekly"), date(2026, 3, 4)).unwrap();
+    assert!(path.ends_with("weekly.2026.03.02.md"));
+
+    let prev = create_adjacent(tmp.path(), Some("weekly.2026.03.02.md"), -1).unwrap();
+    assert!(prev.ends_with("weekly.2026.02.23.md"));
+    assert_eq!(fs::read_to_string(&prev).unwrap(), "# Week\n");
+
+    let next = create_adjacent(tmp.path(), Some("daily.2026.02.28.md"), 1).unwrap();
+    assert!(next.ends_with("daily.2026.03.01.md"));
+}
+
+#[test]
+fn test_create_daily_carries_unfinished_tasks() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        r#"
+[schemas]
+"daily.*" = "schema.daily"
+
+[carry_over.daily]
+heading = "Tasks"
+section = "Tasks"
+"#,
+    )
+    .unwrap();
+    fs::write(tmp.path().join("schema.daily"), "## Tasks\n- [ ]\n\n## Notes\n").unwrap();
+    fs::write(
+        tmp.path().join("daily.2026.03.01.md"),
+        "## Tasks\n- [ ] older task\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("daily.2026.03.02.md"),
+        "## Tasks\n- [ ] write spec\n- [x] done\n\n## Notes\n- [ ] idea\n",
+    )
+    .unwrap();
+
+    let kind = builtin("daily");
+    let today = date(2026, 3, 4);
+    let path = create_periodic_on(tmp.path(), &kind, today, today).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "## Tasks\n- [ ] write spec\n\n## Notes\n"
+    );
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.03.02.md")).unwrap(),
+        "## Tasks\n- [>] write spec\n- [x] done\n\n## Notes\n- [ ] idea\n"
+    );
+    // Only the most recent earlier note is carried from.
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.03.01.md")).unwrap(),
+        "## Tasks\n- [ ] older task\n"
+    );
+
+    // An existing note is never filled again.
+    fs::write(&path, "mine\n").unwrap();
+    create_periodic_on(tmp.path(), &kind, today, today).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "mine\n");
+}
+
+#[test]
+fn test_only_the_current_note_carries_tasks() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("axon.toml"), "[carry_over.daily]\n").unwrap();
+    fs::write(tmp.path().join("daily.2026.03.04.md"), "- [ ] today's task\n").unwrap();
+    let kind = builtin("daily");
+    let today = date(2026, 3, 4);
+
+    // Tomorrow's note, and a past one, are created empty.
+    for day in [date(2026, 3, 5), date(2026, 3, 9), date(2026, 3, 3)] {
+        let path = create_periodic_on(tmp.path(), &kind, day, today).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    }
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.03.04.md")).unwrap(),
+        "- [ ] today's task\n"
+    );
+
+    // Once tomorrow comes, its existing note is left as it is.
+    create_periodic_on(tmp.path(), &kind, date(2026, 3, 5), date(2026, 3, 5)).unwrap();
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.03.04.md")).unwrap(),
+        "- [ ] today's task\n"
+    );
+}
+
+#[test]
+fn test_daily_carry_and_weekly_rollup_both_get_tasks() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        "[carry_over.daily]\nsection = \"Tasks\"\n\n[carry_over.weekly]\nfrom = \"daily\"\nsection = \"Rollup\"\n",
+    )
+    .unwrap();
+    // Friday of the week of 2026-02-23; Monday 03-02 starts the next week.
+    fs::write(tmp.path().join("daily.2026.02.27.md"), "- [ ] a\n- [x] b\n").unwrap();
+    let monday = date(2026, 3, 2);
+
+    let daily = create_periodic_on(tmp.path(), &builtin("daily"), monday, monday).unwrap();
+    let weekly = create_periodic_on(tmp.path(), &builtin("weekly"), monday, monday).unwrap();
+    assert_eq!(fs::read_to_string(&daily).unwrap(), "## Tasks\n\n- [ ] a\n");
+    assert_eq!(fs::read_to_string(&weekly).unwrap(), "## Rollup\n\n- [ ] a\n");
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.02.27.md")).unwrap(),
+        "- [>] a\n- [x] b\n"
+    );
+}
+
+#[test]
+fn test_create_weekly_rolls_up_dailies() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        "[carry_over.weekly]\nfrom = \"daily\"\nsection = \"Rollup\"\n",
+    )
+    .unwrap();
+    // The week of 2026-02-23; 02-20 belongs to the week before.
+    fs::write(tmp.path().join("daily.2026.02.20.md"), "- [ ] too old\n").unwrap();
+    fs::write(tmp.path().join("daily.2026.02.24.md"), "- [ ] a\n- [ ] b\n").unwrap();
+    fs::write(tmp.path().join("daily.2026.02.27.md"), "- [ ] b\n- [ ] c\n").unwrap();
+
+    let today = date(2026, 3, 4);
+    let path = create_periodic_on(tmp.path(), &builtin("weekly"), today, today).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "## Rollup\n\n- [ ] a\n- [ ] b\n- [ ] c\n"
+    );
+    // A rollup copies: the dailies' own carry-over still moves the tasks.
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.02.27.md")).unwrap(),
+        "- [ ] b\n- [ ] c\n"
+    );
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("daily.2026.02.20.md")).unwrap(),
+        "- [ ] too old\n"
+    );
+}
+
+#[test]
+fn test_create_periodic_renders_schema_variables() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("axon.toml"),
+        "[schemas]\n\"daily.*\" = \"schema.daily\"\n\"weekly.*\" = \"schema.weekly\"\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("schema.daily"),
+        "# {{date:%A, %B %d}} ({{date}}, week {{week_number}})\n\
+         {{yesterday}} / {{tomorrow}}\n\
+         [[{{prev_note}}]] [[{{next_note}}]] {{filename}}\n\
+         {{> schema.footer}}\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("schema.footer"), "{{#if kind}}kind: {{kind}}{{/if}}\n").unwrap();
+    fs::write(tmp.path().join("schema.weekly"), "{{unknown}}\n").unwrap();
+
+    let path = create_periodic(tmp.path(), &builtin("daily"), date(2026, 3, 2)).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Monday, March 02 (2026.03.02, week 10)\n\
+         2026-03-01 / 2026-03-03\n\
+         [[daily.2026.03.01]] [[daily.2026.03.03]] daily.2026.03.02.md\n\
+         kind: daily\n"
+    );
+
+    // Unknown variables are left as written.
+    let path = create_periodic(tmp.path(), &builtin("weekly"), date(2026, 3, 2)).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{{unknown}}\n");
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use axon::global_config::{GlobalConfig, Origin};
+use axon::opener::{resolve, split_words, Opener};
+
+#[test]
+fn test_split_words() {
+    assert_eq!(split_words("code --wait").unwrap(), vec!["code", "--wait"]);
+    assert_eq!(
+        split_words(r#"  emacsclient -a "" '+{line}' my\ file "a \"b\"" "#).unwrap(),
+        vec!["emacsclient", "-a", "", "+{line}", "my file", "a \"b\""]
+    );
+    assert!(split_words("vim 'oops").is_err());
+}
+
+#[test]
+fn test_opener_command_substitutes_placeholders() {
+    let path = Path::new("/notes/daily.2026.03.02.md");
+
+    let opener = Opener::parse("nvim +{line} {path}", Origin::BuiltIn).unwrap();
+    assert_eq!(
+        opener.command(path, Some(12)),
+        (
+            "nvim".to_string(),
+            vec!["+12".to_string(), path.display().to_string()]
+        )
+    );
+
+    let opener = Opener::parse("code --wait --goto {path}:{line}", Origin::BuiltIn).unwrap();
+    let (_, args) = opener.command(path, None);
+    assert_eq!(args, vec!["--wait", "--goto", "/notes/daily.2026.03.02.md:1"]);
+
+    // Without {path}, the file is appended.
+    let opener = Opener::parse("hx", Origin::BuiltIn).unwrap();
+    assert_eq!(opener.command(path, None).1, vec![path.display().to_string()]);
+
+    assert!(Opener::parse("  ", Origin::BuiltIn).is_err());
+}
+
+#[test]
+fn test_resolve_prefers_configured_opener() {
+    let cfg = GlobalConfig {
+        opener: Some("hx {path}".to_string()),
+        ..GlobalConfig::default()
+    };
+    let opener = resolve(&cfg).unwrap();
+    assert_eq!(opener.template(), "hx {path}");
+    assert!(matches!(opener.origin, Origin::File(_)));
+}
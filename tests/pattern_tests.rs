@@ -36,3 +36,13 @@ fn test_parse_filename() {
         ParsedFilename::Sop(_) => panic!("expected feat filename"),
     }
 }
+
+#[test]
+fn test_identity_ignores_version() {
+    let v1 = parse_filename("forkcast.feat.objections.specs.initial.v1.md").unwrap();
+    let v3 = parse_filename("forkcast.feat.objections.specs.initial.v3.md").unwrap();
+    assert_eq!(v1.identity(), "forkcast.feat.objections.specs.initial");
+    assert_eq!(v1.identity(), v3.identity());
+    let sop = parse_filename("forkcast.sop.coding-style.v2.md").unwrap();
+    assert_eq!(sop.identity(), "forkcast.sop.coding-style");
+}
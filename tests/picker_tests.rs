@@ -0,0 +1,66 @@
+use axon::fuzzy::{find, rank};
+use axon::picker::{Backend, PickerState};
+
+fn items() -> Vec<String> {
+    ["daily.2026.03.02.md", "weekly.2026.03.02.md", "ideas.md", "scratch.1700000000.md"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[test]
+fn test_fuzzy_find_and_rank() {
+    assert_eq!(find("dly", "daily.md"), find("DLY", "daily.md"));
+    assert_eq!(find("dly", "daily.md").unwrap().1, [0, 3, 4]);
+    assert_eq!(find("id md", "ideas.md").unwrap().1, [0, 1, 6, 7]);
+    // Terms match independently of each other's order.
+    assert_eq!(find("md id", "ideas.md").unwrap().1, [0, 1, 6, 7]);
+    assert!(find("mi", "ideas.md").is_none());
+
+    let items = items();
+    let indices = |query| rank(query, &items).into_iter().map(|found| found.index).collect::<Vec<_>>();
+    assert_eq!(indices(""), [0, 1, 2, 3]);
+    // A match at the start of a segment beats a scattered one.
+    assert_eq!(indices("week"), [1]);
+    assert_eq!(indices("2026"), [0, 1]);
+    assert_eq!(indices("dm"), [0, 2]);
+}
+
+#[test]
+fn test_picker_filters_and_picks() {
+    let mut state = PickerState::new(items());
+    assert_eq!(state.choice().as_deref(), Some("daily.2026.03.02.md"));
+    state.move_by(10);
+    assert_eq!(state.choice().as_deref(), Some("scratch.1700000000.md"));
+
+    for c in "ideas".chars() {
+        state.push(c);
+    }
+    assert_eq!(state.cursor(), 0);
+    assert_eq!(state.matches().len(), 1);
+    assert_eq!(state.choice().as_deref(), Some("ideas.md"));
+
+    // With no match, the typed name is picked, as with fzf --print-query.
+    for c in "-2".chars() {
+        state.push(c);
+    }
+    assert!(state.matches().is_empty());
+    assert_eq!(state.choice().as_deref(), Some("ideas-2"));
+    state.pop();
+    state.pop();
+    assert_eq!(state.query(), "ideas");
+    assert_eq!(state.matches().len(), 1);
+
+    let empty = PickerState::new(Vec::new());
+    assert_eq!(empty.choice(), None);
+}
+
+#[test]
+fn test_picker_backend_parse() {
+    assert_eq!(Backend::parse("fzf"), Ok(Backend::Fzf));
+    assert_eq!(Backend::default(), Backend::Builtin);
+    assert_eq!(
+        Backend::parse("skim").unwrap_err(),
+        "unknown picker 'skim' (expected builtin or fzf)"
+    );
+}
@@ -0,0 +1,77 @@
+use axon::preview::{build, details, format_size, render_markdown, wrap, Span, Style};
+use tempfile::TempDir;
+
+fn styles(line: &[Span]) -> Vec<(&str, Style)> {
+    line.iter().map(|span| (span.text.as_str(), span.style)).collect()
+}
+
+#[test]
+fn test_render_markdown_styles() {
+    let lines = render_markdown(
+        "# Title\n  - [ ] write `tests`\n- [x] ship\n1. first\n> quoted\n```sh\n# not a heading\n```\n#hashtag\n",
+    );
+    assert_eq!(styles(&lines[0]), [("# Title", Style::Heading(1))]);
+    assert_eq!(
+        styles(&lines[1]),
+        [
+            ("  ", Style::Plain),
+            ("-", Style::ListMarker),
+            (" ", Style::Plain),
+            ("[ ] ", Style::TaskOpen),
+            ("write ", Style::Plain),
+            ("`tests`", Style::Code),
+        ]
+    );
+    assert_eq!(
+        styles(&lines[2]),
+        [
+            ("-", Style::ListMarker),
+            (" ", Style::Plain),
+            ("[x] ", Style::TaskDone),
+            ("ship", Style::Done),
+        ]
+    );
+    assert_eq!(styles(&lines[3])[0], ("1.", Style::ListMarker));
+    assert_eq!(styles(&lines[4]), [("> quoted", Style::Quote)]);
+    assert_eq!(styles(&lines[5]), [("```sh", Style::Fence)]);
+    assert_eq!(styles(&lines[6]), [("# not a heading", Style::Code)]);
+    assert_eq!(styles(&lines[8]), [("#hashtag", Style::Plain)]);
+}
+
+#[test]
+fn test_wrap_keeps_styles() {
+    let line = render_markdown("- item `code`").remove(0);
+    let wrapped = wrap(&line, 5);
+    assert_eq!(wrapped.len(), 3);
+    assert_eq!(styles(&wrapped[0]), [("-", Style::ListMarker), (" ", Style::Plain), ("ite", Style::Plain)]);
+    assert_eq!(styles(&wrapped[1]), [("m ", Style::Plain), ("`co", Style::Code)]);
+    assert_eq!(styles(&wrapped[2]), [("de`", Style::Code)]);
+    assert_eq!(wrap(&Vec::new(), 5), [Vec::new()]);
+}
+
+#[test]
+fn test_preview_details() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("myapp.feat.auth.spec.initial.v2.md");
+    std::fs::write(&path, "# Auth\n").unwrap();
+
+    let labels: Vec<&str> = details(&path).into_iter().map(|(label, _)| label).collect();
+    assert_eq!(
+        labels,
+        ["repo", "category", "feature", "type", "variant", "version", "size", "modified"]
+    );
+    assert_eq!(details(&path)[5].1, "2");
+    assert_eq!(details(&path)[6].1, "7 B");
+
+    let note = tmp.path().join("ideas.md");
+    std::fs::write(&note, "").unwrap();
+    assert_eq!(details(&note)[0], ("size", "0 B".to_string()));
+
+    // Details share lines when they fit, above a rule and the content.
+    let lines = build(&path, 40);
+    assert_eq!(lines[0][0].text, "repo: ");
+    assert_eq!(lines.last().unwrap()[0].style, Style::Heading(1));
+
+    assert_eq!(format_size(1536), "1.5 KB");
+    assert_eq!(format_size(3 * 1_048_576), "3.0 MB");
+}
@@ -0,0 +1,101 @@
+use axon::fuzzy::score;
+use axon::prompts::{compose, compose_source, resolve_prompt};
+
+fn files() -> Vec<String> {
+    [
+        "myapp.feat.auth.spec.initial.v1.md",
+        "myapp.feat.auth.spec.initial.v2.md",
+        "myapp.feat.billing.spec.initial.v1.md",
+        "myapp.sop.deploy-checklist.v3.md",
+        "scratch.md",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
+}
+
+#[test]
+fn test_resolve_prompt_exact_and_latest() {
+    let files = files();
+    assert_eq!(
+        resolve_prompt("myapp.feat.auth.spec.initial.v1", &files).unwrap(),
+        "myapp.feat.auth.spec.initial.v1.md"
+    );
+    assert_eq!(resolve_prompt("scratch.md", &files).unwrap(), "scratch.md");
+    assert_eq!(
+        resolve_prompt("myapp.feat.auth.spec.initial", &files).unwrap(),
+        "myapp.feat.auth.spec.initial.v2.md"
+    );
+}
+
+#[test]
+fn test_resolve_prompt_fuzzy() {
+    let files = files();
+    // Only the latest version of a prompt is a candidate.
+    assert_eq!(resolve_prompt("auth", &files).unwrap(), "myapp.feat.auth.spec.initial.v2.md");
+    assert_eq!(resolve_prompt("deploy", &files).unwrap(), "myapp.sop.deploy-checklist.v3.md");
+    assert_eq!(resolve_prompt("bill spec", &files).unwrap(), "myapp.feat.billing.spec.initial.v1.md");
+
+    let err = resolve_prompt("spec", &files).unwrap_err();
+    assert!(err.contains("matches several prompts"), "{err}");
+    assert!(err.contains("myapp.feat.auth.spec.initial.v2.md"));
+    assert!(!err.contains(".v1.md, myapp.feat.auth"));
+
+    assert_eq!(resolve_prompt("zzz", &files).unwrap_err(), "no prompt matches 'zzz'");
+}
+
+#[test]
+fn test_fuzzy_score() {
+    assert!(score("dpl", "myapp.sop.deploy.v1.md").is_some());
+    assert!(score("xyz", "myapp.sop.deploy.v1.md").is_none());
+    assert!(score("auth zzz", "myapp.feat.auth.spec.initial.v1.md").is_none());
+    // Contiguous, segment-start matches beat scattered ones.
+    assert!(score("auth", "myapp.feat.auth.md") > score("auth", "a.unit.test.h.md"));
+    assert!(score("AUTH", "myapp.feat.auth.md") == score("auth", "myapp.feat.auth.md"));
+}
+
+#[test]
+fn test_compose_includes_latest_version() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path();
+    std::fs::write(dir.join("myapp.sop.coding-style.v1.md"), "old style\n").unwrap();
+    std::fs::write(dir.join("myapp.sop.coding-style.v2.md"), "new style\n").unwrap();
+    std::fs::write(dir.join("myapp.sop.review.v1.md"), "review\n{{> myapp.sop.coding-style}}\n").unwrap();
+
+    let source = "# Task\n<!-- axon:include myapp.sop.review.v1.md -->\n{{> myapp.sop.coding-style.v1}}\n{{ticket}}\n";
+    assert_eq!(
+        compose_source(source, dir).unwrap(),
+        "# Task\nreview\nnew style\nold style\n{{ticket}}\n"
+    );
+
+    let path = dir.join("myapp.sop.review.v1.md");
+    assert_eq!(compose(&path).unwrap().unwrap(), "review\nnew style\n");
+    assert_eq!(compose(&dir.join("myapp.sop.coding-style.v1.md")).unwrap(), None);
+
+    let err = compose_source("<!-- axon:include myapp.sop.missing -->\n", dir).unwrap_err();
+    assert!(err.starts_with("cannot include 'myapp.sop.missing': no such prompt"), "{err}");
+}
+
+#[test]
+fn test_compose_leaves_everything_but_includes_as_written() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path();
+    std::fs::write(dir.join("myapp.sop.style.v1.md"), "style {{ticket}}\n").unwrap();
+    let source = "{{> myapp.sop.style}}\n```go\n{{ if .Ok }}{{ .Field }}{{ end }}\n```\n{{#if notes}}\n{{notes}}\n{{/if}}\n{{ x|y }} {{\n";
+    assert_eq!(
+        compose_source(source, dir).unwrap(),
+        "style {{ticket}}\n```go\n{{ if .Ok }}{{ .Field }}{{ end }}\n```\n{{#if notes}}\n{{notes}}\n{{/if}}\n{{ x|y }} {{\n"
+    );
+}
+
+#[test]
+fn test_compose_detects_include_cycles() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path();
+    std::fs::write(dir.join("a.sop.one.v1.md"), "{{> a.sop.two}}\n").unwrap();
+    std::fs::write(dir.join("a.sop.two.v1.md"), "<!-- axon:include a.sop.one -->\n").unwrap();
+    assert_eq!(
+        compose(&dir.join("a.sop.one.v1.md")).unwrap_err(),
+        "include cycle: a.sop.one.v1.md -> a.sop.two.v1.md -> a.sop.one.v1.md"
+    );
+}
@@ -1,8 +1,10 @@
+use axon::fs_utils::{common_ancestor, list_markdown_files, rename_via_temp};
 use axon::refactor::{
     check_existing_target_paths, check_existing_targets, check_for_duplicate_targets,
-    check_for_duplicates, match_files, parse_refactor_pattern, refactor_filename,
-    validate_placeholder_match, RenamePlan,
+    check_existing_target_paths_with, check_for_duplicates, check_plan_paths, check_plan_sources, is_case_only_rename, match_files, parse_refactor_pattern, read_plan,
+    refactor_filename, validate_placeholder_match, write_plan, RenamePlan,
 };
+use std::path::PathBuf;
 use tempfile::TempDir;
 
 #[test]
@@ -132,14 +134,8 @@ fn test_target_exists() {
 #[test]
 fn test_duplicate_targets_in_rename_plan() {
     let renames = vec![
-        RenamePlan {
-            from: "a.b.c.v1.md".to_string(),
-            to: "a.c.b.v1.md".to_string(),
-        },
-        RenamePlan {
-            from: "a.b.d.v1.md".to_string(),
-            to: "a.c.b.v1.md".to_string(),
-        },
+        RenamePlan::new("a.b.c.v1.md", "a.c.b.v1.md"),
+        RenamePlan::new("a.b.d.v1.md", "a.c.b.v1.md"),
     ];
     let result = check_for_duplicate_targets(&renames);
     assert!(result.is_err());
@@ -153,11 +149,117 @@ fn test_existing_target_path_conflict() {
     let tmp = TempDir::new().unwrap();
     let target = tmp.path().join("exists.md");
     std::fs::write(&target, "data").unwrap();
-    let renames = vec![RenamePlan {
-        from: tmp.path().join("source.md").to_string_lossy().to_string(),
-        to: target.to_string_lossy().to_string(),
-    }];
+    let renames = vec![RenamePlan::new(
+        tmp.path().join("source.md").to_string_lossy(),
+        target.to_string_lossy(),
+    )];
     let result = check_existing_target_paths(&renames);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("already exists"));
 }
+
+#[test]
+fn test_plan_roundtrip_formats() {
+    let tmp = TempDir::new().unwrap();
+    let renames = vec![
+        RenamePlan::new("a.b.v1.md", "b.a.v1.md"),
+        RenamePlan::new("odd,name.md", "quoted \"name\".md"),
+    ];
+    for file in ["plan.json", "plan.csv", "plan.tsv"] {
+        let path = tmp.path().join(file);
+        write_plan(&path, &renames).unwrap();
+        let read = read_plan(&path).unwrap();
+        assert_eq!(read.len(), 2, "{file}");
+        assert_eq!(read[1].from, "odd,name.md", "{file}");
+        assert_eq!(read[1].to, "quoted \"name\".md", "{file}");
+    }
+    let csv = std::fs::read_to_string(tmp.path().join("plan.csv")).unwrap();
+    assert!(csv.starts_with("from,to\na.b.v1.md,b.a.v1.md\n"));
+}
+
+#[test]
+fn test_read_plan_bare_json_array_and_bad_csv() {
+    let tmp = TempDir::new().unwrap();
+    let path = tmp.path().join("plan.json");
+    std::fs::write(&path, r#"[{"from": "a.md", "to": "b.md"}]"#).unwrap();
+    assert_eq!(read_plan(&path).unwrap()[0].to, "b.md");
+
+    let path = tmp.path().join("plan.csv");
+    std::fs::write(&path, "a.md,b.md,c.md\n").unwrap();
+    assert!(read_plan(&path).unwrap_err().contains("expected 2 columns"));
+}
+
+#[test]
+fn test_plan_missing_source() {
+    let tmp = TempDir::new().unwrap();
+    let renames = vec![RenamePlan::new("missing.md", "other.md")];
+    let result = check_plan_sources(tmp.path(), &renames);
+    assert!(result.unwrap_err().contains("does not exist"));
+
+    std::fs::write(tmp.path().join("missing.md"), "").unwrap();
+    assert!(check_plan_sources(tmp.path(), &renames).is_ok());
+}
+
+#[test]
+fn test_check_plan_paths_rejects_paths_outside_the_root() {
+    let inside = [
+        RenamePlan::new("a.md", "notes/b.md"),
+        RenamePlan::new("./c.md", "d.md"),
+    ];
+    assert!(check_plan_paths(&inside).is_ok());
+
+    for renames in [
+        [RenamePlan::new("a.md", "../x.md")],
+        [RenamePlan::new("a.md", "notes/../../x.md")],
+        [RenamePlan::new("/etc/a.md", "a.md")],
+        [RenamePlan::new("a.md", "/tmp/x.md")],
+        [RenamePlan::new("a.md", "")],
+    ] {
+        let err = check_plan_paths(&renames).unwrap_err();
+        assert!(err.contains("stay inside the operation root"), "{err}");
+    }
+}
+
+#[test]
+fn test_common_ancestor() {
+    let paths = vec![
+        PathBuf::from("/home/me/prompts"),
+        PathBuf::from("/home/me/notes/daily"),
+    ];
+    assert_eq!(common_ancestor(&paths), Some(PathBuf::from("/home/me")));
+    assert_eq!(
+        common_ancestor(&paths[..1]),
+        Some(PathBuf::from("/home/me/prompts"))
+    );
+    assert_eq!(common_ancestor(&[]), None);
+}
+
+#[test]
+fn test_case_only_rename_on_case_folding_filesystem() {
+    let renames = vec![RenamePlan::new("myapp.Auth.spec.v1.md", "myapp.auth.spec.v1.md")];
+    assert!(is_case_only_rename(&renames[0].from, &renames[0].to));
+    assert!(!is_case_only_rename("a.md", "b.md"));
+
+    // Simulated case-insensitive filesystem holding only the source.
+    let folded = |name: &str| name.to_lowercase() == "myapp.auth.spec.v1.md";
+    assert!(check_existing_target_paths_with(&renames, folded, |_| true).is_ok());
+
+    // Case-sensitive filesystem where both spellings exist.
+    let both = |name: &str| name == "myapp.auth.spec.v1.md" || name == "myapp.Auth.spec.v1.md";
+    assert!(check_existing_target_paths_with(&renames, both, |_| false).is_err());
+
+    // Folding never hides a conflict with a different file.
+    let renames = vec![RenamePlan::new("myapp.auth.spec.v1.md", "myapp.billing.spec.v1.md")];
+    assert!(check_existing_target_paths_with(&renames, |_| true, |_| true).is_err());
+}
+
+#[test]
+fn test_rename_via_temp() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("Auth.md"), "x").unwrap();
+    rename_via_temp(&tmp.path().join("Auth.md"), &tmp.path().join("auth.md")).unwrap();
+
+    let files = list_markdown_files(tmp.path()).unwrap();
+    assert_eq!(files, vec![tmp.path().join("auth.md")]);
+    assert_eq!(std::fs::read_to_string(&files[0]).unwrap(), "x");
+}
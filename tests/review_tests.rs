@@ -0,0 +1,96 @@
+use axon::refactor::RenamePlan;
+use axon::review::ReviewState;
+
+fn state() -> ReviewState {
+    ReviewState::new(vec![
+        RenamePlan::new("a.b.v1.md", "b.a.v1.md"),
+        RenamePlan::new("c.d.v1.md", "d.c.v1.md"),
+        RenamePlan::new("e.f.v1.md", "f.e.v1.md"),
+    ])
+}
+
+#[test]
+fn test_review_deselect_and_edit() {
+    let mut state = state();
+    state.move_by(1);
+    state.toggle();
+    state.move_by(1);
+    state.set_target("custom.v1.md");
+
+    let selected = state.selected();
+    assert_eq!(selected.len(), 2);
+    assert_eq!(selected[0].from, "a.b.v1.md");
+    assert_eq!(selected[1].to, "custom.v1.md");
+}
+
+#[test]
+fn test_review_cursor_clamps() {
+    let mut state = state();
+    state.move_by(-5);
+    assert_eq!(state.cursor, 0);
+    state.move_by(10);
+    assert_eq!(state.cursor, 2);
+}
+
+#[test]
+fn test_review_toggle_all() {
+    let mut state = state();
+    state.toggle_all();
+    assert!(state.selected().is_empty());
+    state.toggle();
+    state.toggle_all();
+    assert_eq!(state.selected().len(), 3);
+}
+
+#[test]
+fn test_review_conflicts() {
+    let mut state = state();
+    assert!(state.conflicts(false, |_| false, |_| false).is_empty());
+
+    // Duplicate targets among selected entries.
+    state.move_by(1);
+    state.set_target("b.a.v1.md");
+    let conflicts = state.conflicts(false, |_| false, |_| false);
+    assert_eq!(conflicts.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+
+    // Deselecting one side resolves the duplicate.
+    state.toggle();
+    assert!(state.conflicts(false, |_| false, |_| false).is_empty());
+
+    // Existing targets conflict unless forced.
+    let conflicts = state.conflicts(false, |name| name == "f.e.v1.md", |_| false);
+    assert_eq!(conflicts.into_iter().collect::<Vec<_>>(), vec![2]);
+    assert!(state.conflicts(true, |name| name == "f.e.v1.md", |_| false).is_empty());
+}
+
+#[test]
+fn test_review_case_only_rename_is_not_a_conflict_when_folding() {
+    let state = ReviewState::new(vec![RenamePlan::new("a.Auth.v1.md", "a.auth.v1.md")]);
+    let exists = |name: &str| name.eq_ignore_ascii_case("a.auth.v1.md");
+    assert!(state.conflicts(false, exists, |_| true).is_empty());
+    assert_eq!(state.conflicts(false, exists, |_| false).len(), 1);
+}
+
+#[test]
+fn test_review_edit_target_in_a_multi_dir_scope() {
+    let mut state = ReviewState::new(vec![
+        RenamePlan::new("notes/a.b.v1.md", "notes/b.a.v1.md"),
+        RenamePlan::new("prompts/a.b.v1.md", "prompts/b.a.v1.md"),
+        RenamePlan::new("top.a.v1.md", "a.top.v1.md"),
+    ]);
+    assert_eq!(state.target_dir(), "notes/");
+    state.set_target_name("custom.v1.md").unwrap();
+
+    state.move_by(1);
+    assert!(state.set_target_name("../escape.v1.md").is_err());
+    assert!(state.set_target_name("notes/moved.v1.md").is_err());
+    assert!(state.set_target_name("  ").is_err());
+    state.set_target_name(" renamed.v1.md ").unwrap();
+
+    state.move_by(1);
+    assert_eq!(state.target_dir(), "");
+    state.set_target_name("root.v1.md").unwrap();
+
+    let targets: Vec<String> = state.selected().into_iter().map(|plan| plan.to).collect();
+    assert_eq!(targets, vec!["notes/custom.v1.md", "prompts/renamed.v1.md", "root.v1.md"]);
+}
@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use axon::global_config::{GlobalConfig, RunnerConfig};
+use axon::runner::{names, resolve, Invocation, Runner};
+
+fn invocation(prompt: Option<&str>) -> Invocation {
+    Invocation {
+        prompt_path: prompt.map(|name| PathBuf::from("/prompts").join(name)),
+        prompts_md: PathBuf::from("/prompts/prompts.md"),
+        cwd: None,
+        composed: None,
+    }
+}
+
+#[test]
+fn test_runner_command_substitutes_placeholders() {
+    let runner = Runner::builtin();
+    let (program, args) = runner.command(&invocation(Some("review.md"))).unwrap();
+    assert_eq!(program, "claude");
+    assert_eq!(
+        args,
+        vec!["read /prompts/prompts.md and /prompts/review.md and ask for next instruction"]
+    );
+
+    let cfg = RunnerConfig {
+        command: Some("aider --yes".to_string()),
+        args: Some("--read {prompts_md} --read {prompt_path} --message '{instruction}'".to_string()),
+        ..RunnerConfig::default()
+    };
+    let runner = Runner::from_config("aider", &cfg).unwrap();
+    let (program, args) = runner.command(&invocation(Some("review.md"))).unwrap();
+    assert_eq!(program, "aider");
+    assert_eq!(args[..5], ["--yes", "--read", "/prompts/prompts.md", "--read", "/prompts/review.md"]);
+
+    // Starting a session drops the empty {prompt_path} word.
+    let (_, args) = runner.command(&invocation(None)).unwrap();
+    assert_eq!(
+        args,
+        vec![
+            "--yes",
+            "--read",
+            "/prompts/prompts.md",
+            "--read",
+            "--message",
+            "read /prompts/prompts.md and ask for next instruction"
+        ]
+    );
+}
+
+#[test]
+fn test_runner_command_without_a_program_is_an_error() {
+    let cfg = RunnerConfig {
+        command: Some("{prompt_path}".to_string()),
+        args: Some(String::new()),
+        ..RunnerConfig::default()
+    };
+    let runner = Runner::from_config("bare", &cfg).unwrap();
+    assert!(runner.command(&invocation(Some("review.md"))).is_ok());
+    let err = runner.command(&invocation(None)).unwrap_err();
+    assert!(err.to_string().contains("runner 'bare'"));
+}
+
+#[test]
+fn test_runner_config_errors() {
+    assert!(Runner::from_config("x", &RunnerConfig::default()).is_err());
+    let cfg = RunnerConfig {
+        command: Some("agent 'oops".to_string()),
+        ..RunnerConfig::default()
+    };
+    assert!(Runner::from_config("x", &cfg).is_err());
+}
+
+#[test]
+fn test_resolve_runner() {
+    let mut cfg = GlobalConfig::default();
+    assert_eq!(resolve(&cfg, None).unwrap(), Runner::builtin());
+    assert_eq!(names(&cfg), vec!["claude"]);
+
+    cfg.runners.insert(
+        "stub".to_string(),
+        RunnerConfig {
+            command: Some("stub".to_string()),
+            ..RunnerConfig::default()
+        },
+    );
+    cfg.runner = Some("stub".to_string());
+    assert_eq!(resolve(&cfg, None).unwrap().name, "stub");
+    assert_eq!(resolve(&cfg, Some("claude")).unwrap(), Runner::builtin());
+    assert_eq!(names(&cfg), vec!["stub", "claude"]);
+
+    let err = resolve(&cfg, Some("codex")).unwrap_err();
+    assert_eq!(err, "unknown runner 'codex' (available: stub, claude)");
+}
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use axon::notes::PeriodicKind;
+use axon::search::{note_kind, search, Filter, Query, Source, SNIPPET_WIDTH};
+use tempfile::TempDir;
+
+#[test]
+fn test_query_literal_regex_and_case() {
+    let query = Query::new("a.b", false, false).unwrap();
+    assert_eq!(query.find("axb A.B a.b"), vec![4..7, 8..11]);
+
+    let query = Query::new("a.b", true, false).unwrap();
+    assert_eq!(query.find("axb").len(), 1);
+
+    // An uppercase letter makes the query case-sensitive.
+    let query = Query::new("Auth", false, false).unwrap();
+    assert_eq!(query.find("auth Auth"), vec![5..9]);
+    let query = Query::new("auth", false, true).unwrap();
+    assert_eq!(query.find("auth Auth"), vec![0..4]);
+
+    assert_eq!(
+        Query::new("(", true, false).unwrap_err(),
+        "invalid regex '(': unclosed group"
+    );
+    assert!(Query::new("", false, false).is_err());
+}
+
+#[test]
+fn test_filter_by_note_kind_and_prompt_fields() {
+    let kinds = PeriodicKind::builtin();
+    let daily = kinds[0].filename(chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+    assert_eq!(note_kind(&kinds, &daily), "daily");
+    assert_eq!(note_kind(&kinds, "scratch.1700000000.md"), "scratch");
+    assert_eq!(note_kind(&kinds, "ideas.md"), "other");
+
+    let filter = Filter {
+        kind: Some("daily".to_string()),
+        ..Filter::default()
+    };
+    assert!(filter.matches(Source::Note, &daily, &kinds));
+    assert!(!filter.matches(Source::Note, "ideas.md", &kinds));
+    assert!(!filter.matches(Source::Prompt, "myapp.sop.deploy.v1.md", &kinds));
+
+    let filter = Filter {
+        repo: Some("myapp".to_string()),
+        doc_type: Some("spec".to_string()),
+        ..Filter::default()
+    };
+    assert!(filter.matches(Source::Prompt, "myapp.feat.auth.spec.initial.v1.md", &kinds));
+    assert!(!filter.matches(Source::Prompt, "myapp.feat.auth.prompt.initial.v1.md", &kinds));
+    assert!(!filter.matches(Source::Prompt, "myapp.sop.deploy.v1.md", &kinds));
+    assert!(!filter.matches(Source::Note, "ideas.md", &kinds));
+}
+
+#[test]
+fn test_search_ranks_and_cuts_snippets() {
+    let tmp = TempDir::new().unwrap();
+    let write = |name: &str, content: &str| {
+        let path = tmp.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    };
+    let long = format!("{} deploy {}", "x".repeat(200), "y".repeat(200));
+    let files: Vec<(Source, PathBuf)> = vec![
+        (Source::Note, write("ideas.md", &format!("deploy\ndeploy again\n{long}\n"))),
+        (Source::Note, write("todo.md", "nothing here\n")),
+        (Source::Note, write("log.md", "deploy once\n")),
+        (Source::Prompt, write("myapp.sop.deploy.v1.md", "steps\n")),
+    ];
+
+    let query = Query::new("deploy", false, false).unwrap();
+    let results = search(&query, &files);
+    let names: Vec<&str> = results.iter().map(|result| result.file.as_str()).collect();
+    assert_eq!(names, ["myapp.sop.deploy.v1.md", "ideas.md", "log.md"]);
+    assert!(results[0].name_match);
+    assert!(results[0].hits.is_empty());
+
+    let hits = &results[1].hits;
+    assert_eq!(hits.iter().map(|hit| hit.line).collect::<Vec<_>>(), [1, 2, 3]);
+    let cut = &hits[2];
+    assert_eq!(cut.text.len(), SNIPPET_WIDTH);
+    assert_eq!(&cut.text[cut.matches[0].clone()], "deploy");
+}
@@ -0,0 +1,37 @@
+use axon::size::{measure_file, Size, Tokenizer};
+use tempfile::TempDir;
+
+#[test]
+fn test_size_measures_text() {
+    let size = Size::measure("# Title\n\nWrite the tests, then ship.\n", Tokenizer::Chars);
+    assert_eq!(
+        size,
+        Size {
+            bytes: 37,
+            words: 7,
+            lines: 3,
+            tokens: 10,
+        }
+    );
+    // 7 words -> 10 tokens, plus `#`, `,` and `.`.
+    assert_eq!(Tokenizer::Words.estimate("# Title\n\nWrite the tests, then ship.\n"), 13);
+    assert_eq!(Tokenizer::Chars.estimate(""), 0);
+    assert!(Tokenizer::parse("bpe").unwrap_err().contains("expected chars or words"));
+}
+
+#[test]
+fn test_measure_file_expands_includes() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("a.sop.style.v1.md"), "Use short sentences.\n").unwrap();
+    let path = tmp.path().join("a.feat.auth.spec.initial.v1.md");
+    std::fs::write(&path, "Build login.\n{{> a.sop.style}}").unwrap();
+
+    let size = measure_file(&path, Tokenizer::Chars).unwrap();
+    assert_eq!(size.size.words, 4);
+    let expanded = size.expanded.unwrap();
+    assert_eq!(expanded.words, 5);
+    assert_eq!(expanded.bytes, "Build login.\nUse short sentences.\n".len());
+
+    std::fs::write(&path, "{{> a.sop.missing}}").unwrap();
+    assert_eq!(measure_file(&path, Tokenizer::Chars).unwrap().expanded, None);
+}
@@ -0,0 +1,90 @@
+use axon::tasks::{insert_tasks, mark_carried, unchecked_tasks};
+
+const NOTE: &str = "# Daily
+
+## Tasks
+- [ ] write spec
+- [x] review PR
+  - [ ] nested follow-up
+
+## Log
+- [ ] not really a task
+```
+- [ ] inside code
+```
+";
+
+#[test]
+fn test_unchecked_tasks() {
+    let texts: Vec<String> = unchecked_tasks(NOTE, None)
+        .into_iter()
+        .map(|task| task.text)
+        .collect();
+    assert_eq!(
+        texts,
+        vec!["write spec", "nested follow-up", "not really a task"]
+    );
+
+    let tasks = unchecked_tasks(NOTE, Some("tasks"));
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].line, 3);
+    assert!(unchecked_tasks(NOTE, Some("Missing")).is_empty());
+}
+
+#[test]
+fn test_unchecked_tasks_keep_nesting_relative_to_their_parent() {
+    let note = "## Tasks\n- [ ] ship release\n    - [ ] write changelog\n      - [ ] list fixes\n    - [x] tag\n      - [ ] push tag\n- [ ] reply\n";
+    let tasks: Vec<(String, String)> = unchecked_tasks(note, Some("Tasks"))
+        .into_iter()
+        .map(|task| (task.indent, task.text))
+        .collect();
+    assert_eq!(
+        tasks,
+        vec![
+            (String::new(), "ship release".to_string()),
+            ("    ".to_string(), "write changelog".to_string()),
+            ("      ".to_string(), "list fixes".to_string()),
+            (String::new(), "push tag".to_string()),
+            (String::new(), "reply".to_string()),
+        ]
+    );
+
+    let nested = "- notes\n  - [ ] only the task is nested\n";
+    assert_eq!(unchecked_tasks(nested, None)[0].indent, "");
+}
+
+#[test]
+fn test_mark_carried() {
+    let lines: Vec<usize> = unchecked_tasks(NOTE, Some("Tasks"))
+        .iter()
+        .map(|task| task.line)
+        .collect();
+    let marked = mark_carried(NOTE, &lines);
+    assert!(marked.contains("- [>] write spec\n"));
+    assert!(marked.contains("  - [>] nested follow-up\n"));
+    assert!(marked.contains("- [ ] not really a task\n"));
+    assert!(marked.contains("- [x] review PR\n"));
+}
+
+#[test]
+fn test_insert_tasks() {
+    let tasks = vec!["write spec".to_string()];
+
+    let schema = "# Daily\n\n## Tasks\n- [ ]\n\n## Notes\n";
+    assert_eq!(
+        insert_tasks(schema, "Tasks", &tasks),
+        "# Daily\n\n## Tasks\n- [ ] write spec\n\n## Notes\n"
+    );
+
+    assert_eq!(
+        insert_tasks("# Daily\n", "Carried over", &tasks),
+        "# Daily\n\n## Carried over\n\n- [ ] write spec\n"
+    );
+    assert_eq!(insert_tasks("# Daily\n", "Carried over", &[]), "# Daily\n");
+
+    let nested = vec!["ship release".to_string(), "  write changelog".to_string()];
+    assert_eq!(
+        insert_tasks("## Tasks\n", "Tasks", &nested),
+        "## Tasks\n- [ ] ship release\n  - [ ] write changelog\n"
+    );
+}
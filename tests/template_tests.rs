@@ -0,0 +1,127 @@
+use axon::template::{render, variables, Context, Value, Variable};
+use chrono::NaiveDate;
+use std::fs;
+use tempfile::TempDir;
+
+fn context() -> Context<'static> {
+    let mut ctx = Context::new();
+    let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    ctx.set_value(
+        "date",
+        Value::Date {
+            date,
+            display: "2026.03.02".to_string(),
+        },
+    );
+    ctx.set("name", "axon");
+    ctx.set("empty", "");
+    ctx
+}
+
+#[test]
+fn test_render_variables_and_formats() {
+    let rendered = render("{{date}} / {{ date:%A, %B %d }} / {{name}}", &context()).unwrap();
+    assert_eq!(rendered.text, "2026.03.02 / Monday, March 02 / axon");
+    assert!(rendered.unknown.is_empty());
+
+    assert!(render("{{name:%A}}", &context()).is_err());
+}
+
+#[test]
+fn test_render_keeps_other_template_code() {
+    let source = "{{name}}\n```jinja\n{% for x in xs %}{{ x|upper }}{% endfor %}\n```\n```go\n{{ .Field }} {{ range .Items }}{{/* c */}}\n```\n{{date\n";
+    let rendered = render(source, &context()).unwrap();
+    assert_eq!(
+        rendered.text,
+        "axon\n```jinja\n{% for x in xs %}{{ x|upper }}{% endfor %}\n```\n```go\n{{ .Field }} {{ range .Items }}{{/* c */}}\n```\n{{date\n"
+    );
+    assert!(rendered.unknown.is_empty());
+    assert_eq!(render("{{ {{name}} }}", &context()).unwrap().text, "{{ axon }}");
+
+    // Jinja variables look like ours, so they are escaped.
+    let source = "\\{{ user.name }} is {{name}}, not \\{{name}}";
+    let rendered = render(source, &context()).unwrap();
+    assert_eq!(rendered.text, "{{ user.name }} is axon, not {{name}}");
+    assert!(variables(source, &context()).unwrap().iter().all(|var| var.name == "name"));
+}
+
+#[test]
+fn test_render_time_format_on_a_date_is_an_error() {
+    for format in ["%H:%M", "%S", "%p", "%Y %H"] {
+        let err = render(&format!("{{{{date:{format}}}}}"), &context()).unwrap_err();
+        assert!(err.contains(&format!("'{format}'")), "{err}");
+    }
+}
+
+#[test]
+fn test_render_reports_unknown_variables() {
+    let rendered = render("{{missing}} and {{missing}} {{other}}", &context()).unwrap();
+    assert_eq!(rendered.text, "{{missing}} and {{missing}} {{other}}");
+    assert_eq!(rendered.unknown, vec!["missing", "other"]);
+}
+
+#[test]
+fn test_render_environment_variables() {
+    let rendered = render("{{env.AXON_TEMPLATE_TEST_UNSET}}", &context()).unwrap();
+    assert_eq!(rendered.unknown, vec!["env.AXON_TEMPLATE_TEST_UNSET"]);
+
+    let home = std::env::var("HOME").unwrap_or_default();
+    let rendered = render("{{#if env.HOME}}{{env.HOME}}{{/if}}", &context()).unwrap();
+    assert_eq!(rendered.text, home);
+}
+
+#[test]
+fn test_render_conditionals() {
+    let source = "# Note\n{{#if name}}\nname: {{name}}\n{{else}}\nanonymous\n{{/if}}\n{{#if !empty}}\nempty\n{{/if}}\n{{#if missing}}{{missing}}{{/if}}end\n";
+    let rendered = render(source, &context()).unwrap();
+    assert_eq!(rendered.text, "# Note\nname: axon\nempty\nend\n");
+    assert!(rendered.unknown.is_empty());
+
+    assert!(render("{{#if name}}open", &context()).is_err());
+    assert!(render("{{/if}}", &context()).is_err());
+    assert!(render("{{#if name}}{{else}}{{else}}{{/if}}", &context()).is_err());
+}
+
+#[test]
+fn test_render_includes() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("header.md"), "# {{name}}").unwrap();
+    fs::write(tmp.path().join("footer.md"), "-- {{> sign.md}}\n").unwrap();
+    fs::write(tmp.path().join("sign.md"), "bye").unwrap();
+    fs::write(tmp.path().join("a.md"), "{{> b.md}}").unwrap();
+    fs::write(tmp.path().join("b.md"), "{{> a.md}}").unwrap();
+
+    let ctx = context().with_include_dir(tmp.path());
+    let rendered = render("{{> header.md}}\nbody\n{{> footer.md}}\n", &ctx).unwrap();
+    assert_eq!(rendered.text, "# axon\nbody\n-- bye\n");
+
+    let err = render("{{> a.md}}", &ctx).unwrap_err();
+    assert_eq!(err, "include cycle: a.md -> b.md -> a.md");
+    let ctx = context().with_include_dir(tmp.path()).with_root("a.md");
+    let err = render("{{> b.md}}", &ctx).unwrap_err();
+    assert_eq!(err, "include cycle: a.md -> b.md -> a.md");
+    let ctx = context().with_include_dir(tmp.path());
+    assert!(render("{{> nope.md}}", &ctx).is_err());
+    assert!(render("{{> header.md}}", &context()).is_err());
+}
+
+#[test]
+fn test_variables_lists_required_and_optional() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("footer.md"), "{{author}}\n").unwrap();
+    let ctx = Context::new().with_include_dir(tmp.path());
+    let source = "{{ticket}} {{env.HOME}}\n{{#if branch}}{{branch}}{{/if}}\n{{#if !draft}}{{ticket}}{{else}}{{draft}}{{/if}}\n{{> footer.md}}\n";
+    let variable = |name: &str, required| Variable {
+        name: name.to_string(),
+        required,
+    };
+    assert_eq!(
+        variables(source, &ctx).unwrap(),
+        vec![
+            variable("ticket", true),
+            variable("branch", false),
+            variable("draft", false),
+            variable("author", true),
+        ]
+    );
+}